@@ -0,0 +1,23 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Sets `ENVIOUS_BUILD_ID` to the time of the build, which is part of the
+/// key of every entry in the compilation cache. Cargo only runs the script
+/// again when the compiler changes, so the id changes with any change to the
+/// compiler or its dependencies, even one that does not change its version.
+fn main() {
+    for path in &[
+        "build.rs",
+        "src",
+        "../envyc",
+        "../standard_library",
+        "../Cargo.lock",
+    ] {
+        println!("cargo:rerun-if-changed={}", path);
+    }
+
+    let build_time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=ENVIOUS_BUILD_ID={}", build_time);
+}
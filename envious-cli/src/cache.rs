@@ -0,0 +1,148 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use envyc::lint::Lint;
+
+use crate::command::CompileOptions;
+
+/// The version of the compiler. It is part of every key so that the output
+/// of one version of the compiler is never reused by another.
+const COMPILER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The id of the build of the compiler, which is set by the build script.
+/// It is part of every key so that the output of a compiler that was changed
+/// without changing its version is never reused either.
+const BUILD_ID: &str = env!("ENVIOUS_BUILD_ID");
+
+/// Struct that stores the object file and the exported signatures of every
+/// file that was compiled. Each entry is keyed by a hash of everything that
+/// the object file depends on: the compiler build, the options, the path
+/// of the file and its source. Files only refer to each other through their
+/// own `extern` declarations, so the signatures of other files are already
+/// part of the source and a file never has to be recompiled because another
+/// file changed.
+pub struct Cache {
+    directory: PathBuf,
+}
+
+impl Cache {
+    /// Creates a cache that stores its entries in the given directory.
+    /// The directory is created when the first entry is stored.
+    ///
+    /// # Arguments
+    /// * `directory` - The directory of the cache.
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    /// Computes the key of the file with the given path and source.
+    ///
+    /// # Arguments
+    /// * `file_path` - The path of the file, which is embedded in panic messages.
+    /// * `source` - The source of the file.
+    /// * `options` - The options the file is compiled with.
+    pub fn key(file_path: &str, source: &str, options: &CompileOptions) -> String {
+        let mut hasher = Fnv1a::default();
+        hasher.write(COMPILER_VERSION.as_bytes());
+        hasher.write(BUILD_ID.as_bytes());
+        hasher.write(&[
+            options.position_independent as u8,
+            options.checked_arithmetic as u8,
+            options.debug_info as u8,
+        ]);
+        hasher.write(options.optimization_level.name().as_bytes());
+        if let Some(ref passes) = options.passes {
+            for pass in passes {
+                hasher.write(pass.name().as_bytes());
+            }
+        }
+
+        hasher.write(
+            options
+                .target_triple
+                .as_deref()
+                .unwrap_or_default()
+                .as_bytes(),
+        );
+        hasher.write(options.target_cpu.as_deref().unwrap_or_default().as_bytes());
+        for feature in options.target_features.iter() {
+            hasher.write(feature.as_bytes());
+        }
+
+        for lint in Lint::ALL.iter() {
+            hasher.write(lint.name().as_bytes());
+            hasher.write(format!("{:?}", options.lints.level(*lint)).as_bytes());
+        }
+
+        hasher.write(file_path.as_bytes());
+        hasher.write(source.as_bytes());
+        format!("{:016x}", hasher.finish())
+    }
+
+    /// Copies the cached object file with the given key to the output file
+    /// and returns the exported signatures of the file. Returns `None` if
+    /// there is no entry with the given key.
+    ///
+    /// # Arguments
+    /// * `key` - The key of the entry.
+    /// * `output_file` - The path to copy the object file to.
+    pub fn load(&self, key: &str, output_file: &Path) -> Option<Vec<String>> {
+        let signatures = fs::read_to_string(self.entry(key, "sig")).ok()?;
+        fs::copy(self.entry(key, "o"), output_file).ok()?;
+        Some(signatures.lines().map(String::from).collect())
+    }
+
+    /// Stores the object file and the exported signatures of a file under the given key.
+    ///
+    /// # Arguments
+    /// * `key` - The key of the entry.
+    /// * `output_file` - The path of the object file to store.
+    /// * `signatures` - The signatures of the functions defined in the file.
+    pub fn store(&self, key: &str, output_file: &Path, signatures: &[String]) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        fs::copy(output_file, self.entry(key, "o"))?;
+        // The signatures are written last, since an entry is only used when
+        // its signatures exist. This keeps an interrupted write from leaving
+        // behind an entry without its object file.
+        fs::write(self.entry(key, "sig"), signatures.join("\n"))
+    }
+
+    /// Gets the path of the file of an entry with the given extension.
+    ///
+    /// # Arguments
+    /// * `key` - The key of the entry.
+    /// * `extension` - The extension of the file.
+    fn entry(&self, key: &str, extension: &str) -> PathBuf {
+        self.directory.join(format!("{}.{}", key, extension))
+    }
+}
+
+/// The 64 bit FNV-1a hash. Unlike the hasher of the standard library,
+/// its output is guaranteed to stay the same across Rust versions.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    /// Adds the bytes to the hash, followed by a separator so that
+    /// consecutive values can not run into each other.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to add.
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes.iter().chain(std::iter::once(&0xff)) {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
@@ -1,27 +1,277 @@
-use std::{error::Error, path::PathBuf, time::Instant};
+use std::{
+    env,
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::Instant,
+};
 
 use clap::ArgMatches;
-use envyc::{error::reporter::ErrorReporter, interner::Interner};
+use envious_vm::{
+    compiler::compile,
+    disassembler::disassemble,
+    module::Module,
+    vm::{Exit, Vm},
+};
+use envyc::{
+    error::{reporter::ErrorFormat, source_map::FileId},
+    lint::{Lint, LintConfig, LintLevel},
+    optimization::{OptimizationLevel, Pass},
+    session::{Options, Session},
+    EmitKind,
+};
 
 use crate::{
-    compile_code,
-    utils::{clean_file, error, get_source, get_stem, path_to_str, replace_last},
+    cache::Cache,
+    check_code, compile_code, defines_main,
+    utils::{clean_file, error, get_source, get_stem, log_progress, path_to_str, OutputBuffer},
 };
 
+/// The directory, relative to the current directory, where compiled files are cached.
+const CACHE_DIRECTORY: &str = "target/envious-cache";
+
+/// The extension of the files written by `envious build --vm`.
+const BYTECODE_EXTENSION: &str = "envyb";
+
+/// The extension of the files written by `envious build --backend=c`.
+const C_EXTENSION: &str = "c";
+
+/// The name and the source of each file of the standard library, which
+/// is compiled along with the files that are run with the JIT or the interpreter.
+/// A program can define functions with the same names as the standard library,
+/// which are then used instead of the functions of the standard library.
+const STANDARD_LIBRARY: [(&str, &str); 1] = [(
+    "std/int.envy",
+    include_str!("../../standard_library/int.envy"),
+)];
+
 #[derive(Debug)]
 pub enum Command {
     Tui,
-    Compile { files: Vec<PathBuf> },
-    Build { files: Vec<PathBuf> },
-    Run { files: Vec<PathBuf> },
+    Compile {
+        files: Vec<PathBuf>,
+        options: CompileOptions,
+    },
+    Build {
+        files: Vec<PathBuf>,
+        library: Option<LibraryKind>,
+        vm: bool,
+        backend: Backend,
+        options: CompileOptions,
+    },
+    Run {
+        files: Vec<PathBuf>,
+        arguments: Vec<String>,
+        output: Option<PathBuf>,
+        jit: bool,
+        interp: bool,
+        vm: bool,
+        options: CompileOptions,
+    },
+    Disasm {
+        files: Vec<PathBuf>,
+        options: CompileOptions,
+    },
+    Repl {
+        options: CompileOptions,
+    },
+    PrintTargets,
+    Explain {
+        code: String,
+    },
     Unknown,
 }
 
+/// The kind of library produced by `envious build --lib`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LibraryKind {
+    /// A `.a` archive of the object files.
+    Static,
+    /// A `.so` shared object built from position independent object files.
+    Shared,
+}
+
+/// The backend that generates the executable built by `envious build`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backend {
+    /// Object files generated by LLVM, which are linked with g++.
+    Llvm,
+    /// A C99 source file, which is compiled with the system C compiler.
+    C,
+}
+
+/// The options that change how each file is compiled.
+#[derive(Debug, Clone)]
+pub struct CompileOptions {
+    /// Whether the generated code should be position independent.
+    pub position_independent: bool,
+    /// Whether the files are built into a library, so that none of their
+    /// functions are reported as unused.
+    pub library: bool,
+    /// Whether integer arithmetic traps on overflow and division by zero.
+    pub checked_arithmetic: bool,
+    /// Whether DWARF debug information is generated for debuggers.
+    pub debug_info: bool,
+    /// The level of each lint.
+    pub lints: LintConfig,
+    /// The format that the errors are reported in.
+    pub error_format: ErrorFormat,
+    /// Whether a report of the time spent on each file is printed.
+    pub timings: bool,
+    /// The number of files that are compiled at the same time.
+    pub jobs: usize,
+    /// The kinds of output written for each file.
+    pub emit: Vec<EmitKind>,
+    /// The directory that the output is written to, or `None` to write
+    /// the output of each file next to the file.
+    pub output_directory: Option<PathBuf>,
+    /// How much the generated code is optimized.
+    pub optimization_level: OptimizationLevel,
+    /// The passes to run instead of the pipeline of the optimization level.
+    pub passes: Option<Vec<Pass>>,
+    /// Whether the LLVM IR is printed after each optimization pass.
+    pub print_after_all: bool,
+    /// Whether the partially built module is written when LLVM fails to verify a function.
+    pub emit_ir_on_ice: bool,
+    /// The triple of the target to compile for, or `None` to compile for the host.
+    pub target_triple: Option<String>,
+    /// The CPU of the target, or `None` for a generic CPU.
+    pub target_cpu: Option<String>,
+    /// The features of the target to enable or disable, such as `+avx2`.
+    pub target_features: Vec<String>,
+}
+
+impl CompileOptions {
+    /// Creates the options from the matches of a subcommand.
+    /// Checked arithmetic is enabled and optimizations are disabled by default
+    /// unless the `--release` flag is given, and files are compiled on as many
+    /// threads as the machine can run in parallel unless the `-j` flag is given.
+    ///
+    /// # Arguments
+    /// * `matches` - The matches of the subcommand.
+    fn from_matches(matches: &ArgMatches) -> Self {
+        let checked_arithmetic = match matches.value_of("checked-arithmetic") {
+            Some("on") => true,
+            Some("off") => false,
+            _ => !matches.is_present("release"),
+        };
+
+        let optimization_level = matches
+            .value_of("optimization-level")
+            .and_then(OptimizationLevel::from_name)
+            .unwrap_or(if matches.is_present("release") {
+                OptimizationLevel::O2
+            } else {
+                OptimizationLevel::O0
+            });
+
+        Self {
+            position_independent: false,
+            library: false,
+            checked_arithmetic,
+            debug_info: matches.is_present("debug-info"),
+            lints: get_lint_config(matches),
+            error_format: match matches.value_of("error-format") {
+                Some("json") => ErrorFormat::Json,
+                _ => ErrorFormat::Human,
+            },
+            timings: matches.is_present("timings"),
+            jobs: matches
+                .value_of("jobs")
+                .and_then(|jobs| jobs.parse().ok())
+                .unwrap_or_else(|| thread::available_parallelism().map_or(1, |jobs| jobs.get())),
+            emit: matches.values_of("emit").map_or_else(
+                || vec![EmitKind::Object],
+                |names| names.filter_map(EmitKind::from_name).collect(),
+            ),
+            output_directory: matches.value_of("out-dir").map(PathBuf::from),
+            optimization_level,
+            passes: matches
+                .values_of("passes")
+                .map(|names| names.filter_map(Pass::from_name).collect()),
+            print_after_all: matches.is_present("print-after-all"),
+            emit_ir_on_ice: matches.is_present("emit-ir-on-ice"),
+            target_triple: matches.value_of("target").map(String::from),
+            target_cpu: matches.value_of("target-cpu").map(String::from),
+            target_features: matches
+                .values_of("target-feature")
+                .map_or_else(Vec::new, |features| features.map(String::from).collect()),
+        }
+    }
+
+    /// Creates the options of the `Session` that compiles each file.
+    pub fn session_options(&self) -> Options {
+        Options {
+            position_independent: self.position_independent,
+            library: self.library,
+            checked_arithmetic: self.checked_arithmetic,
+            step_limit: None,
+            debug_info: self.debug_info,
+            lints: self.lints.clone(),
+            error_format: self.error_format,
+            emit: self.emit.clone(),
+            optimization_level: self.optimization_level,
+            passes: self.passes.clone(),
+            print_after_all: self.print_after_all,
+            emit_ir_on_ice: self.emit_ir_on_ice,
+            target_triple: self.target_triple.clone(),
+            target_cpu: self.target_cpu.clone(),
+            target_features: self.target_features.clone(),
+        }
+    }
+
+    /// Determines whether compiled files can be stored in and loaded from the
+    /// cache. The cache only stores object files, so it is not used when any
+    /// other kind of output is requested or when the IR should be printed.
+    fn is_cacheable(&self) -> bool {
+        self.emit == [EmitKind::Object] && !self.print_after_all
+    }
+}
+
+/// Creates the lint configuration from the `-A`, `-W` and `-D` flags.
+/// The flags are applied in the order they were given, so later flags
+/// take precedence. The name `warnings` refers to every lint.
+///
+/// # Arguments
+/// * `matches` - The matches of the subcommand.
+fn get_lint_config(matches: &ArgMatches) -> LintConfig {
+    let mut flags = vec![];
+    for &(name, level) in &[
+        ("allow", LintLevel::Allow),
+        ("warn", LintLevel::Warn),
+        ("deny", LintLevel::Deny),
+    ] {
+        if let (Some(indices), Some(values)) = (matches.indices_of(name), matches.values_of(name)) {
+            flags.extend(
+                indices
+                    .zip(values)
+                    .map(|(index, lint_name)| (index, lint_name, level)),
+            );
+        }
+    }
+
+    flags.sort_by_key(|(index, _, _)| *index);
+    let mut config = LintConfig::default();
+    for (_, lint_name, level) in flags {
+        match Lint::from_name(lint_name) {
+            Some(lint) => config.set(lint, level),
+            None => config.set_all(level),
+        }
+    }
+
+    config
+}
+
 impl From<ArgMatches<'_>> for Command {
     fn from(matches: ArgMatches) -> Self {
         let start_tui = matches.is_present("tui");
         if start_tui {
             Self::Tui
+        } else if matches.value_of("print") == Some("targets") {
+            Self::PrintTargets
         } else if let Some(compile_matches) = matches.subcommand_matches("compile") {
             let files = compile_matches.values_of("files").unwrap();
             let mut file_paths = vec![];
@@ -29,7 +279,10 @@ impl From<ArgMatches<'_>> for Command {
                 file_paths.push(PathBuf::from(file));
             }
 
-            Self::Compile { files: file_paths }
+            Self::Compile {
+                files: file_paths,
+                options: CompileOptions::from_matches(compile_matches),
+            }
         } else if let Some(compile_matches) = matches.subcommand_matches("build") {
             let files = compile_matches.values_of("files").unwrap();
             let mut file_paths = vec![];
@@ -37,7 +290,22 @@ impl From<ArgMatches<'_>> for Command {
                 file_paths.push(PathBuf::from(file));
             }
 
-            Self::Build { files: file_paths }
+            let library = match compile_matches.value_of("lib") {
+                Some("static") => Some(LibraryKind::Static),
+                Some("shared") => Some(LibraryKind::Shared),
+                _ => None,
+            };
+
+            Self::Build {
+                files: file_paths,
+                library,
+                vm: compile_matches.is_present("vm"),
+                backend: match compile_matches.value_of("backend") {
+                    Some("c") => Backend::C,
+                    _ => Backend::Llvm,
+                },
+                options: CompileOptions::from_matches(compile_matches),
+            }
         } else if let Some(compile_matches) = matches.subcommand_matches("run") {
             let files = compile_matches.values_of("files").unwrap();
             let mut file_paths = vec![];
@@ -45,7 +313,40 @@ impl From<ArgMatches<'_>> for Command {
                 file_paths.push(PathBuf::from(file));
             }
 
-            Self::Run { files: file_paths }
+            let arguments = compile_matches
+                .values_of("arguments")
+                .map_or_else(Vec::new, |arguments| arguments.map(String::from).collect());
+
+            let output = compile_matches.value_of("output").map(PathBuf::from);
+
+            Self::Run {
+                files: file_paths,
+                arguments,
+                output,
+                jit: compile_matches.is_present("jit"),
+                interp: compile_matches.is_present("interp"),
+                vm: compile_matches.is_present("vm"),
+                options: CompileOptions::from_matches(compile_matches),
+            }
+        } else if let Some(disasm_matches) = matches.subcommand_matches("disasm") {
+            let files = disasm_matches.values_of("files").unwrap();
+            let mut file_paths = vec![];
+            for file in files {
+                file_paths.push(PathBuf::from(file));
+            }
+
+            Self::Disasm {
+                files: file_paths,
+                options: CompileOptions::from_matches(disasm_matches),
+            }
+        } else if let Some(repl_matches) = matches.subcommand_matches("repl") {
+            Self::Repl {
+                options: CompileOptions::from_matches(repl_matches),
+            }
+        } else if let Some(explain_matches) = matches.subcommand_matches("explain") {
+            Self::Explain {
+                code: explain_matches.value_of("code").unwrap().to_string(),
+            }
         } else {
             Self::Unknown
         }
@@ -54,55 +355,550 @@ impl From<ArgMatches<'_>> for Command {
 
 pub fn compile_command(
     files: Vec<PathBuf>,
+    options: &CompileOptions,
 ) -> Result<(Vec<PathBuf>, Option<PathBuf>), Box<dyn Error>> {
-    let mut error_reporter = ErrorReporter::new(vec![]);
-    let mut interner = Interner::default();
     let mut clean_files = vec![];
-    let mut sources = vec![];
     for file in files {
-        let file = clean_file(file)?;
-        let source = get_source(&file)?;
-        clean_files.push(file);
-        sources.push(source);
+        clean_files.push(clean_file(file)?);
+    }
+
+    if let Some(ref output_directory) = options.output_directory {
+        fs::create_dir_all(output_directory)?;
     }
 
+    let mut jobs = vec![];
+    for file in clean_files.iter() {
+        let output_directory = match options.output_directory {
+            Some(ref output_directory) => output_directory.clone(),
+            None => file
+                .parent()
+                .ok_or_else(|| error("Could not find a parent for this file"))?
+                .to_path_buf(),
+        };
+
+        jobs.push(CompileJob {
+            file_path: path_to_str(file)?,
+            file_stem: get_stem(file)?,
+            source: get_source(file)?,
+            output_directory,
+        });
+    }
+
+    let cache = Cache::new(PathBuf::from(CACHE_DIRECTORY));
+    let results = compile_jobs(&jobs, &cache, options);
+
+    let mut timings = vec![];
     let mut main_file = None;
-    for (file, source) in clean_files.iter().zip(sources.iter()) {
-        error_reporter.add(path_to_str(file)?, source);
-        let file_stem = get_stem(file)?;
-        let file_path = path_to_str(file)?;
-        let output_file = replace_last(file, format!("{}.o", file_stem))?;
-        let output_file_path = path_to_str(&output_file)?;
-        let bytes = source.as_bytes();
-        let compilation_start = Instant::now();
-        let result = compile_code(
-            &error_reporter,
-            &mut interner,
-            file_stem,
-            file_path,
-            output_file_path,
-            bytes,
-        );
+    for ((job, result), file) in jobs.iter().zip(results).zip(clean_files.iter()) {
+        result.output.print(options.error_format);
+        let signatures = match result.signatures {
+            Some(signatures) => signatures,
+            None => {
+                log_progress(
+                    options.error_format,
+                    &format!("Failed to compile file `{}`.", job.file_path),
+                );
+                return Ok((clean_files, main_file));
+            }
+        };
 
-        if let Some(found_main) = result {
+        if defines_main(&signatures) {
             match main_file {
-                Some(_) if found_main => return Err(error("Found multiple main methods.")),
-                None if found_main => main_file = Some(file.clone()),
-                _ => {}
+                Some(_) => return Err(error("Found multiple main methods.")),
+                None => main_file = Some(file.clone()),
             }
         }
 
-        if result.is_none() {
-            println!("Failed to compile file `{}`.", file_path);
-            return Ok((clean_files, main_file));
+        let message = if result.cache_hit {
+            format!(
+                "Reused the cached compilation of file `{}` after {} seconds.",
+                job.file_path, result.seconds
+            )
         } else {
-            println!(
+            format!(
                 "Finished full compilation process for file `{}` after {} seconds.",
-                file_path,
-                compilation_start.elapsed().as_secs_f64()
-            );
-        }
+                job.file_path, result.seconds
+            )
+        };
+
+        log_progress(options.error_format, &message);
+        timings.push((job.file_path, result.cache_hit, result.seconds));
+    }
+
+    if options.timings {
+        report_timings(&timings, options.error_format);
     }
 
     Ok((clean_files, main_file))
 }
+
+/// A file that is waiting to be compiled.
+struct CompileJob<'a> {
+    /// The path of the file.
+    file_path: &'a str,
+    /// The name of the file without its extension.
+    file_stem: &'a str,
+    /// The source of the file.
+    source: String,
+    /// The directory that the output of the file is written to.
+    output_directory: PathBuf,
+}
+
+impl CompileJob<'_> {
+    /// Gets the path that the given kind of output of the file is written to.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of output.
+    fn output_file(&self, kind: EmitKind) -> PathBuf {
+        self.output_directory
+            .join(format!("{}.{}", self.file_stem, kind.extension()))
+    }
+}
+
+/// The result of compiling a single file on one of the workers.
+struct CompileResult {
+    /// The signatures of the functions defined in the file, or `None` if the file failed to compile.
+    signatures: Option<Vec<String>>,
+    /// Whether the file was reused from the cache.
+    cache_hit: bool,
+    /// The seconds spent on the file.
+    seconds: f64,
+    /// The messages printed while compiling the file.
+    output: OutputBuffer,
+}
+
+/// Compiles the files on `options.jobs` threads and returns the result of
+/// each file in the order the files were given. Every file is compiled in
+/// its own `Session`, since files only refer to each other through their
+/// `extern` declarations. Once a file fails, the files after
+/// it are not started, and only the results up to and including the first
+/// failure are returned.
+///
+/// # Arguments
+/// * `jobs` - The files to compile.
+/// * `cache` - The cache that compiled files are loaded from and stored in.
+/// * `options` - The options the files are compiled with.
+fn compile_jobs(
+    jobs: &[CompileJob],
+    cache: &Cache,
+    options: &CompileOptions,
+) -> Vec<CompileResult> {
+    let next_job = AtomicUsize::new(0);
+    let first_failure = AtomicUsize::new(usize::MAX);
+    let mut results = thread::scope(|scope| {
+        let workers = (0..options.jobs.min(jobs.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = vec![];
+                    loop {
+                        let index = next_job.fetch_add(1, Ordering::SeqCst);
+                        if index >= jobs.len() || index > first_failure.load(Ordering::SeqCst) {
+                            break results;
+                        }
+
+                        let result = compile_job(&jobs[index], cache, options);
+                        if result.signatures.is_none() {
+                            first_failure.fetch_min(index, Ordering::SeqCst);
+                        }
+
+                        results.push((index, result));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    let first_failure = first_failure.into_inner();
+    results
+        .into_iter()
+        .take_while(|(index, _)| *index <= first_failure)
+        .map(|(_, result)| result)
+        .collect()
+}
+
+/// Runs the files with LLVM's JIT and returns the exit code of the program.
+/// Nothing is written to disk, and the standard library is compiled from
+/// its source instead of being linked from `~/.envious/std/std.o`.
+///
+/// # Arguments
+/// * `files` - The files to run.
+/// * `arguments` - The arguments passed to the program.
+/// * `options` - The options the files are compiled with.
+pub fn jit_command(
+    files: Vec<PathBuf>,
+    arguments: &[String],
+    options: &CompileOptions,
+) -> Result<i32, Box<dyn Error>> {
+    let (mut session, file_ids) = load_program(files, options)?;
+    let program_name = session.source_map().name(file_ids[0]).to_string();
+    let mut program_arguments = vec![program_name.as_str()];
+    program_arguments.extend(arguments.iter().map(String::as_str));
+    let exit_code = session.run_jit(&file_ids, &program_arguments);
+    let mut output = OutputBuffer::default();
+    session.report(true, &mut output)?;
+    output.print(options.error_format);
+    exit_code.ok_or_else(|| error("Failed to run the program in the JIT."))
+}
+
+/// Runs the files with the tree-walking interpreter and returns the exit code
+/// of the program. Nothing is compiled with LLVM, so this works on any machine,
+/// but only the C functions known to the interpreter can be called.
+///
+/// # Arguments
+/// * `files` - The files to run.
+/// * `arguments` - The arguments passed to the program.
+/// * `options` - The options the files are checked with.
+pub fn interp_command(
+    files: Vec<PathBuf>,
+    arguments: &[String],
+    options: &CompileOptions,
+) -> Result<i32, Box<dyn Error>> {
+    let (mut session, file_ids) = load_program(files, options)?;
+    let program_name = session.source_map().name(file_ids[0]).to_string();
+    let mut program_arguments = vec![program_name.as_str()];
+    program_arguments.extend(arguments.iter().map(String::as_str));
+    let exit_code = session.interpret(
+        &file_ids,
+        &program_arguments,
+        &mut io::stdout(),
+        &mut io::stderr(),
+    );
+    let mut output = OutputBuffer::default();
+    session.report(true, &mut output)?;
+    output.print(options.error_format);
+    exit_code.ok_or_else(|| error("Failed to run the program in the interpreter."))
+}
+
+/// Runs the files with the bytecode VM and returns the exit code of the
+/// program. The files are either compiled to bytecode along with the
+/// standard library, or are a single `.envyb` file built by `envious build --vm`.
+///
+/// # Arguments
+/// * `files` - The files to run.
+/// * `arguments` - The arguments passed to the program.
+/// * `options` - The options the files are compiled with.
+pub fn vm_command(
+    files: Vec<PathBuf>,
+    arguments: &[String],
+    options: &CompileOptions,
+) -> Result<i32, Box<dyn Error>> {
+    let (module, program_name) = load_module(files, options)?;
+    let mut program_arguments = vec![program_name.as_str()];
+    program_arguments.extend(arguments.iter().map(String::as_str));
+    let mut stdout = io::stdout();
+    match Vm::new(&module, &mut stdout).run_main(&program_arguments) {
+        Ok(exit) => {
+            if let Exit::Panic(ref panic) = exit {
+                eprintln!("{}", panic.render(&module));
+            }
+
+            Ok(exit.code())
+        }
+        Err(errors) => {
+            // The errors of a `Vm` do not refer to any file, so they are
+            // reported by a `Session` without any files.
+            let mut session = Session::new(options.session_options());
+            session.add_errors(errors);
+            let mut output = OutputBuffer::default();
+            session.report(true, &mut output)?;
+            output.print(options.error_format);
+            Err(error("Failed to run the program in the VM."))
+        }
+    }
+}
+
+/// Compiles the files and the standard library to bytecode and writes it
+/// to a `.envyb` file next to the first file, which is returned.
+///
+/// # Arguments
+/// * `files` - The files to compile.
+/// * `options` - The options the files are compiled with.
+pub fn build_bytecode_command(
+    files: Vec<PathBuf>,
+    options: &CompileOptions,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let first_file = clean_file(files[0].clone())?;
+    let output_file = first_file.with_extension(BYTECODE_EXTENSION);
+    if is_bytecode_file(&first_file) {
+        return Err(error("The files have already been compiled to bytecode."));
+    }
+
+    let (module, _) = load_module(files, options)?;
+    fs::write(&output_file, module.encode())?;
+    log_progress(
+        options.error_format,
+        &format!("Wrote {}", output_file.display()),
+    );
+    Ok(output_file)
+}
+
+/// Lowers the files and the standard library to a `.c` file next to the
+/// first file, and compiles it into an executable named after the first
+/// file with the system C compiler, which is `cc` unless the `CC`
+/// environment variable names another one. Returns the path of the executable.
+///
+/// # Arguments
+/// * `files` - The files to build.
+/// * `options` - The options the files are compiled with.
+pub fn build_c_command(
+    files: Vec<PathBuf>,
+    options: &CompileOptions,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let first_file = clean_file(files[0].clone())?;
+    let c_file = first_file.with_extension(C_EXTENSION);
+    let executable = first_file.with_extension("");
+    let (mut session, file_ids) = load_program(files, options)?;
+    let source = session.generate_c(&file_ids);
+    let mut output = OutputBuffer::default();
+    session.report(true, &mut output)?;
+    output.print(options.error_format);
+    let source = source.ok_or_else(|| error("Failed to lower the program to C."))?;
+    fs::write(&c_file, source)?;
+    log_progress(options.error_format, &format!("Wrote {}", c_file.display()));
+
+    let compiler = env::var("CC").unwrap_or_else(|_| String::from("cc"));
+    let optimization_level = match options.optimization_level {
+        // Older versions of GCC do not support `-Oz`.
+        OptimizationLevel::Oz => OptimizationLevel::Os,
+        level => level,
+    };
+    let mut command = process::Command::new(&compiler);
+    // The `extern` declarations are written with the types of Envy, so the
+    // compiler should not compare them with the functions it knows about.
+    command
+        .arg("-std=c99")
+        .arg("-fno-builtin")
+        .arg(format!("-O{}", optimization_level.name()));
+    if options.debug_info {
+        command.arg("-g");
+    }
+
+    let result = command
+        .arg(&c_file)
+        .arg("-o")
+        .arg(&executable)
+        .arg("-lm")
+        .output()
+        .map_err(|_| error(format!("Could not run the C compiler `{}`.", compiler)))?;
+    if !result.status.success() {
+        eprint!("{}", String::from_utf8_lossy(&result.stderr));
+        return Err(error(format!(
+            "Failed to compile `{}` with `{}`.",
+            c_file.display(),
+            compiler
+        )));
+    }
+
+    log_progress(
+        options.error_format,
+        &format!("Wrote {}", executable.display()),
+    );
+    Ok(executable)
+}
+
+/// Prints the bytecode of the files, which are either compiled to bytecode
+/// along with the standard library or are a single `.envyb` file.
+///
+/// # Arguments
+/// * `files` - The files to disassemble.
+/// * `options` - The options the files are compiled with.
+pub fn disasm_command(files: Vec<PathBuf>, options: &CompileOptions) -> Result<(), Box<dyn Error>> {
+    let (module, _) = load_module(files, options)?;
+    print!("{}", disassemble(&module));
+    Ok(())
+}
+
+/// Gets the bytecode of the files and the name of the program. A `.envyb`
+/// file is read as it is, while source files are checked and compiled
+/// along with the standard library.
+///
+/// # Arguments
+/// * `files` - The files of the program.
+/// * `options` - The options the files are compiled with.
+fn load_module(
+    files: Vec<PathBuf>,
+    options: &CompileOptions,
+) -> Result<(Module, String), Box<dyn Error>> {
+    if files.iter().any(|file| is_bytecode_file(file)) {
+        if files.len() != 1 {
+            return Err(error("A `.envyb` file must be given on its own."));
+        }
+
+        let file = clean_file(files[0].clone())?;
+        let module = Module::decode(&fs::read(&file)?).map_err(|decode_error| {
+            error(format!(
+                "Could not read the file `{}`: {}.",
+                file.display(),
+                decode_error
+            ))
+        })?;
+        return Ok((module, path_to_str(&file)?.to_string()));
+    }
+
+    let (mut session, file_ids) = load_program(files, options)?;
+    let program_name = session.source_map().name(file_ids[0]).to_string();
+    let is_linked = session.link(&file_ids).is_some();
+    let result = match session.typed_programs(&file_ids) {
+        Some(programs) if is_linked => compile(
+            &programs,
+            session.interner(),
+            session.source_map(),
+            options.checked_arithmetic,
+        )
+        .map(Some),
+        _ => Ok(None),
+    };
+
+    let module = match result {
+        Ok(module) => module,
+        Err(errors) => {
+            session.add_errors(errors);
+            None
+        }
+    };
+
+    let mut output = OutputBuffer::default();
+    session.report(true, &mut output)?;
+    output.print(options.error_format);
+    module
+        .map(|module| (module, program_name))
+        .ok_or_else(|| error("Failed to compile the program to bytecode."))
+}
+
+/// Determines whether the file contains bytecode instead of source.
+///
+/// # Arguments
+/// * `file` - The file.
+fn is_bytecode_file(file: &Path) -> bool {
+    file.extension() == Some(BYTECODE_EXTENSION.as_ref())
+}
+
+/// Adds the files and the source of the standard library to a new `Session`,
+/// checking each file and reporting its errors. The ids of the files of the
+/// program come before the ids of the standard library.
+///
+/// # Arguments
+/// * `files` - The files of the program.
+/// * `options` - The options the files are compiled with.
+fn load_program(
+    files: Vec<PathBuf>,
+    options: &CompileOptions,
+) -> Result<(Session, Vec<FileId>), Box<dyn Error>> {
+    let mut session = Session::new(options.session_options());
+    let mut file_ids = vec![];
+    for file in files {
+        let file = clean_file(file)?;
+        let file_id = session.add_file(path_to_str(&file)?, get_source(&file)?);
+        let mut output = OutputBuffer::default();
+        let checked = check_code(&mut session, file_id, &mut output);
+        output.print(options.error_format);
+        if checked.is_none() {
+            return Err(error(format!(
+                "Failed to compile file `{}`.",
+                session.source_map().name(file_id)
+            )));
+        }
+
+        file_ids.push(file_id);
+    }
+
+    for (name, source) in STANDARD_LIBRARY.iter() {
+        file_ids.push(session.add_library_file("std", *name, *source));
+    }
+
+    Ok((session, file_ids))
+}
+
+/// Compiles a single file, or reuses its compilation from the cache.
+///
+/// # Arguments
+/// * `job` - The file to compile.
+/// * `cache` - The cache that compiled files are loaded from and stored in.
+/// * `options` - The options the file is compiled with.
+fn compile_job(job: &CompileJob, cache: &Cache, options: &CompileOptions) -> CompileResult {
+    let mut output = OutputBuffer::default();
+    let compilation_start = Instant::now();
+    let key = Cache::key(job.file_path, &job.source, options);
+    let object_file = job.output_file(EmitKind::Object);
+    let cached_signatures = if options.is_cacheable() {
+        cache.load(&key, &object_file)
+    } else {
+        None
+    };
+
+    let (signatures, cache_hit) = if let Some(signatures) = cached_signatures {
+        (Some(signatures), true)
+    } else {
+        let mut session = Session::new(options.session_options());
+        let file_id = session.add_file(job.file_path, job.source.as_str());
+        let compiled_file = compile_code(
+            &mut session,
+            file_id,
+            |kind| job.output_file(kind),
+            &mut output,
+        );
+
+        // Files with warnings are not cached, so that their
+        // warnings are reported every time they are built.
+        if let Some(compiled_file) = &compiled_file {
+            if options.is_cacheable() && !compiled_file.has_warnings {
+                if let Err(cache_error) = cache.store(&key, &object_file, &compiled_file.signatures)
+                {
+                    output.progress(format!(
+                        "Could not cache file `{}`: {}",
+                        job.file_path, cache_error
+                    ));
+                }
+            }
+        }
+
+        (
+            compiled_file.map(|compiled_file| compiled_file.signatures),
+            false,
+        )
+    };
+
+    CompileResult {
+        signatures,
+        cache_hit,
+        seconds: compilation_start.elapsed().as_secs_f64(),
+        output,
+    }
+}
+
+/// Prints the time spent on each file and whether it was reused from the cache.
+///
+/// # Arguments
+/// * `timings` - The path of each file, whether it was a cache hit and the seconds spent on it.
+/// * `error_format` - The format that the errors are reported in.
+fn report_timings(timings: &[(&str, bool, f64)], error_format: ErrorFormat) {
+    log_progress(error_format, "Timings:");
+    for (file_path, cache_hit, seconds) in timings {
+        let status = if *cache_hit { "cache hit" } else { "compiled" };
+        log_progress(
+            error_format,
+            &format!("  {:<9} {:>10.6}s  {}", status, seconds, file_path),
+        );
+    }
+
+    let cache_hits = timings
+        .iter()
+        .filter(|(_, cache_hit, _)| *cache_hit)
+        .count();
+    let total_seconds = timings.iter().map(|(_, _, seconds)| seconds).sum::<f64>();
+    log_progress(
+        error_format,
+        &format!(
+            "{} of {} files were reused from the cache, {:.6}s in total.",
+            cache_hits,
+            timings.len(),
+            total_seconds
+        ),
+    );
+}
@@ -1,7 +1,8 @@
 use std::{
     error::Error,
+    fs::{self, File},
     path::{Path, PathBuf},
-    process,
+    process::{self, ExitStatus, Stdio},
     time::Instant,
 };
 
@@ -9,27 +10,44 @@ use clap::{App, AppSettings, Arg, SubCommand};
 use command::Command;
 use envious_tui::run_tui;
 use envyc::{
-    compile,
-    environment::Environment,
-    error::reporter::{ErrorReporter, Reporter},
-    filter_tokens,
-    function_table::FunctionTable,
-    interner::Interner,
-    lex, parse,
-    semantic_analyzer::types::Type,
-    type_check, Config,
+    available_targets,
+    error::{explanations::explain, FileId},
+    lint::Lint,
+    optimization::{OptimizationLevel, Pass},
+    session::Session,
+    EmitKind,
 };
 use home::home_dir;
 
 use crate::{
-    command::compile_command,
-    utils::{error, get_stem, path_to_str, replace_last},
+    command::{
+        build_bytecode_command, build_c_command, compile_command, disasm_command, interp_command,
+        jit_command, vm_command, Backend, LibraryKind,
+    },
+    repl::run_repl,
+    utils::{error, get_stem, path_to_str, replace_last, OutputBuffer},
 };
 
+pub mod cache;
 pub mod command;
+pub mod repl;
 pub mod utils;
 
 pub fn main() -> Result<(), Box<dyn Error>> {
+    let lint_names = Lint::ALL
+        .iter()
+        .map(|lint| lint.name())
+        .chain(std::iter::once("warnings"))
+        .collect::<Vec<_>>();
+    let optimization_level_names = OptimizationLevel::ALL
+        .iter()
+        .map(|level| level.name())
+        .collect::<Vec<_>>();
+    let pass_names = Pass::ALL.iter().map(|pass| pass.name()).collect::<Vec<_>>();
+    let emit_names = EmitKind::ALL
+        .iter()
+        .map(|kind| kind.name())
+        .collect::<Vec<_>>();
     let matches = App::new("envious")
         .version("0.0.1")
         .author("Revanth Pothukuchi <revanthpothukuchi123@gmail.com>")
@@ -40,6 +58,13 @@ pub fn main() -> Result<(), Box<dyn Error>> {
                 .long("tui")
                 .help("Starts the terminal editor"),
         )
+        .arg(
+            Arg::with_name("print")
+                .long("print")
+                .takes_value(true)
+                .possible_values(&["targets"])
+                .help("Prints information about this build of the compiler"),
+        )
         .subcommand(
             SubCommand::with_name("compile")
                 .about("Compiles the files without linking them")
@@ -51,7 +76,39 @@ pub fn main() -> Result<(), Box<dyn Error>> {
                         .value_delimiter(";")
                         .required(true)
                         .help("The files to compile"),
-                ),
+                )
+                .arg(
+                    Arg::with_name("emit")
+                        .long("emit")
+                        .takes_value(true)
+                        .use_delimiter(true)
+                        .min_values(1)
+                        .possible_values(&emit_names)
+                        .help("The kinds of output to write for each file, an object file by default"),
+                )
+                .arg(
+                    Arg::with_name("out-dir")
+                        .short("o")
+                        .long("out-dir")
+                        .takes_value(true)
+                        .help("Writes the output into the given directory instead of next to each file"),
+                )
+                .arg(
+                    Arg::with_name("target")
+                        .long("target")
+                        .takes_value(true)
+                        .help("The triple of the target to compile for, such as aarch64-unknown-linux-gnu, the host by default"),
+                )
+                .arg(release_arg())
+                .arg(checked_arithmetic_arg())
+                .arg(debug_info_arg())
+                .arg(error_format_arg())
+                .arg(timings_arg())
+                .arg(emit_ir_on_ice_arg())
+                .arg(jobs_arg())
+                .args(&optimization_args(&optimization_level_names, &pass_names))
+                .args(&target_args())
+                .args(&lint_args(&lint_names)),
         )
         .subcommand(
             SubCommand::with_name("build")
@@ -64,7 +121,38 @@ pub fn main() -> Result<(), Box<dyn Error>> {
                         .value_delimiter(";")
                         .required(true)
                         .help("The files to compile and link"),
-                ),
+                )
+                .arg(
+                    Arg::with_name("lib")
+                        .long("lib")
+                        .takes_value(true)
+                        .possible_values(&["static", "shared"])
+                        .help("Builds a static or shared library instead of an executable"),
+                )
+                .arg(
+                    Arg::with_name("vm")
+                        .long("vm")
+                        .conflicts_with("lib")
+                        .help("Builds a .envyb bytecode file for the VM, named after the first file, instead of an executable"),
+                )
+                .arg(
+                    Arg::with_name("backend")
+                        .long("backend")
+                        .takes_value(true)
+                        .possible_values(&["llvm", "c"])
+                        .conflicts_with_all(&["lib", "vm"])
+                        .help("The backend that generates the executable, either LLVM by default or C compiled with the system cc"),
+                )
+                .arg(release_arg())
+                .arg(checked_arithmetic_arg())
+                .arg(debug_info_arg())
+                .arg(error_format_arg())
+                .arg(timings_arg())
+                .arg(emit_ir_on_ice_arg())
+                .arg(jobs_arg())
+                .args(&optimization_args(&optimization_level_names, &pass_names))
+                .args(&target_args())
+                .args(&lint_args(&lint_names)),
         )
         .subcommand(
             SubCommand::with_name("run")
@@ -77,6 +165,80 @@ pub fn main() -> Result<(), Box<dyn Error>> {
                         .value_delimiter(";")
                         .required(true)
                         .help("The files to run after compiling and linking"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .help("Captures the output of the program into the given file"),
+                )
+                .arg(
+                    Arg::with_name("jit")
+                        .long("jit")
+                        .conflicts_with("output")
+                        .help("Runs the program in memory with LLVM's JIT instead of linking it with g++"),
+                )
+                .arg(
+                    Arg::with_name("interp")
+                        .long("interp")
+                        .conflicts_with_all(&["output", "jit"])
+                        .help("Runs the program with the tree-walking interpreter instead of compiling it"),
+                )
+                .arg(
+                    Arg::with_name("vm")
+                        .long("vm")
+                        .conflicts_with_all(&["output", "jit", "interp"])
+                        .help("Runs the program, or a .envyb file built with build --vm, with the bytecode VM"),
+                )
+                .arg(release_arg())
+                .arg(checked_arithmetic_arg())
+                .arg(debug_info_arg())
+                .arg(error_format_arg())
+                .arg(timings_arg())
+                .arg(emit_ir_on_ice_arg())
+                .arg(jobs_arg())
+                .args(&optimization_args(&optimization_level_names, &pass_names))
+                .args(&target_args())
+                .args(&lint_args(&lint_names))
+                .arg(
+                    Arg::with_name("arguments")
+                        .multiple(true)
+                        .last(true)
+                        .help("The arguments passed to the program"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("disasm")
+                .about("Shows the bytecode of the files, or of a .envyb file, for the VM")
+                .arg(
+                    Arg::with_name("files")
+                        .short("f")
+                        .long("files")
+                        .min_values(1)
+                        .value_delimiter(";")
+                        .required(true)
+                        .help("The files to compile to bytecode, or a single .envyb file"),
+                )
+                .arg(release_arg())
+                .arg(checked_arithmetic_arg())
+                .arg(error_format_arg()),
+        )
+        .subcommand(
+            SubCommand::with_name("repl")
+                .about("Starts an interactive session that evaluates definitions and expressions")
+                .arg(release_arg())
+                .arg(checked_arithmetic_arg())
+                .arg(error_format_arg())
+                .args(&optimization_args(&optimization_level_names, &pass_names))
+                .args(&target_args()),
+        )
+        .subcommand(
+            SubCommand::with_name("explain")
+                .about("Explains an error code in detail")
+                .arg(
+                    Arg::with_name("code")
+                        .required(true)
+                        .help("The code of the error, such as E0001"),
                 ),
         )
         .settings(&[AppSettings::ArgRequiredElseHelp, AppSettings::ColorAlways])
@@ -85,87 +247,343 @@ pub fn main() -> Result<(), Box<dyn Error>> {
     let command = Command::from(matches);
     match command {
         Command::Tui => run_tui()?,
-        Command::Compile { files } => {
-            compile_command(files)?;
+        Command::Compile { files, options } => {
+            compile_command(files, &options)?;
+        }
+        Command::Build {
+            files,
+            vm: true,
+            options,
+            ..
+        } => {
+            build_bytecode_command(files, &options)?;
         }
-        Command::Build { files } => {
-            let (files, main_file) = compile_command(files)?;
+        Command::Build {
+            files,
+            backend: Backend::C,
+            options,
+            ..
+        } => {
+            build_c_command(files, &options)?;
+        }
+        Command::Build {
+            files,
+            library: Some(library),
+            mut options,
+            ..
+        } => {
+            options.position_independent = library == LibraryKind::Shared;
+            options.library = true;
+            let (files, _) = compile_command(files, &options)?;
+            build_library(&files, library)?;
+        }
+        Command::Build {
+            files,
+            library: None,
+            options,
+            ..
+        } => {
+            let (files, main_file) = compile_command(files, &options)?;
             if let Some(ref main_file) = main_file {
                 build_static_files(&files, main_file)?;
             } else {
                 return Err(error("No main method could be found."));
             }
         }
-        Command::Run { files } => {
-            let (files, main_file) = compile_command(files)?;
+        Command::Run {
+            files,
+            arguments,
+            vm: true,
+            options,
+            ..
+        } => {
+            let exit_code = vm_command(files, &arguments, &options)?;
+            process::exit(exit_code);
+        }
+        Command::Run {
+            files,
+            arguments,
+            interp: true,
+            options,
+            ..
+        } => {
+            let exit_code = interp_command(files, &arguments, &options)?;
+            process::exit(exit_code);
+        }
+        Command::Run {
+            files,
+            arguments,
+            jit: true,
+            options,
+            ..
+        } => {
+            let exit_code = jit_command(files, &arguments, &options)?;
+            process::exit(exit_code);
+        }
+        Command::Run {
+            files,
+            arguments,
+            output,
+            jit: false,
+            options,
+            ..
+        } => {
+            let (files, main_file) = compile_command(files, &options)?;
             if let Some(ref main_file) = main_file {
                 build_static_files(&files, main_file)?;
-                run(path_to_str(&replace_last(
-                    main_file,
-                    get_stem(main_file)?,
-                )?)?)?;
+                let exit_code = run(
+                    path_to_str(&replace_last(main_file, get_stem(main_file)?)?)?,
+                    &arguments,
+                    output.as_deref(),
+                )?;
+                process::exit(exit_code);
             } else {
                 return Err(error("No main method could be found."));
             }
         }
+        Command::Disasm { files, options } => disasm_command(files, &options)?,
+        Command::Repl { options } => run_repl(&options)?,
+        Command::PrintTargets => {
+            for (name, description) in available_targets() {
+                println!("{:<12} {}", name, description);
+            }
+        }
+        Command::Explain { code } => match explain(&code) {
+            Some(explanation) => print!("{}", explanation),
+            None => return Err(error(format!("`{}` is not a known error code.", code))),
+        },
         Command::Unknown => return Err(error("Unrecognized command")),
     }
 
     Ok(())
 }
 
+fn release_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("release")
+        .long("release")
+        .help("Compiles without the checks that are enabled by default")
+}
+
+fn checked_arithmetic_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("checked-arithmetic")
+        .long("checked-arithmetic")
+        .takes_value(true)
+        .possible_values(&["on", "off"])
+        .help("Traps on integer overflow and division by zero, on by default unless --release is given")
+}
+
+fn debug_info_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("debug-info")
+        .short("g")
+        .help("Generates debug information, so that the program can be debugged with gdb or lldb")
+}
+
+fn error_format_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("error-format")
+        .long("error-format")
+        .takes_value(true)
+        .possible_values(&["human", "json"])
+        .help("Reports errors for people to read, or as one JSON object per line")
+}
+
+fn timings_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("timings")
+        .long("timings")
+        .help("Reports the time spent on each file and whether it was reused from the cache")
+}
+
+fn emit_ir_on_ice_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("emit-ir-on-ice")
+        .long("emit-ir-on-ice")
+        .help("Writes the partially built LLVM module to a .ice.ll file when an internal compiler error occurs")
+}
+
+fn jobs_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("jobs")
+        .short("j")
+        .long("jobs")
+        .takes_value(true)
+        .validator(|jobs| match jobs.parse::<usize>() {
+            Ok(jobs) if jobs > 0 => Ok(()),
+            _ => Err(String::from(
+                "The number of jobs must be a positive integer",
+            )),
+        })
+        .help("The number of files to compile in parallel, the number of CPUs by default")
+}
+
+fn optimization_args<'a, 'b>(
+    optimization_level_names: &[&'b str],
+    pass_names: &[&'b str],
+) -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("optimization-level")
+            .short("O")
+            .takes_value(true)
+            .possible_values(optimization_level_names)
+            .help("Optimizes the code, -O0 by default and -O2 when --release is given"),
+        Arg::with_name("passes")
+            .long("passes")
+            .takes_value(true)
+            .use_delimiter(true)
+            .min_values(1)
+            .possible_values(pass_names)
+            .help("Runs exactly the given optimization passes, in order, instead of the pipeline of the level"),
+        Arg::with_name("print-after-all")
+            .long("print-after-all")
+            .help("Prints the LLVM IR after each optimization pass"),
+    ]
+}
+
+fn target_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("target-cpu")
+            .long("target-cpu")
+            .takes_value(true)
+            .help("The CPU to generate code for, or `native` for the CPU of this machine, generic by default"),
+        Arg::with_name("target-feature")
+            .long("target-feature")
+            .takes_value(true)
+            .multiple(true)
+            .use_delimiter(true)
+            .number_of_values(1)
+            .help("Enables or disables features of the target, such as +avx2 or -sse4.1"),
+    ]
+}
+
+fn lint_args<'a, 'b>(lint_names: &[&'b str]) -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("allow")
+            .short("A")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .possible_values(lint_names)
+            .help("Allows the given lint, or every lint when given `warnings`"),
+        Arg::with_name("warn")
+            .short("W")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .possible_values(lint_names)
+            .help("Reports the given lint as a warning, or every lint when given `warnings`"),
+        Arg::with_name("deny")
+            .short("D")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .possible_values(lint_names)
+            .help("Reports the given lint as an error, or every lint when given `warnings`"),
+    ]
+}
+
 fn compile_code(
-    error_reporter: &ErrorReporter,
-    interner: &mut Interner<String>,
-    module_name: &str,
-    file_path: &str,
-    output_file_path: &str,
-    bytes: &[u8],
-) -> Option<bool> {
-    let tokens = time("Lexing", &error_reporter, || {
-        lex(file_path, bytes, interner)
-    })?;
+    session: &mut Session,
+    file_id: FileId,
+    output_file: impl Fn(EmitKind) -> PathBuf,
+    output: &mut OutputBuffer,
+) -> Option<CompiledFile> {
+    let has_warnings = check_code(session, file_id, output)?;
+    let artifacts = time("Compiling", session, output, |session| {
+        session.emit_all(file_id)
+    });
+    for ice_dump in session.take_ice_dumps() {
+        let ice_file = output_file(EmitKind::LlvmIr).with_extension("ice.ll");
+        match fs::write(&ice_file, ice_dump) {
+            Ok(()) => output.progress(format!(
+                "Wrote the partially built module to `{}`.",
+                ice_file.display()
+            )),
+            Err(write_error) => output.progress(format!(
+                "Could not write the file `{}`: {}",
+                ice_file.display(),
+                write_error
+            )),
+        }
+    }
 
-    let filtered_tokens = filter_tokens(tokens);
-    let program = time("Parsing", &error_reporter, || parse(filtered_tokens))?;
+    let artifacts = artifacts?;
+    for ir_dump in session.take_ir_dumps() {
+        output.progress(ir_dump.trim_end());
+    }
 
-    let mut type_env = Environment::default();
-    let mut function_table = FunctionTable::default();
-    let typed_program = time("Checking", &error_reporter, || {
-        type_check(program, &mut type_env, &mut function_table)
-    })?;
+    for (kind, artifact) in artifacts {
+        let output_file = output_file(kind);
+        if let Err(write_error) = fs::write(&output_file, artifact) {
+            output.progress(format!(
+                "Could not write the file `{}`: {}",
+                output_file.display(),
+                write_error
+            ));
+            return None;
+        }
+    }
 
-    time("Compiling", &error_reporter, || {
-        let config = Config {
-            writing_to_file: true,
-            output_file_path,
-        };
+    let signatures = session.signatures(file_id)?;
+    Some(CompiledFile {
+        signatures,
+        has_warnings,
+    })
+}
 
-        compile(&typed_program, module_name, interner, Some(config))
+/// Lexes, parses, type checks and lints the file, and returns whether any
+/// warnings were found. Returns `None` if any of the stages failed.
+///
+/// # Arguments
+/// * `session` - The `Session` containing the file.
+/// * `file_id` - The id of the file.
+/// * `output` - The buffer that the progress and the diagnostics are written to.
+pub fn check_code(
+    session: &mut Session,
+    file_id: FileId,
+    output: &mut OutputBuffer,
+) -> Option<bool> {
+    time("Lexing", session, output, |session| {
+        session.tokens(file_id).map(|_| ())
     })?;
+    time("Parsing", session, output, |session| {
+        session.ast(file_id).map(|_| ())
+    })?;
+    time("Checking", session, output, |session| {
+        session.typed_ast(file_id).map(|_| ())
+    })?;
+    time("Linting", session, output, |session| session.lint(file_id))
+}
 
-    let contains_main = typed_program.functions.iter().any(|function| {
-        function.prototype.name == interner.insert("main".to_string())
-            && function.prototype.parameters.is_empty()
-            && function.prototype.return_type == Type::Void
-    });
+/// The result of compiling a single file.
+pub struct CompiledFile {
+    /// The signatures of the functions defined in the file, such as `add(Int, Int) :: Int`.
+    pub signatures: Vec<String>,
+    /// Whether any warnings were reported while compiling the file.
+    pub has_warnings: bool,
+}
 
-    Some(contains_main)
+/// Determines whether one of the signatures is the entry point of a program.
+/// The entry point takes no parameters and returns either `Void` or an `Int`.
+///
+/// # Arguments
+/// * `signatures` - The signatures of the functions defined in a file.
+pub fn defines_main(signatures: &[String]) -> bool {
+    signatures
+        .iter()
+        .any(|signature| signature == "main() :: Void" || signature == "main() :: Int")
 }
 
-fn time<O: Reporter>(
+fn time<T>(
     name: &str,
-    error_reporter: &ErrorReporter,
-    function: impl FnOnce() -> O,
-) -> Option<O::Output> {
+    session: &mut Session,
+    output: &mut OutputBuffer,
+    function: impl FnOnce(&mut Session) -> Option<T>,
+) -> Option<T> {
     let start = Instant::now();
-    let value = function();
-    println!(
+    let value = function(session);
+    output.progress(format!(
         "Process `{}` took {} seconds.",
         name,
         start.elapsed().as_secs_f64()
-    );
-    value.report(error_reporter, true)
+    ));
+    session.report(true, output).ok()?;
+    value
 }
 
 fn build_static_files(files: &[PathBuf], main_file_path: &Path) -> Result<(), Box<dyn Error>> {
@@ -176,7 +594,9 @@ fn build_static_files(files: &[PathBuf], main_file_path: &Path) -> Result<(), Bo
     }
 
     let executable_path = replace_last(main_file_path, get_stem(main_file_path)?.to_string())?;
-    let std_path = home_dir().ok_or("Could not find home directory.")?.join(".envious/std/std.o");
+    let std_path = home_dir()
+        .ok_or("Could not find home directory.")?
+        .join(".envious/std/std.o");
     let output = command
         .arg(std_path)
         .arg("-o")
@@ -190,16 +610,81 @@ fn build_static_files(files: &[PathBuf], main_file_path: &Path) -> Result<(), Bo
     Ok(())
 }
 
-fn run(executable_path: &str) -> Result<(), Box<dyn Error>> {
-    let output = process::Command::new(executable_path).output()?;
-
-    if !output.stdout.is_empty() {
-        print!("{}", String::from_utf8(output.stdout)?);
+fn build_library(files: &[PathBuf], library: LibraryKind) -> Result<(), Box<dyn Error>> {
+    let first_file = files
+        .first()
+        .ok_or_else(|| error("No files were provided to build a library from."))?;
+    let library_stem = get_stem(first_file)?;
+    let mut object_files = vec![];
+    for file in files {
+        let file_stem = get_stem(file)?;
+        object_files.push(replace_last(file, format!("{}.o", file_stem))?);
     }
 
-    if !output.stderr.is_empty() {
-        print!("{}", String::from_utf8(output.stderr)?);
+    let output = match library {
+        LibraryKind::Static => {
+            let library_path = replace_last(first_file, format!("lib{}.a", library_stem))?;
+            process::Command::new("ar")
+                .arg("rcs")
+                .arg(&library_path)
+                .args(&object_files)
+                .output()?
+        }
+        LibraryKind::Shared => {
+            let library_path = replace_last(first_file, format!("lib{}.so", library_stem))?;
+            process::Command::new("g++")
+                .arg("-shared")
+                .args(&object_files)
+                .arg("-o")
+                .arg(&library_path)
+                .output()?
+        }
+    };
+
+    if !output.status.success() {
+        return Err(error("Failed to create the library"));
     }
 
     Ok(())
 }
+
+fn run(
+    executable_path: &str,
+    arguments: &[String],
+    output_file: Option<&Path>,
+) -> Result<i32, Box<dyn Error>> {
+    let mut command = process::Command::new(executable_path);
+    command.args(arguments);
+    if let Some(output_file) = output_file {
+        let file = File::create(output_file)?;
+        command
+            .stdout(Stdio::from(file.try_clone()?))
+            .stderr(Stdio::from(file));
+    }
+
+    let status = command.status()?;
+    if let Some(code) = status.code() {
+        Ok(code)
+    } else {
+        Err(terminated_error(status))
+    }
+}
+
+#[cfg(unix)]
+fn terminated_error(status: ExitStatus) -> Box<dyn Error> {
+    use std::os::unix::process::ExitStatusExt;
+
+    match status.signal() {
+        Some(signal) => error(format!(
+            "The program was terminated by signal {} ({}).",
+            signal,
+            utils::signal_name(signal)
+        )),
+        None => error("The program was terminated without an exit code."),
+    }
+}
+
+#[cfg(not(unix))]
+fn terminated_error(_: ExitStatus) -> Box<dyn Error> {
+    error("The program was terminated without an exit code.")
+}
@@ -0,0 +1,196 @@
+use std::{
+    error::Error,
+    fs::{self, OpenOptions},
+    io::{self, BufRead, Write},
+    mem,
+    path::{Path, PathBuf},
+};
+
+use envyc::{
+    error::reporter::ErrorReporter,
+    repl::{Evaluation, Repl},
+};
+use home::home_dir;
+
+use crate::{command::CompileOptions, utils::get_source};
+
+/// The prompt shown when the REPL is waiting for a new input.
+const PROMPT: &str = "envy> ";
+/// The prompt shown when the input continues on the next line
+/// because one of its braces has not been closed.
+const CONTINUATION_PROMPT: &str = "  ... ";
+
+const HELP: &str = "\
+Enter a definition, such as `define square(x: Int) :: Int = x * x`,
+or an expression to evaluate, such as `square(4)`.
+
+Commands:
+  :type <expression>  Shows the type of the expression without running it
+  :ir <function>      Shows the optimized LLVM IR of a function
+  :load <file>        Loads the definitions of a file
+  :history            Shows the inputs entered so far
+  :help               Shows this message
+  :quit               Exits the REPL";
+
+/// Runs the REPL until the input ends or `:quit` is entered. The inputs
+/// are kept in `~/.envious/repl_history`, so that they can be seen in
+/// later sessions with `:history`.
+///
+/// # Arguments
+/// * `options` - The options the inputs are compiled with.
+pub fn run_repl(options: &CompileOptions) -> Result<(), Box<dyn Error>> {
+    let mut repl = Repl::new(options.session_options());
+    let history_file = home_dir().map(|home| home.join(".envious/repl_history"));
+    let mut history = history_file.as_deref().map_or_else(Vec::new, load_history);
+
+    println!("Envious REPL. Enter `:help` for help.");
+    let stdin = io::stdin();
+    let mut input = String::new();
+    loop {
+        if input.is_empty() {
+            print!("{}", PROMPT);
+        } else {
+            print!("{}", CONTINUATION_PROMPT);
+        }
+
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            break;
+        }
+
+        input.push_str(&line);
+        if !Repl::is_complete(&input) {
+            continue;
+        }
+
+        let entry = mem::take(&mut input);
+        let entry = entry.trim();
+        if entry.is_empty() {
+            continue;
+        }
+
+        // Whitespace is not significant in Envy, so an input that spans
+        // several lines is kept in the history as a single line.
+        let entry = entry.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+        if let Some(ref history_file) = history_file {
+            save_history(history_file, &entry);
+        }
+
+        history.push(entry.clone());
+        let (command, argument) = match entry.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (entry.as_str(), ""),
+        };
+
+        match command {
+            ":quit" | ":q" => break,
+            ":help" => println!("{}", HELP),
+            ":history" => {
+                for (index, entry) in history.iter().enumerate() {
+                    println!("{:>5}  {}", index + 1, entry);
+                }
+            }
+            ":type" => match repl.type_of(argument) {
+                Ok(ty) => println!("{} :: {}", argument, ty),
+                Err(errors) => report(&repl, &errors, options),
+            },
+            ":ir" => match repl.ir(argument) {
+                Ok(Some(ir)) => println!("{}", ir.trim_end()),
+                Ok(None) => println!("No function named `{}` has been defined.", argument),
+                Err(errors) => report(&repl, &errors, options),
+            },
+            ":load" => {
+                let file = PathBuf::from(argument);
+                match get_source(&file) {
+                    Ok(source) => match repl.load(argument, source) {
+                        Ok(signatures) => print_signatures(&signatures),
+                        Err(errors) => report(&repl, &errors, options),
+                    },
+                    Err(read_error) => {
+                        println!(
+                            "Could not read the file `{}`: {}",
+                            file.display(),
+                            read_error
+                        )
+                    }
+                }
+            }
+            _ if command.starts_with(':') => {
+                println!("Unknown command `{}`. Enter `:help` for help.", command)
+            }
+            _ => match repl.evaluate(&entry) {
+                Ok(Evaluation::Definitions(signatures)) => print_signatures(&signatures),
+                Ok(Evaluation::Value {
+                    value: Some(value),
+                    ty,
+                }) => println!("{} :: {}", value, ty),
+                Ok(Evaluation::Value { value: None, ty }) => println!(":: {}", ty),
+                Ok(Evaluation::Exit(code)) => println!("exited with code {}", code),
+                Err(errors) => report(&repl, &errors, options),
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the signatures of the definitions that were added.
+///
+/// # Arguments
+/// * `signatures` - The signatures of the definitions.
+fn print_signatures(signatures: &[String]) {
+    for signature in signatures {
+        println!("defined {}", signature);
+    }
+}
+
+/// Reports the errors of an input.
+///
+/// # Arguments
+/// * `repl` - The `Repl` containing the input.
+/// * `errors` - The errors of the input.
+/// * `options` - The options containing the format of the errors.
+fn report(repl: &Repl, errors: &[envyc::error::Error], options: &CompileOptions) {
+    let mut error_reporter = ErrorReporter::new(repl.source_map());
+    error_reporter.set_format(options.error_format);
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    for error in errors {
+        let _ = stdout.write_all(&error_reporter.report(error, true));
+    }
+
+    let _ = stdout.flush();
+}
+
+/// Loads the inputs of previous sessions. A history that can not be
+/// read is treated as empty.
+///
+/// # Arguments
+/// * `history_file` - The file that the history is kept in.
+fn load_history(history_file: &Path) -> Vec<String> {
+    fs::read_to_string(history_file).map_or_else(
+        |_| vec![],
+        |history| history.lines().map(String::from).collect(),
+    )
+}
+
+/// Adds an input to the end of the history. The history is only a
+/// convenience, so any failure to write it is ignored.
+///
+/// # Arguments
+/// * `history_file` - The file that the history is kept in.
+/// * `entry` - The input.
+fn save_history(history_file: &Path, entry: &str) {
+    if let Some(directory) = history_file.parent() {
+        let _ = fs::create_dir_all(directory);
+    }
+
+    if let Ok(mut file) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(history_file)
+    {
+        let _ = writeln!(file, "{}", entry);
+    }
+}
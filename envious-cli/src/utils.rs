@@ -1,9 +1,12 @@
 use std::{
     error::Error,
     fs,
+    io::{self, Write},
     path::{Path, PathBuf},
 };
 
+use envyc::error::reporter::ErrorFormat;
+
 pub fn clean_file(file: PathBuf) -> Result<PathBuf, Box<dyn Error>> {
     if file.is_file() {
         Ok(file.canonicalize()?)
@@ -49,6 +52,98 @@ pub fn replace_last<S: AsRef<str>>(file: &Path, replacement: S) -> Result<PathBu
     Ok(parent.join(replacement.as_ref()))
 }
 
+/// Prints a message about the progress of the compilation. When errors are
+/// reported as JSON, the message is printed to stderr instead of stdout so
+/// that stdout only contains the errors.
+///
+/// # Arguments
+/// * `error_format` - The format that the errors are reported in.
+/// * `message` - The message to print.
+pub fn log_progress(error_format: ErrorFormat, message: &str) {
+    match error_format {
+        ErrorFormat::Human => println!("{}", message),
+        ErrorFormat::Json => eprintln!("{}", message),
+    }
+}
+
+/// Struct that collects the messages printed while a file is compiled.
+/// Files are compiled in parallel, so their messages are collected
+/// instead of printed right away and each file's messages are printed
+/// together, in the order the files were given.
+#[derive(Debug, Default)]
+pub struct OutputBuffer {
+    /// Each message, along with whether it is a progress message rather than
+    /// the output of the error reporter.
+    messages: Vec<(bool, Vec<u8>)>,
+}
+
+impl OutputBuffer {
+    /// Adds a message about the progress of the compilation.
+    ///
+    /// # Arguments
+    /// * `message` - The message to add.
+    pub fn progress<S: Into<String>>(&mut self, message: S) {
+        self.messages.push((true, message.into().into_bytes()));
+    }
+
+    /// Prints the messages in the order they were added. Progress messages
+    /// are printed with `log_progress`, and everything else is printed to stdout.
+    ///
+    /// # Arguments
+    /// * `error_format` - The format that the errors are reported in.
+    pub fn print(self, error_format: ErrorFormat) {
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        for (is_progress, message) in self.messages {
+            if is_progress {
+                log_progress(error_format, &String::from_utf8_lossy(&message));
+            } else {
+                let _ = stdout.write_all(&message);
+                let _ = stdout.flush();
+            }
+        }
+    }
+}
+
+impl Write for OutputBuffer {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        match self.messages.last_mut() {
+            Some((false, message)) => message.extend_from_slice(bytes),
+            _ => self.messages.push((false, bytes.to_vec())),
+        }
+
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 pub fn error<S: AsRef<str>>(message: S) -> Box<dyn Error> {
     Box::<dyn Error + Send + Sync>::from(message.as_ref())
 }
+
+/// Gets the name of a signal that killed a program, which is shown to the
+/// user instead of the number of the signal.
+///
+/// # Arguments
+/// * `signal` - The number of the signal.
+#[cfg(unix)]
+pub fn signal_name(signal: i32) -> &'static str {
+    match signal {
+        1 => "SIGHUP",
+        2 => "SIGINT",
+        3 => "SIGQUIT",
+        4 => "SIGILL",
+        5 => "SIGTRAP",
+        6 => "SIGABRT",
+        8 => "SIGFPE",
+        9 => "SIGKILL",
+        11 => "SIGSEGV",
+        13 => "SIGPIPE",
+        14 => "SIGALRM",
+        15 => "SIGTERM",
+        _ => "unknown signal",
+    }
+}
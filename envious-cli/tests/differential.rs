@@ -0,0 +1,175 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+/// The command-line arguments passed to every program of the corpus.
+const ARGUMENTS: [&str; 2] = ["3", "42"];
+
+/// The ways of running a program, which should all behave the same.
+const BACKENDS: [Backend; 4] = [Backend::Interp, Backend::Vm, Backend::C, Backend::Jit];
+
+/// Enum that details a way of running a program.
+#[derive(Debug, Clone, Copy)]
+enum Backend {
+    Interp,
+    Vm,
+    C,
+    Jit,
+}
+
+impl Backend {
+    /// Gets the name of the backend, as it is written on the command line.
+    fn name(self) -> &'static str {
+        match self {
+            Backend::Interp => "--interp",
+            Backend::Vm => "--vm",
+            Backend::C => "--backend=c",
+            Backend::Jit => "--jit",
+        }
+    }
+
+    /// Runs the program and returns how it stopped.
+    ///
+    /// # Arguments
+    /// * `file` - The file of the program.
+    fn run(self, file: &Path) -> Outcome {
+        let output = match self {
+            Backend::Interp | Backend::Vm | Backend::Jit => envious(&["run", self.name()], file)
+                .arg("--")
+                .args(ARGUMENTS)
+                .output(),
+            Backend::C => {
+                let build = envious(&["build", self.name()], file)
+                    .output()
+                    .expect("could not run envious");
+                check_compiled(self, file, &build);
+                Command::new(file.with_extension(""))
+                    .args(ARGUMENTS)
+                    .output()
+            }
+        }
+        .expect("could not run the program");
+
+        check_compiled(self, file, &output);
+        Outcome::new(file, &output)
+    }
+}
+
+/// Struct that holds how a program stopped, which is compared between the backends.
+#[derive(Debug, PartialEq)]
+struct Outcome {
+    stdout: String,
+    exit_code: Option<i32>,
+    /// The panic printed to stderr, without the directory of the file.
+    panic: Vec<String>,
+}
+
+impl Outcome {
+    /// Collects the outcome of a program from its output.
+    ///
+    /// # Arguments
+    /// * `file` - The file of the program.
+    /// * `output` - The output of the program.
+    fn new(file: &Path, output: &Output) -> Self {
+        let file_name = file.file_name().unwrap().to_str().unwrap();
+        let prefix = format!("{}:", file_name);
+        let panic = String::from_utf8_lossy(&output.stderr)
+            .lines()
+            .filter_map(|line| line.find(&prefix).map(|start| line[start..].to_string()))
+            .collect();
+        Self {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            exit_code: output.status.code(),
+            panic,
+        }
+    }
+}
+
+/// Creates the command that runs `envious` on the file. Errors are reported
+/// as JSON so that the messages about the progress of the compilation are
+/// printed to stderr, and stdout only contains the output of the program.
+///
+/// # Arguments
+/// * `arguments` - The subcommand and the backend.
+/// * `file` - The file of the program.
+fn envious(arguments: &[&str], file: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_envious"));
+    command
+        .args(arguments)
+        .args(["--error-format=json", "-A", "warnings", "-f"])
+        .arg(file);
+    command
+}
+
+/// Panics if `envious` failed to compile or run the program, rather than
+/// the program itself stopping, since every backend would agree on that.
+///
+/// # Arguments
+/// * `backend` - The backend that ran the program.
+/// * `file` - The file of the program.
+/// * `output` - The output of `envious`.
+fn check_compiled(backend: Backend, file: &Path, output: &Output) {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !stderr.contains("Error: "),
+        "{} could not run `{}`:\n{}{}",
+        backend.name(),
+        file.display(),
+        String::from_utf8_lossy(&output.stdout),
+        stderr
+    );
+}
+
+/// Copies the programs of the corpus into a new directory, so that the
+/// files built next to them do not end up in the source tree.
+fn corpus() -> Vec<PathBuf> {
+    let source = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/programs");
+    let directory = Path::new(env!("CARGO_TARGET_TMPDIR")).join("differential");
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+
+    let mut files = fs::read_dir(source)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension() == Some("envy".as_ref()))
+        .map(|path| {
+            let file = directory.join(path.file_name().unwrap());
+            fs::copy(&path, &file).unwrap();
+            file
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+#[test]
+fn backends_agree() {
+    let files = corpus();
+    assert!(!files.is_empty(), "the corpus has no programs");
+
+    let mut differences = vec![];
+    for file in &files {
+        let expected = Backend::Interp.run(file);
+        for &backend in &BACKENDS[1..] {
+            let outcome = backend.run(file);
+            if outcome != expected {
+                differences.push(format!(
+                    "{}:\n  {}: {:?}\n  {}: {:?}",
+                    file.file_name().unwrap().to_string_lossy(),
+                    Backend::Interp.name(),
+                    expected,
+                    backend.name(),
+                    outcome
+                ));
+            }
+        }
+    }
+
+    assert!(
+        differences.is_empty(),
+        "the backends disagree:\n{}",
+        differences.join("\n")
+    );
+}
@@ -12,6 +12,8 @@ pub struct App {
     pub generated_code: String,
     pub output: Vec<String>,
     pub focused_block: FocusedBlock,
+    /// Whether the generated code is optimized.
+    pub optimized: bool,
 }
 
 impl App {
@@ -25,6 +27,7 @@ impl App {
             generated_code: String::new(),
             output: Vec::new(),
             focused_block: FocusedBlock::Output,
+            optimized: true,
         }
     }
 
@@ -12,13 +12,9 @@ use crossterm::{
     ExecutableCommand,
 };
 use envyc::{
-    compile,
-    environment::Environment,
-    error::reporter::{ErrorReporter, ReporterResult},
-    filter_tokens,
-    function_table::FunctionTable,
-    interner::Interner,
-    lex, parse, type_check,
+    optimization::OptimizationLevel,
+    session::{Options, Session},
+    EmitKind,
 };
 use event::{Event, Events};
 use tui::{
@@ -34,6 +30,10 @@ pub mod app;
 pub mod event;
 pub mod ui;
 
+/// The number of expressions that a program run in the TUI can evaluate
+/// before it is stopped, which takes under a second in a release build.
+const STEP_LIMIT: usize = 10_000_000;
+
 pub fn run_tui() -> Result<(), Box<dyn Error>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -64,6 +64,17 @@ pub fn run_tui() -> Result<(), Box<dyn Error>> {
                         terminal.backend_mut().execute(EnableBlinking)?;
                         app.generated_code.drain(..);
                     }
+                    KeyCode::Char('o') => {
+                        app.optimized = !app.optimized;
+                        if !app.code.is_empty() {
+                            evaluate(&mut app);
+                        }
+                    }
+                    KeyCode::Char('r') => {
+                        if !app.code.is_empty() {
+                            app.output = run_code(&app.code);
+                        }
+                    }
                     KeyCode::Esc => {
                         disable_raw_mode()?;
                         terminal
@@ -153,16 +164,7 @@ pub fn run_tui() -> Result<(), Box<dyn Error>> {
                         app.focused_block = FocusedBlock::Output;
                         events.enable_exit_key();
                         terminal.backend_mut().execute(DisableBlinking)?;
-                        match compile_code(&app.code) {
-                            Ok(generated_code) => {
-                                app.generated_code = generated_code;
-                                app.output = vec![];
-                            }
-                            Err(errors) => {
-                                app.output = errors;
-                                continue;
-                            }
-                        }
+                        evaluate(&mut app);
                     }
                     _ => {}
                 },
@@ -173,17 +175,56 @@ pub fn run_tui() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn compile_code(code: &str) -> Result<String, Vec<String>> {
-    let mut error_reporter = ErrorReporter::new(vec![]);
-    let mut interner = Interner::default();
-    error_reporter.add("editor", code);
-    let tokens =
-        lex("editor", code.as_bytes(), &mut interner).report_result(&error_reporter, true)?;
-    let filtered_tokens = filter_tokens(tokens);
-    let program = parse(filtered_tokens).report_result(&error_reporter, true)?;
-    let mut type_env = Environment::default();
-    let mut function_table = FunctionTable::default();
-    let typed_program = type_check(program, &mut type_env, &mut function_table)
-        .report_result(&error_reporter, true)?;
-    compile(&typed_program, "editor", &mut interner, None).report_result(&error_reporter, true)
+/// Compiles the code in the editor and shows either the generated code or the errors.
+fn evaluate(app: &mut App) {
+    match compile_code(&app.code, app.optimized) {
+        Ok(generated_code) => {
+            app.generated_code = generated_code;
+            app.output = vec![];
+        }
+        Err(errors) => app.output = errors,
+    }
+}
+
+fn compile_code(code: &str, optimized: bool) -> Result<String, Vec<String>> {
+    let mut session = Session::new(Options {
+        emit: vec![EmitKind::LlvmIr],
+        optimization_level: if optimized {
+            OptimizationLevel::O2
+        } else {
+            OptimizationLevel::O0
+        },
+        ..Options::default()
+    });
+    let file_id = session.add_file("editor", code);
+    match session.emit(file_id, EmitKind::LlvmIr) {
+        Some(generated_code) => Ok(String::from_utf8_lossy(&generated_code).into_owned()),
+        None => Err(session.take_diagnostics(true)),
+    }
+}
+
+/// Runs the code in the editor with the interpreter and returns the lines
+/// that it wrote, followed by its exit code, or the errors of the code.
+/// The TUI waits for the program, so it panics once it reaches `STEP_LIMIT`
+/// instead of freezing the TUI when it never stops.
+fn run_code(code: &str) -> Vec<String> {
+    let mut session = Session::new(Options {
+        step_limit: Some(STEP_LIMIT),
+        ..Options::default()
+    });
+    let file_id = session.add_file("editor", code);
+    let mut output = vec![];
+    let mut error_output = vec![];
+    match session.interpret(&[file_id], &["editor"], &mut output, &mut error_output) {
+        Some(exit_code) => {
+            output.extend(error_output);
+            let mut lines = String::from_utf8_lossy(&output)
+                .lines()
+                .map(String::from)
+                .collect::<Vec<_>>();
+            lines.push(format!("Exited with code {}.", exit_code));
+            lines
+        }
+        None => session.take_diagnostics(true),
+    }
 }
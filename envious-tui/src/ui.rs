@@ -19,7 +19,15 @@ pub fn render_help_message<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Re
                 Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
                 Span::raw(" to exit, "),
                 Span::styled("e", Style::default().add_modifier(Modifier::BOLD)),
-                Span::raw(" to start editing."),
+                Span::raw(" to start editing, "),
+                Span::styled("o", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(if app.optimized {
+                    " to show the unoptimized code, "
+                } else {
+                    " to show the optimized code, "
+                }),
+                Span::styled("r", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" to run it."),
             ],
             Style::default().add_modifier(Modifier::RAPID_BLINK),
         ),
@@ -66,7 +74,11 @@ pub fn render_editor_generated_output<B: Backend>(f: &mut Frame<B>, app: &mut Ap
     let generated_code = Paragraph::new(app.generated_code.as_ref()).block(
         Block::default()
             .borders(Borders::ALL)
-            .title("Generated Code"),
+            .title(if app.optimized {
+                "Generated Code (optimized)"
+            } else {
+                "Generated Code (unoptimized)"
+            }),
     );
 
     f.render_widget(generated_code, chunks[1]);
@@ -0,0 +1,415 @@
+use std::collections::HashMap;
+
+use envyc::{
+    builtins::Builtin,
+    error::{Error, SourceMap, Span},
+    interner::Interner,
+    interpreter::externs::{resolve_externs, ExternFunction},
+    linker::check_duplicate_definitions,
+    parser::{
+        expression::{BinaryOperation, UnaryOperation},
+        typed_ast::{TypedFunction, TypedProgram},
+        typed_expression::{TypedExpression, TypedExpressionKind},
+    },
+    semantic_analyzer::types::Type,
+};
+
+use crate::{
+    instruction::Instruction,
+    module::{Function, Location, Module},
+};
+
+/// Compiles type checked programs into a `Module`, linking them together.
+/// The bytecode gives the same results and stops with the same runtime errors
+/// as the code generated by LLVM. Returns an error for each function that
+/// is defined more than once and for each `extern` declaration that is
+/// neither defined by one of the programs nor a C function known to the VM.
+///
+/// # Arguments
+/// * `programs` - The type checked programs.
+/// * `interner` - The `Interner` of the names in the programs.
+/// * `source_map` - The `SourceMap` of the files of the programs.
+/// * `checked_arithmetic` - Whether integer arithmetic panics on overflow.
+pub fn compile(
+    programs: &[&TypedProgram],
+    interner: &Interner<String>,
+    source_map: &SourceMap,
+    checked_arithmetic: bool,
+) -> Result<Module, Vec<Error>> {
+    check_duplicate_definitions(programs, interner)?;
+    let externs = resolve_externs(programs, interner)?;
+    let mut compiler = Compiler {
+        interner,
+        source_map,
+        checked_arithmetic,
+        module: Module::default(),
+        strings: HashMap::new(),
+        functions: HashMap::new(),
+        externs: externs
+            .into_iter()
+            .map(|(name, (extern_function, return_type))| {
+                (name, (extern_function, return_type, None))
+            })
+            .collect(),
+    };
+
+    let functions = programs
+        .iter()
+        .flat_map(|program| program.functions.iter())
+        .collect::<Vec<_>>();
+    for (index, function) in functions.iter().enumerate() {
+        compiler
+            .functions
+            .insert(function.prototype.name, index as u32);
+    }
+
+    for function in functions {
+        let compiled_function = compiler.compile_function(function);
+        let index = compiler.module.functions.len() as u32;
+        compiler.module.functions.push(compiled_function);
+        if interner.get(function.prototype.name) == "main"
+            && function.prototype.parameters.is_empty()
+            && matches!(function.prototype.return_type, Type::Void | Type::Int)
+        {
+            compiler.module.main = Some(index);
+        }
+    }
+
+    Ok(compiler.module)
+}
+
+/// Struct that compiles the functions of the programs into the `Module`.
+struct Compiler<'a> {
+    interner: &'a Interner<String>,
+    source_map: &'a SourceMap,
+    checked_arithmetic: bool,
+    module: Module,
+    /// The index of each string that was added to the `Module`.
+    strings: HashMap<String, u32>,
+    /// The index of each function, by the id of its name.
+    functions: HashMap<usize, u32>,
+    /// The C function of each `extern` declaration, the return type it was
+    /// declared with and its index in the `Module` once it is called.
+    externs: HashMap<usize, (ExternFunction, Type, Option<u32>)>,
+}
+
+/// The function that is being compiled.
+struct FunctionContext {
+    code: Vec<Instruction>,
+    locations: Vec<(u32, Location)>,
+    /// The index of each local variable, by the id of its name. Like in the
+    /// generated code, a variable lives until the function returns, so every
+    /// `let` with the same name in a function stores into the same variable.
+    locals: HashMap<usize, u32>,
+    return_type: Type,
+}
+
+impl<'a> Compiler<'a> {
+    fn compile_function(&mut self, function: &TypedFunction) -> Function {
+        let mut context = FunctionContext {
+            code: vec![],
+            locations: vec![],
+            locals: HashMap::new(),
+            return_type: function.prototype.return_type,
+        };
+
+        for parameter in &function.prototype.parameters {
+            context.local(parameter.name);
+        }
+
+        self.compile_expression(&function.body, &mut context);
+        self.compile_return(&function.body, &mut context);
+        Function {
+            name: self.string(self.interner.get(function.prototype.name)),
+            parameter_count: function.prototype.parameters.len() as u32,
+            local_count: context.locals.len() as u32,
+            code: context.code,
+            locations: context.locations,
+        }
+    }
+
+    /// Compiles an expression, leaving its value on the stack.
+    ///
+    /// # Arguments
+    /// * `expression` - The expression to compile.
+    /// * `context` - The function that is being compiled.
+    fn compile_expression(&mut self, expression: &TypedExpression, context: &mut FunctionContext) {
+        let span = expression.0;
+        match expression.1 {
+            TypedExpressionKind::Int(value) => {
+                self.emit(Instruction::PushInt(value), span, context)
+            }
+            TypedExpressionKind::Float(value) => {
+                self.emit(Instruction::PushFloat(value), span, context)
+            }
+            TypedExpressionKind::Boolean(value) => {
+                self.emit(Instruction::PushBoolean(value), span, context)
+            }
+            TypedExpressionKind::Char(value) => {
+                self.emit(Instruction::PushChar(value as u8), span, context)
+            }
+            TypedExpressionKind::Identifier(ref identifier) => {
+                let local = context.local(identifier.id);
+                self.emit(Instruction::Load(local), span, context);
+            }
+            TypedExpressionKind::Unary(ref unary) => {
+                self.compile_expression(&unary.expression, context);
+                match unary.operation {
+                    UnaryOperation::Plus => {}
+                    UnaryOperation::Minus if self.checked_arithmetic => {
+                        self.emit(Instruction::CheckedNegate, span, context)
+                    }
+                    UnaryOperation::Minus => self.emit(Instruction::Negate, span, context),
+                    UnaryOperation::Not => self.emit(Instruction::Not, span, context),
+                }
+            }
+            TypedExpressionKind::Binary(ref binary) => {
+                // Like in the generated code, both operands of `and` and `or` are evaluated.
+                self.compile_expression(&binary.left, context);
+                self.compile_expression(&binary.right, context);
+                let instruction = match (binary.operation, self.checked_arithmetic) {
+                    (BinaryOperation::Plus, false) => Instruction::Add,
+                    (BinaryOperation::Minus, false) => Instruction::Subtract,
+                    (BinaryOperation::Multiply, false) => Instruction::Multiply,
+                    (BinaryOperation::Divide, false) => Instruction::Divide,
+                    (BinaryOperation::Plus, true) => Instruction::CheckedAdd,
+                    (BinaryOperation::Minus, true) => Instruction::CheckedSubtract,
+                    (BinaryOperation::Multiply, true) => Instruction::CheckedMultiply,
+                    (BinaryOperation::Divide, true) => Instruction::CheckedDivide,
+                    (BinaryOperation::Equals, _) => Instruction::Equals,
+                    (BinaryOperation::LessThan, _) => Instruction::LessThan,
+                    (BinaryOperation::GreaterThan, _) => Instruction::GreaterThan,
+                    (BinaryOperation::LessThanEquals, _) => Instruction::LessThanEquals,
+                    (BinaryOperation::GreaterThanEquals, _) => Instruction::GreaterThanEquals,
+                    (BinaryOperation::And, _) => Instruction::And,
+                    (BinaryOperation::Or, _) => Instruction::Or,
+                };
+
+                self.emit(instruction, binary.operation_span, context);
+            }
+            TypedExpressionKind::If(ref typed_if) if typed_if.else_branch.is_some() => {
+                self.compile_expression(&typed_if.condition, context);
+                let jump_to_else = self.emit_jump(Instruction::JumpIfFalse(0), span, context);
+                self.compile_expression(&typed_if.then_branch, context);
+                let jump_to_end = self.emit_jump(Instruction::Jump(0), span, context);
+                context.patch_jump(jump_to_else);
+                if let Some(ref else_branch) = typed_if.else_branch {
+                    self.compile_expression(else_branch, context);
+                }
+
+                context.patch_jump(jump_to_end);
+            }
+            TypedExpressionKind::Block(ref expressions) => match expressions.split_last() {
+                Some((last, expressions)) => {
+                    for expression in expressions {
+                        self.compile_statement(expression, context);
+                    }
+
+                    self.compile_expression(last, context);
+                }
+                None => self.emit(Instruction::PushVoid, span, context),
+            },
+            TypedExpressionKind::Application(ref application) => {
+                for parameter in &application.parameters {
+                    self.compile_expression(parameter, context);
+                }
+
+                let name = application.function_name.1;
+                if let Some(index) = self.functions.get(&name).copied() {
+                    self.emit(Instruction::Call(index), span, context);
+                } else if let Some((index, return_type)) = self.extern_index(name) {
+                    self.emit(Instruction::CallExtern(index), span, context);
+                    if return_type == Type::Void {
+                        self.emit(Instruction::Pop, span, context);
+                        self.emit(Instruction::PushVoid, span, context);
+                    }
+                } else {
+                    let builtin = Builtin::from_name(self.interner.get(name)).unwrap();
+                    self.emit(Instruction::CallBuiltin(builtin), span, context);
+                }
+            }
+            // An `if` without an `else` has the type `Void`, like `let` and `while`.
+            TypedExpressionKind::If(_)
+            | TypedExpressionKind::Let(_)
+            | TypedExpressionKind::While(_) => {
+                self.compile_statement(expression, context);
+                self.emit(Instruction::PushVoid, span, context);
+            }
+            TypedExpressionKind::Return(ref value) => match value {
+                Some(value) => {
+                    self.compile_expression(value, context);
+                    self.compile_return(value, context);
+                }
+                None => {
+                    self.emit(Instruction::PushVoid, span, context);
+                    self.emit(Instruction::Return, span, context);
+                }
+            },
+            TypedExpressionKind::Panic(message) => {
+                let message = self.string(self.interner.get(message));
+                self.emit(Instruction::Panic(message), span, context);
+            }
+            TypedExpressionKind::Assert(ref condition) => {
+                self.compile_expression(condition, context);
+                self.emit(Instruction::Assert, span, context);
+                self.emit(Instruction::PushVoid, span, context);
+            }
+            TypedExpressionKind::AssertEq(ref assert_eq) => {
+                self.compile_expression(&assert_eq.left, context);
+                self.compile_expression(&assert_eq.right, context);
+                self.emit(Instruction::AssertEq, span, context);
+                self.emit(Instruction::PushVoid, span, context);
+            }
+            // Programs with syntax errors are never compiled.
+            TypedExpressionKind::Error => unreachable!(),
+        }
+    }
+
+    /// Compiles an expression whose value is not used, leaving nothing on the stack.
+    ///
+    /// # Arguments
+    /// * `expression` - The expression to compile.
+    /// * `context` - The function that is being compiled.
+    fn compile_statement(&mut self, expression: &TypedExpression, context: &mut FunctionContext) {
+        let span = expression.0;
+        match expression.1 {
+            TypedExpressionKind::Let(ref typed_let) => {
+                self.compile_expression(&typed_let.expression, context);
+                let local = context.local(typed_let.name.1.id);
+                self.emit(Instruction::Store(local), span, context);
+            }
+            TypedExpressionKind::While(ref typed_while) => {
+                let start = context.code.len() as u32;
+                self.compile_expression(&typed_while.condition, context);
+                let jump_to_end = self.emit_jump(Instruction::JumpIfFalse(0), span, context);
+                self.compile_statement(&typed_while.expression, context);
+                self.emit(Instruction::Jump(start), span, context);
+                context.patch_jump(jump_to_end);
+            }
+            TypedExpressionKind::If(ref typed_if) if typed_if.else_branch.is_none() => {
+                self.compile_expression(&typed_if.condition, context);
+                let jump_to_end = self.emit_jump(Instruction::JumpIfFalse(0), span, context);
+                self.compile_statement(&typed_if.then_branch, context);
+                context.patch_jump(jump_to_end);
+            }
+            TypedExpressionKind::Block(ref expressions) => {
+                for expression in expressions {
+                    self.compile_statement(expression, context);
+                }
+            }
+            _ => {
+                self.compile_expression(expression, context);
+                self.emit(Instruction::Pop, span, context);
+            }
+        }
+    }
+
+    /// Returns the value of an expression, which is on the stack, from the
+    /// function. The value is replaced when the function returns `Void`,
+    /// since its body can end with an expression of any type.
+    ///
+    /// # Arguments
+    /// * `value` - The expression whose value is returned.
+    /// * `context` - The function that is being compiled.
+    fn compile_return(&mut self, value: &TypedExpression, context: &mut FunctionContext) {
+        let span = value.0;
+        if context.return_type == Type::Void && value.1.get_type() != Type::Void {
+            self.emit(Instruction::Pop, span, context);
+            self.emit(Instruction::PushVoid, span, context);
+        }
+
+        self.emit(Instruction::Return, span, context);
+    }
+
+    /// Adds an instruction to the function, along with its location if it can panic.
+    ///
+    /// # Arguments
+    /// * `instruction` - The instruction to add.
+    /// * `span` - The `Span` reported as the location of a panic.
+    /// * `context` - The function that is being compiled.
+    fn emit(&mut self, instruction: Instruction, span: Span, context: &mut FunctionContext) {
+        if instruction.can_panic() {
+            let (line, column) = self.source_map.line_column(span.file_id, span.start);
+            let location = Location {
+                file: self.string(self.source_map.name(span.file_id)),
+                line: line as u32,
+                column: column as u32,
+            };
+            context
+                .locations
+                .push((context.code.len() as u32, location));
+        }
+
+        context.code.push(instruction);
+    }
+
+    /// Adds a jump whose target is set later with `patch_jump`, and returns its index.
+    fn emit_jump(
+        &mut self,
+        instruction: Instruction,
+        span: Span,
+        context: &mut FunctionContext,
+    ) -> usize {
+        self.emit(instruction, span, context);
+        context.code.len() - 1
+    }
+
+    /// Gets the index of the C function called by an `extern` declaration in
+    /// the `Module`, adding it if needed, along with the return type it was
+    /// declared with. Returns `None` if the function is not declared with `extern`.
+    ///
+    /// # Arguments
+    /// * `name` - The id of the name of the function.
+    fn extern_index(&mut self, name: usize) -> Option<(u32, Type)> {
+        let (extern_function, return_type, index) = self.externs.get_mut(&name)?;
+        let index = match index {
+            Some(index) => *index,
+            None => {
+                self.module.externs.push(*extern_function);
+                *index = Some(self.module.externs.len() as u32 - 1);
+                self.module.externs.len() as u32 - 1
+            }
+        };
+
+        Some((index, *return_type))
+    }
+
+    /// Gets the index of a string in the `Module`, adding it if needed.
+    ///
+    /// # Arguments
+    /// * `string` - The string.
+    fn string(&mut self, string: &str) -> u32 {
+        if let Some(index) = self.strings.get(string) {
+            return *index;
+        }
+
+        let index = self.module.strings.len() as u32;
+        self.module.strings.push(string.to_string());
+        self.strings.insert(string.to_string(), index);
+        index
+    }
+}
+
+impl FunctionContext {
+    /// Gets the index of a local variable, adding it if needed.
+    ///
+    /// # Arguments
+    /// * `name` - The id of the name of the variable.
+    fn local(&mut self, name: usize) -> u32 {
+        let next = self.locals.len() as u32;
+        *self.locals.entry(name).or_insert(next)
+    }
+
+    /// Makes the jump with the given index continue at the next instruction.
+    ///
+    /// # Arguments
+    /// * `index` - The index of the jump.
+    fn patch_jump(&mut self, index: usize) {
+        let target = self.code.len() as u32;
+        match self.code[index] {
+            Instruction::Jump(ref mut jump_target)
+            | Instruction::JumpIfFalse(ref mut jump_target) => *jump_target = target,
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use std::fmt::Write;
+
+use crate::{instruction::Instruction, module::Module};
+
+/// Renders the bytecode of a module as text, one instruction per line.
+/// The operands that refer to functions, externs and strings are shown
+/// along with what they refer to, and each instruction that can panic is
+/// followed by its location in the source.
+///
+/// # Arguments
+/// * `module` - The `Module` to disassemble.
+pub fn disassemble(module: &Module) -> String {
+    let mut text = String::new();
+    for (index, extern_function) in module.externs.iter().enumerate() {
+        let _ = writeln!(text, "extern {}: {}", index, extern_function.name());
+    }
+
+    for (index, function) in module.functions.iter().enumerate() {
+        if index > 0 || !module.externs.is_empty() {
+            text.push('\n');
+        }
+
+        let _ = write!(
+            text,
+            "function {}: {} ({} parameters, {} locals)",
+            index,
+            module.function_name(index),
+            function.parameter_count,
+            function.local_count
+        );
+        if module.main == Some(index as u32) {
+            text.push_str(" entry point");
+        }
+
+        text.push('\n');
+        for (instruction_index, instruction) in function.code.iter().enumerate() {
+            let mut line = format!("  {:>5}  {}", instruction_index, instruction.name());
+            let _ = match *instruction {
+                Instruction::PushInt(value) => write!(line, " {}", value),
+                Instruction::PushFloat(value) => write!(line, " {:?}", value),
+                Instruction::PushBoolean(value) => write!(line, " {}", value),
+                Instruction::PushChar(value) => write!(line, " {:?}", value as char),
+                Instruction::Load(operand)
+                | Instruction::Store(operand)
+                | Instruction::Jump(operand)
+                | Instruction::JumpIfFalse(operand) => write!(line, " {}", operand),
+                Instruction::Call(callee) => write!(
+                    line,
+                    " {} ({})",
+                    callee,
+                    module.function_name(callee as usize)
+                ),
+                Instruction::CallExtern(extern_index) => write!(
+                    line,
+                    " {} ({})",
+                    extern_index,
+                    module.externs[extern_index as usize].name()
+                ),
+                Instruction::CallBuiltin(builtin) => write!(line, " {}", builtin.name()),
+                Instruction::Panic(message) => {
+                    write!(line, " {:?}", module.strings[message as usize])
+                }
+                _ => Ok(()),
+            };
+
+            if let Some(location) = function.location(instruction_index) {
+                let _ = write!(
+                    line,
+                    "{:width$}; {}:{}:{}",
+                    "",
+                    module.strings[location.file as usize],
+                    location.line,
+                    location.column,
+                    width = 36usize.saturating_sub(line.len()).max(1)
+                );
+            }
+
+            text.push_str(&line);
+            text.push('\n');
+        }
+    }
+
+    text
+}
@@ -0,0 +1,250 @@
+use envyc::builtins::Builtin;
+
+use crate::module::{DecodeError, Reader};
+
+/// Enum that details the instructions of the VM. Each instruction pops its
+/// operands from the stack of values and pushes its result, so every
+/// expression leaves exactly one value on the stack. The indices of
+/// functions, externs, strings and instructions are those of the `Module`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Instruction {
+    PushInt(i64),
+    PushFloat(f64),
+    PushBoolean(bool),
+    PushChar(u8),
+    /// Pushes the value of an expression of type `Void`.
+    PushVoid,
+    /// Pushes the value of the local variable with the given index. The
+    /// parameters of a function are its first local variables.
+    Load(u32),
+    /// Pops a value into the local variable with the given index.
+    Store(u32),
+    Pop,
+    /// Negates an `Int` or a `Float`. Integers wrap around.
+    Negate,
+    Not,
+    /// Adds two values of the same type. Integers and characters wrap around.
+    Add,
+    Subtract,
+    Multiply,
+    /// Divides two values of the same type. Dividing an `Int` by zero panics,
+    /// while the overflow of dividing the minimum `Int` by -1 wraps around.
+    Divide,
+    /// Adds two values of the same type, panicking when integers or characters overflow.
+    CheckedAdd,
+    CheckedSubtract,
+    CheckedMultiply,
+    CheckedDivide,
+    /// Negates an `Int` or a `Float`, panicking when the minimum `Int` is negated.
+    CheckedNegate,
+    Equals,
+    LessThan,
+    GreaterThan,
+    LessThanEquals,
+    GreaterThanEquals,
+    /// Combines two booleans. Both of them have already been evaluated.
+    And,
+    Or,
+    /// Continues at the instruction with the given index.
+    Jump(u32),
+    /// Pops a boolean and continues at the instruction with the given index if it is false.
+    JumpIfFalse(u32),
+    /// Calls the function with the given index with the arguments on the stack.
+    Call(u32),
+    /// Calls the C function with the given index in the externs of the `Module`.
+    CallExtern(u32),
+    CallBuiltin(Builtin),
+    /// Returns the value on the top of the stack from the current function.
+    Return,
+    /// Panics with the string with the given index as the message.
+    Panic(u32),
+    /// Pops a boolean and panics if it is false.
+    Assert,
+    /// Pops two values and panics if they are not equal.
+    AssertEq,
+}
+
+impl Instruction {
+    /// Gets the name of the instruction shown by the disassembler.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Instruction::PushInt(_) => "push_int",
+            Instruction::PushFloat(_) => "push_float",
+            Instruction::PushBoolean(_) => "push_boolean",
+            Instruction::PushChar(_) => "push_char",
+            Instruction::PushVoid => "push_void",
+            Instruction::Load(_) => "load",
+            Instruction::Store(_) => "store",
+            Instruction::Pop => "pop",
+            Instruction::Negate => "negate",
+            Instruction::Not => "not",
+            Instruction::Add => "add",
+            Instruction::Subtract => "subtract",
+            Instruction::Multiply => "multiply",
+            Instruction::Divide => "divide",
+            Instruction::CheckedAdd => "checked_add",
+            Instruction::CheckedSubtract => "checked_subtract",
+            Instruction::CheckedMultiply => "checked_multiply",
+            Instruction::CheckedDivide => "checked_divide",
+            Instruction::CheckedNegate => "checked_negate",
+            Instruction::Equals => "equals",
+            Instruction::LessThan => "less_than",
+            Instruction::GreaterThan => "greater_than",
+            Instruction::LessThanEquals => "less_than_equals",
+            Instruction::GreaterThanEquals => "greater_than_equals",
+            Instruction::And => "and",
+            Instruction::Or => "or",
+            Instruction::Jump(_) => "jump",
+            Instruction::JumpIfFalse(_) => "jump_if_false",
+            Instruction::Call(_) => "call",
+            Instruction::CallExtern(_) => "call_extern",
+            Instruction::CallBuiltin(_) => "call_builtin",
+            Instruction::Return => "return",
+            Instruction::Panic(_) => "panic",
+            Instruction::Assert => "assert",
+            Instruction::AssertEq => "assert_eq",
+        }
+    }
+
+    /// Determines whether the instruction can panic, in which case the
+    /// `Function` containing it has the location of the instruction.
+    pub fn can_panic(&self) -> bool {
+        matches!(
+            self,
+            Instruction::Divide
+                | Instruction::CheckedAdd
+                | Instruction::CheckedSubtract
+                | Instruction::CheckedMultiply
+                | Instruction::CheckedDivide
+                | Instruction::CheckedNegate
+                | Instruction::Call(_)
+                | Instruction::Panic(_)
+                | Instruction::Assert
+                | Instruction::AssertEq
+        )
+    }
+
+    /// Gets the byte that identifies the instruction in a `.envyb` file.
+    fn opcode(&self) -> u8 {
+        match self {
+            Instruction::PushInt(_) => 0,
+            Instruction::PushFloat(_) => 1,
+            Instruction::PushBoolean(_) => 2,
+            Instruction::PushChar(_) => 3,
+            Instruction::PushVoid => 4,
+            Instruction::Load(_) => 5,
+            Instruction::Store(_) => 6,
+            Instruction::Pop => 7,
+            Instruction::Negate => 8,
+            Instruction::Not => 9,
+            Instruction::Add => 10,
+            Instruction::Subtract => 11,
+            Instruction::Multiply => 12,
+            Instruction::Divide => 13,
+            Instruction::CheckedAdd => 14,
+            Instruction::CheckedSubtract => 15,
+            Instruction::CheckedMultiply => 16,
+            Instruction::CheckedDivide => 17,
+            Instruction::Equals => 18,
+            Instruction::LessThan => 19,
+            Instruction::GreaterThan => 20,
+            Instruction::LessThanEquals => 21,
+            Instruction::GreaterThanEquals => 22,
+            Instruction::And => 23,
+            Instruction::Or => 24,
+            Instruction::Jump(_) => 25,
+            Instruction::JumpIfFalse(_) => 26,
+            Instruction::Call(_) => 27,
+            Instruction::CallExtern(_) => 28,
+            Instruction::CallBuiltin(_) => 29,
+            Instruction::Return => 30,
+            Instruction::Panic(_) => 31,
+            Instruction::Assert => 32,
+            Instruction::AssertEq => 33,
+            Instruction::CheckedNegate => 34,
+        }
+    }
+
+    /// Writes the instruction as its opcode followed by its operand, if any.
+    /// Every number is written in little-endian order.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes of the `.envyb` file.
+    pub(crate) fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.push(self.opcode());
+        match *self {
+            Instruction::PushInt(value) => bytes.extend_from_slice(&value.to_le_bytes()),
+            Instruction::PushFloat(value) => bytes.extend_from_slice(&value.to_le_bytes()),
+            Instruction::PushBoolean(value) => bytes.push(value as u8),
+            Instruction::PushChar(value) => bytes.push(value),
+            Instruction::Load(index)
+            | Instruction::Store(index)
+            | Instruction::Jump(index)
+            | Instruction::JumpIfFalse(index)
+            | Instruction::Call(index)
+            | Instruction::CallExtern(index)
+            | Instruction::Panic(index) => bytes.extend_from_slice(&index.to_le_bytes()),
+            Instruction::CallBuiltin(builtin) => {
+                let index = Builtin::ALL.iter().position(|other| *other == builtin);
+                bytes.push(index.unwrap() as u8);
+            }
+            _ => {}
+        }
+    }
+
+    /// Reads an instruction written by `encode`.
+    ///
+    /// # Arguments
+    /// * `reader` - The `Reader` of the `.envyb` file.
+    pub(crate) fn decode(reader: &mut Reader) -> Result<Instruction, DecodeError> {
+        let opcode = reader.read_u8()?;
+        let instruction = match opcode {
+            0 => Instruction::PushInt(i64::from_le_bytes(reader.read_array()?)),
+            1 => Instruction::PushFloat(f64::from_le_bytes(reader.read_array()?)),
+            2 => match reader.read_u8()? {
+                0 => Instruction::PushBoolean(false),
+                1 => Instruction::PushBoolean(true),
+                _ => return Err(DecodeError::InvalidOperand),
+            },
+            3 => Instruction::PushChar(reader.read_u8()?),
+            4 => Instruction::PushVoid,
+            5 => Instruction::Load(reader.read_u32()?),
+            6 => Instruction::Store(reader.read_u32()?),
+            7 => Instruction::Pop,
+            8 => Instruction::Negate,
+            9 => Instruction::Not,
+            10 => Instruction::Add,
+            11 => Instruction::Subtract,
+            12 => Instruction::Multiply,
+            13 => Instruction::Divide,
+            14 => Instruction::CheckedAdd,
+            15 => Instruction::CheckedSubtract,
+            16 => Instruction::CheckedMultiply,
+            17 => Instruction::CheckedDivide,
+            18 => Instruction::Equals,
+            19 => Instruction::LessThan,
+            20 => Instruction::GreaterThan,
+            21 => Instruction::LessThanEquals,
+            22 => Instruction::GreaterThanEquals,
+            23 => Instruction::And,
+            24 => Instruction::Or,
+            25 => Instruction::Jump(reader.read_u32()?),
+            26 => Instruction::JumpIfFalse(reader.read_u32()?),
+            27 => Instruction::Call(reader.read_u32()?),
+            28 => Instruction::CallExtern(reader.read_u32()?),
+            29 => {
+                let index = reader.read_u8()? as usize;
+                let builtin = Builtin::ALL.get(index).ok_or(DecodeError::InvalidOperand)?;
+                Instruction::CallBuiltin(*builtin)
+            }
+            30 => Instruction::Return,
+            31 => Instruction::Panic(reader.read_u32()?),
+            32 => Instruction::Assert,
+            33 => Instruction::AssertEq,
+            34 => Instruction::CheckedNegate,
+            _ => return Err(DecodeError::InvalidOpcode(opcode)),
+        };
+
+        Ok(instruction)
+    }
+}
@@ -0,0 +1,5 @@
+pub mod compiler;
+pub mod disassembler;
+pub mod instruction;
+pub mod module;
+pub mod vm;
@@ -0,0 +1,329 @@
+use std::{convert::TryInto, fmt::Display};
+
+use envyc::interpreter::externs::ExternFunction;
+
+use crate::instruction::Instruction;
+
+/// The bytes that every `.envyb` file starts with.
+const MAGIC: &[u8] = b"ENVYB";
+/// The version of the format written by `Module::encode`. It changes
+/// whenever the format or the meaning of an instruction changes.
+pub const FORMAT_VERSION: u8 = 2;
+/// The value written in place of the index of `main` when there is none.
+const NO_MAIN: u32 = u32::MAX;
+
+/// The location in the source of an instruction that can panic, which
+/// is printed with the message of the panic. The location is kept in the
+/// `Module`, since the source is not available when a `.envyb` file is run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Location {
+    /// The index of the name of the file in the strings of the `Module`.
+    pub file: u32,
+    pub line: u32,
+    pub column: u32,
+}
+
+/// A function compiled to bytecode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    /// The index of the name of the function in the strings of the `Module`.
+    pub name: u32,
+    pub parameter_count: u32,
+    /// The number of local variables, including the parameters.
+    pub local_count: u32,
+    pub code: Vec<Instruction>,
+    /// The location of each instruction that can panic, by the index of the
+    /// instruction. The locations are sorted by that index.
+    pub locations: Vec<(u32, Location)>,
+}
+
+impl Function {
+    /// Gets the location of the instruction with the given index.
+    ///
+    /// # Arguments
+    /// * `index` - The index of an instruction that can panic.
+    pub fn location(&self, index: usize) -> Option<Location> {
+        self.locations
+            .binary_search_by_key(&(index as u32), |(index, _)| *index)
+            .ok()
+            .map(|position| self.locations[position].1)
+    }
+}
+
+/// Struct that holds programs compiled to bytecode, which can be run by the
+/// `Vm` or written to a `.envyb` file. The functions of every program are
+/// linked together, like the object files of compiled programs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Module {
+    /// The names of the functions and the files, and the messages of the panics.
+    pub strings: Vec<String>,
+    /// The C functions called by the programs.
+    pub externs: Vec<ExternFunction>,
+    pub functions: Vec<Function>,
+    /// The index of the `main` function, if any of the programs define one.
+    pub main: Option<u32>,
+}
+
+impl Module {
+    /// Writes the module in the format of a `.envyb` file.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(FORMAT_VERSION);
+        write_u32(&mut bytes, self.strings.len() as u32);
+        for string in &self.strings {
+            write_u32(&mut bytes, string.len() as u32);
+            bytes.extend_from_slice(string.as_bytes());
+        }
+
+        write_u32(&mut bytes, self.externs.len() as u32);
+        for extern_function in &self.externs {
+            let index = ExternFunction::ALL
+                .iter()
+                .position(|other| other == extern_function);
+            bytes.push(index.unwrap() as u8);
+        }
+
+        write_u32(&mut bytes, self.functions.len() as u32);
+        for function in &self.functions {
+            write_u32(&mut bytes, function.name);
+            write_u32(&mut bytes, function.parameter_count);
+            write_u32(&mut bytes, function.local_count);
+            write_u32(&mut bytes, function.code.len() as u32);
+            for instruction in &function.code {
+                instruction.encode(&mut bytes);
+            }
+
+            write_u32(&mut bytes, function.locations.len() as u32);
+            for (index, location) in &function.locations {
+                write_u32(&mut bytes, *index);
+                write_u32(&mut bytes, location.file);
+                write_u32(&mut bytes, location.line);
+                write_u32(&mut bytes, location.column);
+            }
+        }
+
+        write_u32(&mut bytes, self.main.unwrap_or(NO_MAIN));
+        bytes
+    }
+
+    /// Reads a module from the contents of a `.envyb` file. The module is
+    /// validated, so that every index in it refers to something that exists.
+    ///
+    /// # Arguments
+    /// * `bytes` - The contents of the file.
+    pub fn decode(bytes: &[u8]) -> Result<Module, DecodeError> {
+        let mut reader = Reader { bytes, position: 0 };
+        if reader.read_bytes(MAGIC.len()) != Ok(MAGIC) {
+            return Err(DecodeError::InvalidMagic);
+        }
+
+        let version = reader.read_u8()?;
+        if version != FORMAT_VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+
+        let mut module = Module::default();
+        for _ in 0..reader.read_u32()? {
+            let length = reader.read_u32()? as usize;
+            let string = std::str::from_utf8(reader.read_bytes(length)?)
+                .map_err(|_| DecodeError::InvalidString)?;
+            module.strings.push(string.to_string());
+        }
+
+        for _ in 0..reader.read_u32()? {
+            let index = reader.read_u8()? as usize;
+            let extern_function = ExternFunction::ALL
+                .get(index)
+                .ok_or(DecodeError::InvalidOperand)?;
+            module.externs.push(*extern_function);
+        }
+
+        for _ in 0..reader.read_u32()? {
+            let name = reader.read_u32()?;
+            let parameter_count = reader.read_u32()?;
+            let local_count = reader.read_u32()?;
+            let mut code = vec![];
+            for _ in 0..reader.read_u32()? {
+                code.push(Instruction::decode(&mut reader)?);
+            }
+
+            let mut locations = vec![];
+            for _ in 0..reader.read_u32()? {
+                let index = reader.read_u32()?;
+                let location = Location {
+                    file: reader.read_u32()?,
+                    line: reader.read_u32()?,
+                    column: reader.read_u32()?,
+                };
+                locations.push((index, location));
+            }
+
+            module.functions.push(Function {
+                name,
+                parameter_count,
+                local_count,
+                code,
+                locations,
+            });
+        }
+
+        let main = reader.read_u32()?;
+        module.main = if main == NO_MAIN { None } else { Some(main) };
+        if reader.position != bytes.len() {
+            return Err(DecodeError::TrailingBytes);
+        }
+
+        module.validate()?;
+        Ok(module)
+    }
+
+    /// Gets the name of the function with the given index.
+    ///
+    /// # Arguments
+    /// * `index` - The index of the function.
+    pub fn function_name(&self, index: usize) -> &str {
+        &self.strings[self.functions[index].name as usize]
+    }
+
+    /// Checks that every index in the module refers to something that exists,
+    /// that every function ends by leaving it, and that every instruction
+    /// that can panic has a location. The number of values on the stack is
+    /// not checked, so the `Vm` still assumes the bytecode was compiled by
+    /// `compile`.
+    fn validate(&self) -> Result<(), DecodeError> {
+        let is_string = |index: u32| (index as usize) < self.strings.len();
+        let is_function = |index: u32| (index as usize) < self.functions.len();
+        for function in &self.functions {
+            if !is_string(function.name) || function.local_count < function.parameter_count {
+                return Err(DecodeError::InvalidOperand);
+            }
+
+            if !matches!(
+                function.code.last(),
+                Some(Instruction::Return)
+                    | Some(Instruction::Jump(_))
+                    | Some(Instruction::Panic(_))
+            ) {
+                return Err(DecodeError::InvalidOperand);
+            }
+
+            for (index, instruction) in function.code.iter().enumerate() {
+                let is_valid = match *instruction {
+                    Instruction::Load(local) | Instruction::Store(local) => {
+                        local < function.local_count
+                    }
+                    Instruction::Jump(target) | Instruction::JumpIfFalse(target) => {
+                        (target as usize) < function.code.len()
+                    }
+                    Instruction::Call(callee) => is_function(callee),
+                    Instruction::CallExtern(extern_index) => {
+                        (extern_index as usize) < self.externs.len()
+                    }
+                    Instruction::Panic(message) => is_string(message),
+                    _ => true,
+                };
+
+                if !is_valid || (instruction.can_panic() && function.location(index).is_none()) {
+                    return Err(DecodeError::InvalidOperand);
+                }
+            }
+
+            if function
+                .locations
+                .iter()
+                .any(|(_, location)| !is_string(location.file))
+            {
+                return Err(DecodeError::InvalidOperand);
+            }
+        }
+
+        match self.main {
+            Some(main)
+                if !is_function(main) || self.functions[main as usize].parameter_count != 0 =>
+            {
+                Err(DecodeError::InvalidOperand)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Enum that details why a `.envyb` file could not be read.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    /// The file does not start with the bytes of a `.envyb` file.
+    InvalidMagic,
+    /// The file was written by a version of the format that is not supported.
+    UnsupportedVersion(u8),
+    /// The file ends in the middle of the module.
+    UnexpectedEnd,
+    /// The file continues after the end of the module.
+    TrailingBytes,
+    InvalidString,
+    InvalidOpcode(u8),
+    /// An operand or an index does not refer to anything in the module.
+    InvalidOperand,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::InvalidMagic => write!(f, "the file is not an Envy bytecode file"),
+            DecodeError::UnsupportedVersion(version) => write!(
+                f,
+                "the file has version {} of the bytecode format, but only version {} is supported",
+                version, FORMAT_VERSION
+            ),
+            DecodeError::UnexpectedEnd => write!(f, "the file ends unexpectedly"),
+            DecodeError::TrailingBytes => write!(f, "the file continues after the module"),
+            DecodeError::InvalidString => write!(f, "the file contains a string that is not UTF-8"),
+            DecodeError::InvalidOpcode(opcode) => {
+                write!(f, "the file contains the unknown opcode {}", opcode)
+            }
+            DecodeError::InvalidOperand => {
+                write!(f, "the file refers to something that it does not contain")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Struct that reads the numbers and the bytes of a `.envyb` file in order.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Reads the given number of bytes.
+    ///
+    /// # Arguments
+    /// * `length` - The number of bytes to read.
+    pub(crate) fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], DecodeError> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(DecodeError::UnexpectedEnd)?;
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    pub(crate) fn read_array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        Ok(self.read_bytes(N)?.try_into().unwrap())
+    }
+
+    pub(crate) fn read_u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub(crate) fn read_u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+}
+
+fn write_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
@@ -0,0 +1,441 @@
+use std::io::Write;
+
+use envyc::{
+    builtins::Builtin,
+    codegen::PANIC_EXIT_CODE,
+    error::Error,
+    interpreter::{parse_integer, value::Value},
+};
+
+use crate::{
+    instruction::Instruction,
+    module::{Location, Module},
+};
+
+/// The number of calls that can be nested before the program is stopped.
+/// A call only uses a few values of memory, so this is far more than the
+/// stack of a compiled program allows, while still stopping infinite recursion.
+pub const MAX_CALL_DEPTH: usize = 1_000_000;
+
+/// A runtime error that stopped the program, such as a failed assertion.
+/// The message is the same as the one printed by the compiled program.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Panic {
+    pub location: Location,
+    pub message: String,
+}
+
+impl Panic {
+    /// Renders the panic as `file:line:column: message`, which is how
+    /// the compiled program prints it to stderr.
+    ///
+    /// # Arguments
+    /// * `module` - The `Module` containing the name of the file of the panic.
+    pub fn render(&self, module: &Module) -> String {
+        format!(
+            "{}:{}:{}: {}",
+            module.strings[self.location.file as usize],
+            self.location.line,
+            self.location.column,
+            self.message
+        )
+    }
+}
+
+/// Enum that details how a program run by the `Vm` stopped.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Exit {
+    /// The program returned from `main` or called `exit` with the exit code.
+    Code(i32),
+    /// The program panicked.
+    Panic(Panic),
+}
+
+impl Exit {
+    /// Gets the exit code of the program. A panic exits with
+    /// `PANIC_EXIT_CODE`, like the compiled program.
+    pub fn code(&self) -> i32 {
+        match self {
+            Exit::Code(code) => *code,
+            Exit::Panic(_) => PANIC_EXIT_CODE as i32,
+        }
+    }
+}
+
+/// A call of a function that has not returned yet.
+struct Frame {
+    function: usize,
+    /// The index of the next instruction of the function.
+    next: usize,
+    /// The index of the first local variable of the function on the stack.
+    base: usize,
+}
+
+/// Struct that runs the bytecode of a `Module`. The values of the local
+/// variables and the values of the expressions being evaluated share a
+/// single stack, and calls do not use the stack of the VM itself, so deep
+/// recursion only stops the program when it reaches `MAX_CALL_DEPTH`.
+pub struct Vm<'a> {
+    module: &'a Module,
+    stack: Vec<Value>,
+    frames: Vec<Frame>,
+    /// The command-line arguments of the program, starting with its name.
+    arguments: Vec<String>,
+    /// The writer that the standard output of the program is written to.
+    output: &'a mut dyn Write,
+}
+
+impl<'a> Vm<'a> {
+    /// Creates a `Vm` that runs the module.
+    ///
+    /// # Arguments
+    /// * `module` - The `Module` to run.
+    /// * `output` - The writer that the standard output of the program is written to.
+    pub fn new(module: &'a Module, output: &'a mut dyn Write) -> Self {
+        Self {
+            module,
+            stack: vec![],
+            frames: vec![],
+            arguments: vec![],
+            output,
+        }
+    }
+
+    /// Runs the `main` function of the module and returns how the program
+    /// stopped. Returns an error if the module does not have a `main` function.
+    ///
+    /// # Arguments
+    /// * `arguments` - The command-line arguments of the program, starting with its name.
+    pub fn run_main(&mut self, arguments: &[&str]) -> Result<Exit, Vec<Error>> {
+        let main = self
+            .module
+            .main
+            .ok_or_else(|| vec![Error::MissingEntryPoint])? as usize;
+        self.arguments = arguments
+            .iter()
+            .map(|argument| argument.to_string())
+            .collect();
+        self.stack.clear();
+        self.frames.clear();
+        self.enter(main);
+        let exit = match self.run() {
+            // The exit code is truncated to the `int` returned by the C `main`.
+            Ok(Value::Int(code)) => Exit::Code(code as i32),
+            Ok(_) => Exit::Code(0),
+            Err(exit) => exit,
+        };
+
+        let _ = self.output.flush();
+        Ok(exit)
+    }
+
+    /// Runs instructions until the first function that was called returns.
+    /// Returns the exit when the program stops early.
+    fn run(&mut self) -> Result<Value, Exit> {
+        loop {
+            let frame = self.frames.last_mut().unwrap();
+            let index = frame.next;
+            frame.next += 1;
+            match self.module.functions[frame.function].code[index] {
+                Instruction::PushInt(value) => self.stack.push(Value::Int(value)),
+                Instruction::PushFloat(value) => self.stack.push(Value::Float(value)),
+                Instruction::PushBoolean(value) => self.stack.push(Value::Boolean(value)),
+                Instruction::PushChar(value) => self.stack.push(Value::Char(value)),
+                Instruction::PushVoid => self.stack.push(Value::Void),
+                Instruction::Load(local) => {
+                    let value = self.stack[self.base() + local as usize];
+                    self.stack.push(value);
+                }
+                Instruction::Store(local) => {
+                    let value = self.pop();
+                    let local = self.base() + local as usize;
+                    self.stack[local] = value;
+                }
+                Instruction::Pop => {
+                    self.pop();
+                }
+                Instruction::Negate | Instruction::CheckedNegate => {
+                    let value = match self.pop() {
+                        Value::Int(value) => Value::Int(self.check_overflow(
+                            value.overflowing_neg(),
+                            index,
+                            "attempt to negate with overflow",
+                        )?),
+                        Value::Float(value) => Value::Float(-value),
+                        _ => unreachable!(),
+                    };
+                    self.stack.push(value);
+                }
+                Instruction::Not => {
+                    let value = !self.pop().as_boolean();
+                    self.stack.push(Value::Boolean(value));
+                }
+                Instruction::Add | Instruction::CheckedAdd => {
+                    let value = match self.pop_pair() {
+                        (Value::Int(left), Value::Int(right)) => Value::Int(self.check_overflow(
+                            left.overflowing_add(right),
+                            index,
+                            "attempt to add with overflow",
+                        )?),
+                        (Value::Char(left), Value::Char(right)) => {
+                            let value = self.check_overflow(
+                                (left as i8).overflowing_add(right as i8),
+                                index,
+                                "attempt to add with overflow",
+                            )?;
+                            Value::Char(value as u8)
+                        }
+                        (Value::Float(left), Value::Float(right)) => Value::Float(left + right),
+                        _ => unreachable!(),
+                    };
+                    self.stack.push(value);
+                }
+                Instruction::Subtract | Instruction::CheckedSubtract => {
+                    let value = match self.pop_pair() {
+                        (Value::Int(left), Value::Int(right)) => Value::Int(self.check_overflow(
+                            left.overflowing_sub(right),
+                            index,
+                            "attempt to subtract with overflow",
+                        )?),
+                        (Value::Float(left), Value::Float(right)) => Value::Float(left - right),
+                        _ => unreachable!(),
+                    };
+                    self.stack.push(value);
+                }
+                Instruction::Multiply | Instruction::CheckedMultiply => {
+                    let value = match self.pop_pair() {
+                        (Value::Int(left), Value::Int(right)) => Value::Int(self.check_overflow(
+                            left.overflowing_mul(right),
+                            index,
+                            "attempt to multiply with overflow",
+                        )?),
+                        (Value::Float(left), Value::Float(right)) => Value::Float(left * right),
+                        _ => unreachable!(),
+                    };
+                    self.stack.push(value);
+                }
+                Instruction::Divide | Instruction::CheckedDivide => {
+                    let value = match self.pop_pair() {
+                        (Value::Int(_), Value::Int(0)) => {
+                            return Err(self.panic(index, "attempt to divide by zero"));
+                        }
+                        (Value::Int(left), Value::Int(right)) => Value::Int(self.check_overflow(
+                            left.overflowing_div(right),
+                            index,
+                            "attempt to divide with overflow",
+                        )?),
+                        (Value::Float(left), Value::Float(right)) => Value::Float(left / right),
+                        _ => unreachable!(),
+                    };
+                    self.stack.push(value);
+                }
+                Instruction::Equals => {
+                    let (left, right) = self.pop_pair();
+                    self.stack.push(Value::Boolean(left == right));
+                }
+                instruction @ Instruction::LessThan
+                | instruction @ Instruction::GreaterThan
+                | instruction @ Instruction::LessThanEquals
+                | instruction @ Instruction::GreaterThanEquals => {
+                    let value = match self.pop_pair() {
+                        (Value::Int(left), Value::Int(right)) => compare(instruction, left, right),
+                        (Value::Float(left), Value::Float(right)) => {
+                            compare(instruction, left, right)
+                        }
+                        // Characters are compared as signed bytes, as they are in the generated code.
+                        (Value::Char(left), Value::Char(right)) => {
+                            compare(instruction, left as i8, right as i8)
+                        }
+                        _ => unreachable!(),
+                    };
+                    self.stack.push(Value::Boolean(value));
+                }
+                Instruction::And => {
+                    let (left, right) = self.pop_pair();
+                    self.stack
+                        .push(Value::Boolean(left.as_boolean() && right.as_boolean()));
+                }
+                Instruction::Or => {
+                    let (left, right) = self.pop_pair();
+                    self.stack
+                        .push(Value::Boolean(left.as_boolean() || right.as_boolean()));
+                }
+                Instruction::Jump(target) => self.frames.last_mut().unwrap().next = target as usize,
+                Instruction::JumpIfFalse(target) => {
+                    if !self.pop().as_boolean() {
+                        self.frames.last_mut().unwrap().next = target as usize;
+                    }
+                }
+                Instruction::Call(callee) => {
+                    if self.frames.len() == MAX_CALL_DEPTH {
+                        return Err(self.panic(index, "stack overflow"));
+                    }
+
+                    self.enter(callee as usize);
+                }
+                Instruction::CallExtern(extern_index) => {
+                    let extern_function = self.module.externs[extern_index as usize];
+                    let parameter_count = extern_function.signature().0.len();
+                    let arguments = self.stack.split_off(self.stack.len() - parameter_count);
+                    let value = extern_function
+                        .call(&arguments, self.output)
+                        .map_err(Exit::Code)?;
+                    self.stack.push(value);
+                }
+                Instruction::CallBuiltin(builtin) => {
+                    let parameter_count = builtin.parameter_types().len();
+                    let arguments = self.stack.split_off(self.stack.len() - parameter_count);
+                    let value = self.call_builtin(builtin, &arguments);
+                    self.stack.push(value);
+                }
+                Instruction::Return => {
+                    let value = self.pop();
+                    let frame = self.frames.pop().unwrap();
+                    self.stack.truncate(frame.base);
+                    if self.frames.is_empty() {
+                        return Ok(value);
+                    }
+
+                    self.stack.push(value);
+                }
+                Instruction::Panic(message) => {
+                    return Err(self.panic(index, &self.module.strings[message as usize]));
+                }
+                Instruction::Assert => {
+                    if !self.pop().as_boolean() {
+                        return Err(self.panic(index, "assertion failed"));
+                    }
+                }
+                Instruction::AssertEq => {
+                    let (left, right) = self.pop_pair();
+                    if left != right {
+                        return Err(self.panic(index, "assertion failed: left == right"));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Starts a call of a function, whose arguments are on the top of the stack.
+    ///
+    /// # Arguments
+    /// * `function` - The index of the function.
+    fn enter(&mut self, function: usize) {
+        let callee = &self.module.functions[function];
+        let base = self.stack.len() - callee.parameter_count as usize;
+        self.stack
+            .resize(base + callee.local_count as usize, Value::Void);
+        self.frames.push(Frame {
+            function,
+            next: 0,
+            base,
+        });
+    }
+
+    /// Calls a function that is provided by the compiler.
+    ///
+    /// # Arguments
+    /// * `builtin` - The `Builtin` being called.
+    /// * `arguments` - The values of the arguments.
+    fn call_builtin(&self, builtin: Builtin, arguments: &[Value]) -> Value {
+        match builtin {
+            // The first argument is always the name of the program, which is skipped.
+            Builtin::ArgCount => Value::Int(self.arguments.len() as i64 - 1),
+            Builtin::Arg => {
+                let index = arguments[0].as_int();
+                let argument = if index >= 0 {
+                    self.arguments.get(index as usize + 1)
+                } else {
+                    None
+                };
+
+                Value::Int(argument.map_or(0, |argument| parse_integer(argument)))
+            }
+            Builtin::WrappingAdd => {
+                Value::Int(arguments[0].as_int().wrapping_add(arguments[1].as_int()))
+            }
+            Builtin::SaturatingAdd => {
+                Value::Int(arguments[0].as_int().saturating_add(arguments[1].as_int()))
+            }
+        }
+    }
+
+    /// Gets the index of the first local variable of the current function on the stack.
+    fn base(&self) -> usize {
+        self.frames.last().unwrap().base
+    }
+
+    fn pop(&mut self) -> Value {
+        self.stack.pop().unwrap()
+    }
+
+    /// Pops the two operands of a binary operation, returning the left one first.
+    fn pop_pair(&mut self) -> (Value, Value) {
+        let right = self.pop();
+        let left = self.pop();
+        (left, right)
+    }
+
+    /// Gets the result of an integer operation, which panics with the message
+    /// when the operation overflowed and the instruction is checked.
+    /// Otherwise, the result wraps around.
+    ///
+    /// # Arguments
+    /// * `result` - The result of the operation and whether it overflowed.
+    /// * `index` - The index of the instruction of the operation.
+    /// * `message` - The message of the panic.
+    fn check_overflow<T>(
+        &self,
+        (value, overflows): (T, bool),
+        index: usize,
+        message: &str,
+    ) -> Result<T, Exit> {
+        let frame = self.frames.last().unwrap();
+        let is_checked = matches!(
+            self.module.functions[frame.function].code[index],
+            Instruction::CheckedAdd
+                | Instruction::CheckedSubtract
+                | Instruction::CheckedMultiply
+                | Instruction::CheckedDivide
+                | Instruction::CheckedNegate
+        );
+        if overflows && is_checked {
+            Err(self.panic(index, message))
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Creates the exit of a panic at an instruction of the current function.
+    ///
+    /// # Arguments
+    /// * `index` - The index of the instruction that panicked.
+    /// * `message` - The message of the panic.
+    fn panic(&self, index: usize, message: &str) -> Exit {
+        let frame = self.frames.last().unwrap();
+        let location = self.module.functions[frame.function]
+            .location(index)
+            .unwrap();
+        Exit::Panic(Panic {
+            location,
+            message: message.to_string(),
+        })
+    }
+}
+
+/// Compares two values with a comparison instruction. The comparisons of
+/// floats are ordered, so they are false when either float is NaN.
+///
+/// # Arguments
+/// * `instruction` - The comparison instruction.
+/// * `left` - The left operand.
+/// * `right` - The right operand.
+fn compare<T: PartialOrd>(instruction: Instruction, left: T, right: T) -> bool {
+    match instruction {
+        Instruction::LessThan => left < right,
+        Instruction::GreaterThan => left > right,
+        Instruction::LessThanEquals => left <= right,
+        Instruction::GreaterThanEquals => left >= right,
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{
+    environment::Environment, function_table::FunctionTable, interner::Interner,
+    semantic_analyzer::types::Type,
+};
+
+/// Enum that details the functions that are provided by the compiler
+/// itself instead of being defined by the user or declared as `extern`.
+/// The code generator lowers calls to these functions directly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Builtin {
+    /// Returns the number of command-line arguments passed to the program,
+    /// not including the name of the program.
+    ArgCount,
+    /// Returns the command-line argument at the given index parsed as an `Int`.
+    /// Arguments that are out of bounds or that are not integers evaluate to 0.
+    Arg,
+    /// Adds two integers, wrapping around on overflow.
+    WrappingAdd,
+    /// Adds two integers, clamping the result to the bounds of an `Int` on overflow.
+    SaturatingAdd,
+}
+
+impl Builtin {
+    /// All of the builtins known to the compiler.
+    pub const ALL: [Builtin; 4] = [
+        Builtin::ArgCount,
+        Builtin::Arg,
+        Builtin::WrappingAdd,
+        Builtin::SaturatingAdd,
+    ];
+
+    /// Gets the name used to call this builtin.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Builtin::ArgCount => "arg_count",
+            Builtin::Arg => "arg",
+            Builtin::WrappingAdd => "wrapping_add",
+            Builtin::SaturatingAdd => "saturating_add",
+        }
+    }
+
+    /// Finds the builtin with the given name.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the function being called.
+    pub fn from_name(name: &str) -> Option<Builtin> {
+        Builtin::ALL
+            .iter()
+            .copied()
+            .find(|builtin| builtin.name() == name)
+    }
+
+    /// Gets the types of the parameters of this builtin.
+    pub fn parameter_types(&self) -> Vec<Type> {
+        match self {
+            Builtin::ArgCount => vec![],
+            Builtin::Arg => vec![Type::Int],
+            Builtin::WrappingAdd | Builtin::SaturatingAdd => vec![Type::Int, Type::Int],
+        }
+    }
+
+    /// Gets the return type of this builtin.
+    pub fn return_type(&self) -> Type {
+        match self {
+            Builtin::ArgCount => Type::Int,
+            Builtin::Arg => Type::Int,
+            Builtin::WrappingAdd => Type::Int,
+            Builtin::SaturatingAdd => Type::Int,
+        }
+    }
+}
+
+/// Defines every builtin in the environment and the function table so
+/// that calls to them can be type checked like any other function.
+/// User-defined functions with the same name take precedence.
+///
+/// # Arguments
+/// * `env` - The `Environment` that stores the return types of functions.
+/// * `function_table` - The `FunctionTable` that stores the parameter types of functions.
+/// * `interner` - The `Interner` used to get the id of the name of each builtin.
+pub fn define_builtins(
+    env: &mut Environment<Type>,
+    function_table: &mut FunctionTable,
+    interner: &mut Interner<String>,
+) {
+    for builtin in Builtin::ALL.iter() {
+        let id = interner.insert(builtin.name().to_string());
+        env.define(id, builtin.return_type());
+        function_table.add_function_definition(id, builtin.parameter_types());
+    }
+}
@@ -0,0 +1,925 @@
+use std::{collections::HashSet, fmt::Write};
+
+use crate::{
+    builtins::Builtin,
+    error::{Error, SourceMap, Span},
+    interner::Interner,
+    linker::check_duplicate_definitions,
+    parser::{
+        expression::{BinaryOperation, UnaryOperation},
+        typed_ast::{TypedFunction, TypedProgram, TypedPrototype},
+        typed_expression::{TypedApplication, TypedBinary, TypedExpression, TypedExpressionKind},
+    },
+    semantic_analyzer::types::Type,
+};
+
+use super::PANIC_EXIT_CODE;
+
+/// The C functions that the runtime calls, along with their prototypes.
+/// No header of the C standard library is included, since the `extern`
+/// declarations of the programs may declare the same functions with the
+/// types of Envy, like they do for LLVM. A function that is declared by
+/// the programs is called through their prototype instead.
+const RUNTIME_FUNCTIONS: [(&str, &str); 3] = [
+    ("atoll", "long long atoll(const char *);"),
+    ("dprintf", "int dprintf(int, const char *, ...);"),
+    ("exit", "void exit(int);"),
+];
+
+/// The definitions that the generated functions rely on. The arithmetic
+/// is done on unsigned integers whenever it can wrap around, since signed
+/// overflow is undefined in C.
+const RUNTIME: &str = r#"static int envy_argc;
+static char **envy_argv;
+
+static void envy_panic(const char *file, long long line, long long column, const char *message)
+{
+    dprintf(2, "%s:%lld:%lld: %s\n", file, line, column, message);
+    exit(ENVY_PANIC_EXIT_CODE);
+}
+
+static int64_t envy_arg(int64_t index)
+{
+    return index >= 0 && index < envy_argc - 1 ? atoll(envy_argv[index + 1]) : 0;
+}
+
+static int64_t envy_wrapping_add(int64_t left, int64_t right)
+{
+    return (int64_t)((uint64_t)left + (uint64_t)right);
+}
+
+static int64_t envy_wrapping_subtract(int64_t left, int64_t right)
+{
+    return (int64_t)((uint64_t)left - (uint64_t)right);
+}
+
+static int64_t envy_wrapping_multiply(int64_t left, int64_t right)
+{
+    return (int64_t)((uint64_t)left * (uint64_t)right);
+}
+
+static int64_t envy_wrapping_negate(int64_t value)
+{
+    return (int64_t)(0 - (uint64_t)value);
+}
+
+static int64_t envy_wrapping_divide(int64_t left, int64_t right)
+{
+    return right == -1 ? envy_wrapping_negate(left) : left / right;
+}
+
+static int8_t envy_wrapping_char_add(int8_t left, int8_t right)
+{
+    return (int8_t)(uint8_t)(left + right);
+}
+
+static bool envy_add_overflows(int64_t left, int64_t right)
+{
+    return right > 0 ? left > INT64_MAX - right : left < INT64_MIN - right;
+}
+
+static bool envy_subtract_overflows(int64_t left, int64_t right)
+{
+    return right < 0 ? left > INT64_MAX + right : left < INT64_MIN + right;
+}
+
+static bool envy_multiply_overflows(int64_t left, int64_t right)
+{
+    if (left == 0 || right == 0) {
+        return false;
+    }
+
+    if (left > 0) {
+        return right > 0 ? left > INT64_MAX / right : right < INT64_MIN / left;
+    }
+
+    return right > 0 ? left < INT64_MIN / right : left < INT64_MAX / right;
+}
+
+static bool envy_divide_overflows(int64_t left, int64_t right)
+{
+    return left == INT64_MIN && right == -1;
+}
+
+static bool envy_char_overflows(int value)
+{
+    return value < INT8_MIN || value > INT8_MAX;
+}
+
+static int64_t envy_saturating_add(int64_t left, int64_t right)
+{
+    if (!envy_add_overflows(left, right)) {
+        return left + right;
+    }
+
+    return right > 0 ? INT64_MAX : INT64_MIN;
+}
+"#;
+
+/// The label at the end of every function that each `return` jumps to.
+const RETURN_LABEL: &str = "envy_return";
+/// The variable that holds the value returned by a function.
+const RESULT_VARIABLE: &str = "envy_result";
+
+/// Lowers type checked programs to a single C99 source file, linking them
+/// together like the JIT does. The generated code gives the same results
+/// and stops with the same runtime errors as the code generated by LLVM.
+///
+/// The functions of the programs are mangled, so that they never clash
+/// with the C standard library, while the `extern` declarations that are
+/// not defined by one of the programs become prototypes of C functions.
+/// Returns an error for each function that is defined more than once, or
+/// if none of the programs define a `main` function that can be run.
+///
+/// # Arguments
+/// * `programs` - The type checked programs.
+/// * `interner` - The `Interner` of the names in the programs.
+/// * `source_map` - The `SourceMap` of the files of the programs.
+/// * `checked_arithmetic` - Whether integer arithmetic panics on overflow.
+pub fn generate_c(
+    programs: &[&TypedProgram],
+    interner: &Interner<String>,
+    source_map: &SourceMap,
+    checked_arithmetic: bool,
+) -> Result<String, Vec<Error>> {
+    check_duplicate_definitions(programs, interner)?;
+    let functions = programs
+        .iter()
+        .flat_map(|program| program.functions.iter())
+        .collect::<Vec<_>>();
+    let main_function = functions
+        .iter()
+        .find(|function| is_entry_point(&function.prototype, interner))
+        .ok_or_else(|| vec![Error::MissingEntryPoint])?;
+    let mut generator = CGenerator {
+        interner,
+        source_map,
+        checked_arithmetic,
+        functions: functions
+            .iter()
+            .map(|function| function.prototype.name)
+            .collect(),
+        externs: HashSet::new(),
+    };
+
+    let mut extern_prototypes = String::new();
+    for extern_declaration in programs
+        .iter()
+        .flat_map(|program| program.extern_declarations.iter())
+    {
+        let name = extern_declaration.name;
+        if generator.functions.contains(&name) || !generator.externs.insert(name) {
+            continue;
+        }
+
+        let parameter_types = extern_declaration
+            .parameters
+            .iter()
+            .map(|(ty, _)| c_type(*ty).to_string())
+            .collect::<Vec<_>>();
+        writeln!(
+            extern_prototypes,
+            "{} {}({});",
+            c_type(extern_declaration.return_type.0),
+            interner.get(name),
+            parameter_list(parameter_types)
+        )
+        .unwrap();
+    }
+
+    let mut output = String::from("#include <stdbool.h>\n#include <stdint.h>\n\n");
+    writeln!(output, "#define ENVY_PANIC_EXIT_CODE {}\n", PANIC_EXIT_CODE).unwrap();
+    for (name, prototype) in RUNTIME_FUNCTIONS.iter() {
+        if !generator.is_extern(name) {
+            writeln!(output, "{}", prototype).unwrap();
+        }
+    }
+
+    output.push_str(&extern_prototypes);
+    output.push('\n');
+    output.push_str(RUNTIME);
+
+    output.push('\n');
+    for function in &functions {
+        writeln!(
+            output,
+            "{};",
+            generator.prototype(&function.prototype, false)
+        )
+        .unwrap();
+    }
+
+    for function in &functions {
+        output.push('\n');
+        output.push_str(&generator.generate_function(function));
+    }
+
+    output.push('\n');
+    output.push_str(&generator.generate_entry_point(&main_function.prototype));
+    Ok(output)
+}
+
+/// Struct that lowers the functions of the programs to C.
+struct CGenerator<'a> {
+    interner: &'a Interner<String>,
+    source_map: &'a SourceMap,
+    checked_arithmetic: bool,
+    /// The ids of the names of the functions defined by the programs.
+    functions: HashSet<usize>,
+    /// The ids of the names of the C functions declared with `extern`.
+    externs: HashSet<usize>,
+}
+
+/// The function that is being lowered.
+struct FunctionContext {
+    return_type: Type,
+    /// The declarations of the variables and the temporaries, which are
+    /// placed at the start of the function so that every `goto` only
+    /// jumps over statements.
+    declarations: Vec<String>,
+    /// The ids of the names of the declared variables. Like in the LLVM
+    /// code, a variable lives until the function returns, so every `let`
+    /// with the same name in a function assigns the same variable.
+    variables: HashSet<usize>,
+    temporary_count: usize,
+    /// The number of `goto` statements that jump to the label at the end
+    /// of the function. Like the return blocks of the LLVM `FunctionContext`,
+    /// each of them stores its value in the result before jumping.
+    return_count: usize,
+    body: String,
+    depth: usize,
+}
+
+impl<'a> CGenerator<'a> {
+    /// Determines whether a C function with the given name is declared with `extern`.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the function.
+    fn is_extern(&self, name: &str) -> bool {
+        self.externs
+            .iter()
+            .any(|extern_name| self.interner.get(*extern_name) == name)
+    }
+
+    /// Gets the prototype of a function, such as `int64_t envy_fn_add(int64_t, int64_t)`.
+    ///
+    /// # Arguments
+    /// * `prototype` - The `TypedPrototype` of the function.
+    /// * `has_names` - Whether the names of the parameters are included.
+    fn prototype(&self, prototype: &TypedPrototype, has_names: bool) -> String {
+        let parameters = prototype
+            .parameters
+            .iter()
+            .map(|parameter| {
+                if has_names {
+                    format!(
+                        "{} {}",
+                        c_type(parameter.ty),
+                        variable_name(self.interner.get(parameter.name))
+                    )
+                } else {
+                    c_type(parameter.ty).to_string()
+                }
+            })
+            .collect::<Vec<_>>();
+        format!(
+            "{} {}({})",
+            c_type(prototype.return_type),
+            function_name(self.interner.get(prototype.name)),
+            parameter_list(parameters)
+        )
+    }
+
+    fn generate_function(&mut self, function: &TypedFunction) -> String {
+        let mut context = FunctionContext {
+            return_type: function.prototype.return_type,
+            declarations: vec![],
+            variables: HashSet::new(),
+            temporary_count: 0,
+            return_count: 0,
+            body: String::new(),
+            depth: 1,
+        };
+
+        for parameter in &function.prototype.parameters {
+            context.variables.insert(parameter.name);
+        }
+
+        if context.return_type != Type::Void {
+            context.declare(context.return_type, RESULT_VARIABLE);
+        }
+
+        let value = self.compile_expression(&function.body, &mut context);
+        if let (Some(value), false) = (value, context.return_type == Type::Void) {
+            context.line(format!("{} = {};", RESULT_VARIABLE, value));
+        }
+
+        let mut output = format!("{}\n{{\n", self.prototype(&function.prototype, true));
+        for declaration in &context.declarations {
+            writeln!(output, "    {}", declaration).unwrap();
+        }
+
+        if !context.declarations.is_empty() && !context.body.is_empty() {
+            output.push('\n');
+        }
+
+        output.push_str(&context.body);
+        if context.return_count > 0 {
+            writeln!(output, "{}:", RETURN_LABEL).unwrap();
+        }
+
+        if context.return_type == Type::Void {
+            output.push_str("    return;\n}\n");
+        } else {
+            writeln!(output, "    return {};\n}}", RESULT_VARIABLE).unwrap();
+        }
+
+        output
+    }
+
+    /// Generates the `main` function that is called by the C runtime.
+    /// It stores the command-line arguments so that they can be accessed by
+    /// the builtins, calls the `main` function defined by the user, and
+    /// converts its result into the exit code of the program.
+    ///
+    /// # Arguments
+    /// * `prototype` - The `TypedPrototype` of the `main` function defined by the user.
+    fn generate_entry_point(&self, prototype: &TypedPrototype) -> String {
+        let user_main = function_name(self.interner.get(prototype.name));
+        let mut output = String::from("int main(int argc, char **argv)\n{\n");
+        output.push_str("    envy_argc = argc;\n    envy_argv = argv;\n");
+        if prototype.return_type == Type::Int {
+            writeln!(output, "    return (int){}();", user_main).unwrap();
+        } else {
+            writeln!(output, "    {}();\n    return 0;", user_main).unwrap();
+        }
+
+        output.push_str("}\n");
+        output
+    }
+
+    /// Lowers an expression to statements in the function, and returns the
+    /// C expression of its value. Every value that is computed is stored in
+    /// a temporary, so that the expressions are evaluated in order. Returns
+    /// `None` if the expression has the type `Void` or `Never`.
+    ///
+    /// # Arguments
+    /// * `expression` - The expression to lower.
+    /// * `context` - The function that is being lowered.
+    fn compile_expression(
+        &mut self,
+        expression: &TypedExpression,
+        context: &mut FunctionContext,
+    ) -> Option<String> {
+        let span = expression.0;
+        let value = match expression.1 {
+            TypedExpressionKind::Int(value) if value == i64::MIN => String::from("INT64_MIN"),
+            TypedExpressionKind::Int(value) => format!("INT64_C({})", value),
+            TypedExpressionKind::Float(value) => float_literal(value),
+            TypedExpressionKind::Boolean(value) => value.to_string(),
+            TypedExpressionKind::Char(value) => format!("(int8_t){}", value as u8 as i8),
+            TypedExpressionKind::Identifier(ref identifier) => {
+                let variable = variable_name(self.interner.get(identifier.id));
+                context.assign_temporary(identifier.ty, variable)
+            }
+            TypedExpressionKind::Unary(ref unary) => {
+                let value = self.compile_operand(&unary.expression, context);
+                let value = match (unary.operation, unary.ty) {
+                    (UnaryOperation::Plus, _) => value,
+                    (UnaryOperation::Minus, Type::Float) => format!("-({})", value),
+                    (UnaryOperation::Minus, _) if self.checked_arithmetic => {
+                        self.compile_panic_if(
+                            &format!("{} == INT64_MIN", value),
+                            span,
+                            "attempt to negate with overflow",
+                            context,
+                        );
+                        format!("-({})", value)
+                    }
+                    (UnaryOperation::Minus, _) => format!("envy_wrapping_negate({})", value),
+                    (UnaryOperation::Not, _) => format!("!{}", value),
+                };
+
+                context.assign_temporary(unary.ty, value)
+            }
+            TypedExpressionKind::Binary(ref binary) => self.compile_binary(binary, context),
+            TypedExpressionKind::If(ref typed_if) if typed_if.else_branch.is_some() => {
+                let condition = self.compile_operand(&typed_if.condition, context);
+                let result = match typed_if.ty {
+                    Type::Void | Type::Never => None,
+                    ty => Some(context.temporary(ty)),
+                };
+
+                context.line(format!("if ({}) {{", condition));
+                context.depth += 1;
+                self.compile_branch(&typed_if.then_branch, result.as_deref(), context);
+                context.depth -= 1;
+                context.line("} else {");
+                context.depth += 1;
+                if let Some(ref else_branch) = typed_if.else_branch {
+                    self.compile_branch(else_branch, result.as_deref(), context);
+                }
+
+                context.depth -= 1;
+                context.line("}");
+                return result;
+            }
+            TypedExpressionKind::Block(ref expressions) => match expressions.split_last() {
+                Some((last, expressions)) => {
+                    for expression in expressions {
+                        self.compile_statement(expression, context);
+                    }
+
+                    return self.compile_expression(last, context);
+                }
+                None => return None,
+            },
+            TypedExpressionKind::Application(ref application) => {
+                return self.compile_application(application, context);
+            }
+            // An `if` without an `else` has the type `Void`, like `let` and `while`.
+            TypedExpressionKind::If(_)
+            | TypedExpressionKind::Let(_)
+            | TypedExpressionKind::While(_) => {
+                self.compile_statement(expression, context);
+                return None;
+            }
+            TypedExpressionKind::Return(ref value) => {
+                let value = value
+                    .as_ref()
+                    .and_then(|value| self.compile_expression(value, context));
+                if let (Some(value), false) = (value, context.return_type == Type::Void) {
+                    context.line(format!("{} = {};", RESULT_VARIABLE, value));
+                }
+
+                context.line(format!("goto {};", RETURN_LABEL));
+                context.return_count += 1;
+                return None;
+            }
+            TypedExpressionKind::Panic(message) => {
+                let panic = self.compile_panic(span, self.interner.get(message));
+                context.line(panic);
+                return None;
+            }
+            TypedExpressionKind::Assert(ref condition) => {
+                let condition = self.compile_operand(condition, context);
+                self.compile_panic_if(
+                    &format!("!{}", condition),
+                    span,
+                    "assertion failed",
+                    context,
+                );
+                return None;
+            }
+            TypedExpressionKind::AssertEq(ref assert_eq) => {
+                let left = self.compile_operand(&assert_eq.left, context);
+                let right = self.compile_operand(&assert_eq.right, context);
+                self.compile_panic_if(
+                    &format!("{} != {}", left, right),
+                    span,
+                    "assertion failed: left == right",
+                    context,
+                );
+                return None;
+            }
+            // Programs with syntax errors are never compiled.
+            TypedExpressionKind::Error => unreachable!(),
+        };
+
+        Some(value)
+    }
+
+    /// Lowers an expression whose value is used by another expression. An
+    /// operand of type `Never` jumps away before its value would be used,
+    /// so any value can take its place.
+    ///
+    /// # Arguments
+    /// * `expression` - The expression to lower.
+    /// * `context` - The function that is being lowered.
+    fn compile_operand(
+        &mut self,
+        expression: &TypedExpression,
+        context: &mut FunctionContext,
+    ) -> String {
+        self.compile_expression(expression, context)
+            .unwrap_or_else(|| String::from("0"))
+    }
+
+    /// Lowers an expression whose value is not used.
+    ///
+    /// # Arguments
+    /// * `expression` - The expression to lower.
+    /// * `context` - The function that is being lowered.
+    fn compile_statement(&mut self, expression: &TypedExpression, context: &mut FunctionContext) {
+        match expression.1 {
+            TypedExpressionKind::Let(ref typed_let) => {
+                let ty = typed_let.expression.1.get_type();
+                let value = self.compile_expression(&typed_let.expression, context);
+                if let Some(value) = value {
+                    let variable = context.variable(typed_let.name.1.id, ty, self.interner);
+                    context.line(format!("{} = {};", variable, value));
+                }
+            }
+            TypedExpressionKind::While(ref typed_while) => {
+                context.line("for (;;) {");
+                context.depth += 1;
+                let condition = self.compile_operand(&typed_while.condition, context);
+                context.line(format!("if (!{}) {{", condition));
+                context.line("    break;");
+                context.line("}");
+                self.compile_statement(&typed_while.expression, context);
+                context.depth -= 1;
+                context.line("}");
+            }
+            TypedExpressionKind::If(ref typed_if) if typed_if.else_branch.is_none() => {
+                let condition = self.compile_operand(&typed_if.condition, context);
+                context.line(format!("if ({}) {{", condition));
+                context.depth += 1;
+                self.compile_statement(&typed_if.then_branch, context);
+                context.depth -= 1;
+                context.line("}");
+            }
+            TypedExpressionKind::Block(ref expressions) => {
+                for expression in expressions {
+                    self.compile_statement(expression, context);
+                }
+            }
+            _ => {
+                self.compile_expression(expression, context);
+            }
+        }
+    }
+
+    /// Lowers a branch of an `if`, storing its value in the result of the `if`.
+    ///
+    /// # Arguments
+    /// * `branch` - The branch to lower.
+    /// * `result` - The temporary that holds the value of the `if`, if it has one.
+    /// * `context` - The function that is being lowered.
+    fn compile_branch(
+        &mut self,
+        branch: &TypedExpression,
+        result: Option<&str>,
+        context: &mut FunctionContext,
+    ) {
+        let value = self.compile_expression(branch, context);
+        if let (Some(result), Some(value)) = (result, value) {
+            context.line(format!("{} = {};", result, value));
+        }
+    }
+
+    fn compile_binary(&mut self, binary: &TypedBinary, context: &mut FunctionContext) -> String {
+        // Like in the LLVM code, both operands of `and` and `or` are evaluated.
+        let left = self.compile_operand(&binary.left, context);
+        let right = self.compile_operand(&binary.right, context);
+        let operand_type = binary.left.1.get_type();
+        let span = binary.operation_span;
+        let value = match (binary.operation, operand_type) {
+            (BinaryOperation::Plus, Type::Char) if self.checked_arithmetic => {
+                self.compile_panic_if(
+                    &format!("envy_char_overflows({} + {})", left, right),
+                    span,
+                    "attempt to add with overflow",
+                    context,
+                );
+                format!("(int8_t)({} + {})", left, right)
+            }
+            (BinaryOperation::Plus, Type::Char) => {
+                format!("envy_wrapping_char_add({}, {})", left, right)
+            }
+            (BinaryOperation::Divide, Type::Int) if self.checked_arithmetic => {
+                self.compile_panic_if(
+                    &format!("{} == 0", right),
+                    span,
+                    "attempt to divide by zero",
+                    context,
+                );
+                self.compile_panic_if(
+                    &format!("envy_divide_overflows({}, {})", left, right),
+                    span,
+                    "attempt to divide with overflow",
+                    context,
+                );
+                format!("{} / {}", left, right)
+            }
+            (operation, Type::Int) if self.checked_arithmetic && is_arithmetic(operation) => {
+                let (name, message) = match operation {
+                    BinaryOperation::Plus => ("add", "attempt to add with overflow"),
+                    BinaryOperation::Minus => ("subtract", "attempt to subtract with overflow"),
+                    _ => ("multiply", "attempt to multiply with overflow"),
+                };
+
+                self.compile_panic_if(
+                    &format!("envy_{}_overflows({}, {})", name, left, right),
+                    span,
+                    message,
+                    context,
+                );
+                format!("{} {} {}", left, operator(operation), right)
+            }
+            (operation, Type::Int) if is_arithmetic(operation) => {
+                let name = match operation {
+                    BinaryOperation::Plus => "add",
+                    BinaryOperation::Minus => "subtract",
+                    BinaryOperation::Multiply => "multiply",
+                    _ => "divide",
+                };
+
+                format!("envy_wrapping_{}({}, {})", name, left, right)
+            }
+            (operation, _) => format!("{} {} {}", left, operator(operation), right),
+        };
+
+        context.assign_temporary(binary.ty, value)
+    }
+
+    fn compile_application(
+        &mut self,
+        application: &TypedApplication,
+        context: &mut FunctionContext,
+    ) -> Option<String> {
+        let arguments = application
+            .parameters
+            .iter()
+            .map(|parameter| self.compile_operand(parameter, context))
+            .collect::<Vec<_>>();
+        let name = application.function_name.1;
+        let call = if self.functions.contains(&name) {
+            format!(
+                "{}({})",
+                function_name(self.interner.get(name)),
+                arguments.join(", ")
+            )
+        } else if self.externs.contains(&name) {
+            format!("{}({})", self.interner.get(name), arguments.join(", "))
+        } else {
+            let builtin = Builtin::from_name(self.interner.get(name)).unwrap();
+            compile_builtin(builtin, &arguments)
+        };
+
+        match application.ty {
+            Type::Void | Type::Never => {
+                context.line(format!("{};", call));
+                None
+            }
+            ty => Some(context.assign_temporary(ty, call)),
+        }
+    }
+
+    /// Adds a statement that panics when the condition holds.
+    ///
+    /// # Arguments
+    /// * `condition` - The C expression of the condition.
+    /// * `span` - The `Span` reported as the location of the panic.
+    /// * `message` - The message printed after the location.
+    /// * `context` - The function that is being lowered.
+    fn compile_panic_if(
+        &self,
+        condition: &str,
+        span: Span,
+        message: &str,
+        context: &mut FunctionContext,
+    ) {
+        context.line(format!("if ({}) {{", condition));
+        context.line(format!("    {}", self.compile_panic(span, message)));
+        context.line("}");
+    }
+
+    /// Gets the statement that calls the panic routine with the location of
+    /// the given span. The file name and message are written as string literals.
+    ///
+    /// # Arguments
+    /// * `span` - The `Span` reported as the location of the panic.
+    /// * `message` - The message printed after the location.
+    fn compile_panic(&self, span: Span, message: &str) -> String {
+        let (line, column) = self.source_map.line_column(span.file_id, span.start);
+        format!(
+            "envy_panic({}, {}, {}, {});",
+            string_literal(self.source_map.name(span.file_id)),
+            line,
+            column,
+            string_literal(message)
+        )
+    }
+}
+
+impl FunctionContext {
+    /// Adds a line to the body of the function at the current depth.
+    ///
+    /// # Arguments
+    /// * `line` - The line, without its indentation.
+    fn line(&mut self, line: impl AsRef<str>) {
+        writeln!(self.body, "{:1$}{2}", "", self.depth * 4, line.as_ref()).unwrap();
+    }
+
+    /// Declares a variable at the start of the function.
+    ///
+    /// # Arguments
+    /// * `ty` - The type of the variable.
+    /// * `name` - The name of the variable in C.
+    fn declare(&mut self, ty: Type, name: &str) {
+        self.declarations.push(format!("{} {};", c_type(ty), name));
+    }
+
+    /// Declares a new temporary and returns its name.
+    ///
+    /// # Arguments
+    /// * `ty` - The type of the value held by the temporary.
+    fn temporary(&mut self, ty: Type) -> String {
+        let name = format!("envy_t{}", self.temporary_count);
+        self.temporary_count += 1;
+        self.declare(ty, &name);
+        name
+    }
+
+    /// Stores a value in a new temporary and returns the name of the temporary.
+    ///
+    /// # Arguments
+    /// * `ty` - The type of the value.
+    /// * `value` - The C expression of the value.
+    fn assign_temporary(&mut self, ty: Type, value: String) -> String {
+        let temporary = self.temporary(ty);
+        self.line(format!("{} = {};", temporary, value));
+        temporary
+    }
+
+    /// Gets the name of a variable in C, declaring it if needed.
+    ///
+    /// # Arguments
+    /// * `id` - The id of the name of the variable.
+    /// * `ty` - The type of the variable.
+    /// * `interner` - The `Interner` of the name of the variable.
+    fn variable(&mut self, id: usize, ty: Type, interner: &Interner<String>) -> String {
+        let name = variable_name(interner.get(id));
+        if self.variables.insert(id) {
+            self.declare(ty, &name);
+        }
+
+        name
+    }
+}
+
+/// Determines whether a binary operation computes a number instead of a `Boolean`.
+///
+/// # Arguments
+/// * `operation` - The operation.
+fn is_arithmetic(operation: BinaryOperation) -> bool {
+    matches!(
+        operation,
+        BinaryOperation::Plus
+            | BinaryOperation::Minus
+            | BinaryOperation::Multiply
+            | BinaryOperation::Divide
+    )
+}
+
+/// Gets the C expression of a call to a function that is provided by the compiler.
+///
+/// # Arguments
+/// * `builtin` - The `Builtin` being called.
+/// * `arguments` - The C expressions of the arguments passed to the builtin.
+fn compile_builtin(builtin: Builtin, arguments: &[String]) -> String {
+    match builtin {
+        // The first argument is always the name of the program, which is skipped.
+        Builtin::ArgCount => String::from("(int64_t)envy_argc - 1"),
+        Builtin::Arg => format!("envy_arg({})", arguments[0]),
+        Builtin::WrappingAdd => format!("envy_wrapping_add({}, {})", arguments[0], arguments[1]),
+        Builtin::SaturatingAdd => {
+            format!("envy_saturating_add({}, {})", arguments[0], arguments[1])
+        }
+    }
+}
+
+/// Determines whether the given prototype is the entry point of the program.
+/// The entry point must be named `main`, take no parameters and return
+/// either `Void` or an `Int` exit code.
+///
+/// # Arguments
+/// * `prototype` - The `TypedPrototype` to check.
+/// * `interner` - The `Interner` of the name of the function.
+fn is_entry_point(prototype: &TypedPrototype, interner: &Interner<String>) -> bool {
+    interner.get(prototype.name) == "main"
+        && prototype.parameters.is_empty()
+        && matches!(prototype.return_type, Type::Void | Type::Int)
+}
+
+/// Gets the mangled name of a function defined by the programs. The names
+/// of the functions start with `envy_fn_`, so that they never clash with
+/// the C standard library or the C keywords.
+///
+/// # Arguments
+/// * `name` - The name of the function.
+fn function_name(name: &str) -> String {
+    format!("envy_fn_{}", mangle(name))
+}
+
+/// Gets the name of a variable in C, which starts with `envy_v_`.
+///
+/// # Arguments
+/// * `name` - The name of the variable.
+fn variable_name(name: &str) -> String {
+    format!("envy_v_{}", mangle(name))
+}
+
+/// Turns a name into a valid C identifier. Letters and digits are kept,
+/// underscores are doubled and every other byte is written as an
+/// underscore followed by its value in hexadecimal, so that different
+/// names are never mangled into the same identifier.
+///
+/// # Arguments
+/// * `name` - The name to mangle.
+fn mangle(name: &str) -> String {
+    let mut mangled = String::new();
+    for byte in name.bytes() {
+        match byte {
+            b'_' => mangled.push_str("__"),
+            byte if byte.is_ascii_alphanumeric() => mangled.push(byte as char),
+            byte => write!(mangled, "_{:02x}", byte).unwrap(),
+        }
+    }
+
+    mangled
+}
+
+/// Gets the C type of a type. `Never` is only the return type of functions
+/// that do not return, so it is treated like `Void`.
+///
+/// # Arguments
+/// * `ty` - The type.
+fn c_type(ty: Type) -> &'static str {
+    match ty {
+        Type::Int => "int64_t",
+        Type::Float => "double",
+        Type::Boolean => "bool",
+        Type::Char => "int8_t",
+        Type::Void | Type::Never => "void",
+        // Programs with type errors are never compiled.
+        Type::Error => unreachable!(),
+    }
+}
+
+/// Joins the parameters of a prototype, which is `void` when there are none.
+///
+/// # Arguments
+/// * `parameters` - The parameters.
+fn parameter_list(parameters: Vec<String>) -> String {
+    if parameters.is_empty() {
+        String::from("void")
+    } else {
+        parameters.join(", ")
+    }
+}
+
+/// Gets the C operator of a binary operation.
+///
+/// # Arguments
+/// * `operation` - The operation.
+fn operator(operation: BinaryOperation) -> &'static str {
+    match operation {
+        BinaryOperation::Plus => "+",
+        BinaryOperation::Minus => "-",
+        BinaryOperation::Multiply => "*",
+        BinaryOperation::Divide => "/",
+        BinaryOperation::Equals => "==",
+        BinaryOperation::LessThan => "<",
+        BinaryOperation::GreaterThan => ">",
+        BinaryOperation::LessThanEquals => "<=",
+        BinaryOperation::GreaterThanEquals => ">=",
+        BinaryOperation::And => "&&",
+        BinaryOperation::Or => "||",
+    }
+}
+
+/// Gets the C literal of a float, which is written with every digit needed
+/// to read back the same value.
+///
+/// # Arguments
+/// * `value` - The value of the float.
+fn float_literal(value: f64) -> String {
+    if value.is_nan() {
+        String::from("(0.0 / 0.0)")
+    } else if value.is_infinite() {
+        format!("({}1.0 / 0.0)", if value < 0.0 { "-" } else { "" })
+    } else {
+        format!("{:?}", value)
+    }
+}
+
+/// Gets the C literal of a string. Every byte that is not printable ASCII
+/// is escaped in octal, along with `?` so that no trigraph is formed.
+///
+/// # Arguments
+/// * `string` - The string.
+fn string_literal(string: &str) -> String {
+    let mut literal = String::from("\"");
+    for byte in string.bytes() {
+        match byte {
+            b'"' | b'\\' | b'?' => write!(literal, "\\{}", byte as char).unwrap(),
+            b' '..=b'~' => literal.push(byte as char),
+            byte => write!(literal, "\\{:03o}", byte).unwrap(),
+        }
+    }
+
+    literal.push('"');
+    literal
+}
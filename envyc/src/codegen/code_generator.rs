@@ -1,55 +1,84 @@
 use inkwell::{
     builder::Builder,
     context::Context,
-    module::Module,
-    types::{BasicType, BasicTypeEnum},
-    values::{BasicValue, BasicValueEnum, FunctionValue, PointerValue},
-    FloatPredicate, IntPredicate,
+    module::{Linkage, Module},
+    types::{BasicType, BasicTypeEnum, FunctionType},
+    values::{BasicValue, BasicValueEnum, FunctionValue, GlobalValue, IntValue, PointerValue},
+    AddressSpace, FloatPredicate, IntPredicate,
 };
 
 use crate::{
+    builtins::Builtin,
     environment::Environment,
-    error::Error,
+    error::{Error, FileId, SourceMap, Span},
     interner::Interner,
     parser::{
         expression::{BinaryOperation, UnaryOperation},
         typed_ast::{TypedExternDeclaration, TypedFunction, TypedProgram, TypedPrototype},
         typed_expression::{
-            TypedApplication, TypedBinary, TypedExpression, TypedExpressionKind, TypedIdentifier,
-            TypedIf, TypedLet, TypedUnary, TypedWhile,
+            TypedApplication, TypedAssertEq, TypedBinary, TypedExpression, TypedExpressionKind,
+            TypedIdentifier, TypedIf, TypedLet, TypedUnary, TypedWhile,
         },
     },
     semantic_analyzer::types::Type,
 };
 
-use super::context::FunctionContext;
+use super::{context::FunctionContext, debug_info::DebugInfo, PANIC_EXIT_CODE};
 
 pub struct CodeGenerator<'a, 'b, 'ctx> {
     context: &'ctx Context,
     module: &'a Module<'ctx>,
     builder: &'a Builder<'ctx>,
     interner: &'b mut Interner<String>,
+    /// The source of the files, used to find the location of panics.
+    source_map: &'b SourceMap,
     env: &'a mut Environment<PointerValue<'ctx>>,
+    /// Whether integer arithmetic traps on overflow and division by zero.
+    checked_arithmetic: bool,
+    /// The builder of the debug information, if it is enabled.
+    debug_info: Option<DebugInfo<'b, 'ctx>>,
 }
 
-impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
+impl<'a, 'b, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
     pub fn new(
         context: &'ctx Context,
         module: &'a Module<'ctx>,
         builder: &'a Builder<'ctx>,
         interner: &'b mut Interner<String>,
+        source_map: &'b SourceMap,
         env: &'a mut Environment<PointerValue<'ctx>>,
+        checked_arithmetic: bool,
     ) -> Self {
         Self {
             context,
             module,
             builder,
             interner,
+            source_map,
             env,
+            checked_arithmetic,
+            debug_info: None,
         }
     }
 
-    pub fn generate_program(&mut self, program: &TypedProgram<'c>) -> Result<(), Vec<Error<'c>>> {
+    /// Enables the generation of DWARF debug information for the file,
+    /// including the location of every expression and the variables of
+    /// every function.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the file that the program is from.
+    /// * `is_optimized` - Whether the module is optimized.
+    pub fn enable_debug_info(&mut self, file_id: FileId, is_optimized: bool) {
+        self.debug_info = Some(DebugInfo::new(
+            self.context,
+            self.module,
+            self.source_map,
+            file_id,
+            is_optimized,
+        ));
+    }
+
+    pub fn generate_program(&mut self, program: &TypedProgram) -> Result<(), Vec<Error>> {
         let mut errors = vec![];
         for extern_declaration in &program.extern_declarations {
             if let Err(error) = self.generate_extern(extern_declaration) {
@@ -72,6 +101,19 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
                 }
             }
 
+            let main_function = program
+                .functions
+                .iter()
+                .find(|function| self.is_entry_point(&function.prototype));
+            if let Some(main_function) = main_function {
+                self.builder.unset_current_debug_location();
+                self.generate_entry_point(&main_function.prototype);
+            }
+
+            if let Some(ref debug_info) = self.debug_info {
+                debug_info.finalize();
+            }
+
             if !errors.is_empty() {
                 Err(errors)
             } else {
@@ -82,8 +124,8 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
 
     fn generate_extern(
         &mut self,
-        extern_declaration: &TypedExternDeclaration<'c>,
-    ) -> Result<(), Error<'c>> {
+        extern_declaration: &TypedExternDeclaration,
+    ) -> Result<(), Error> {
         let parameter_types = extern_declaration
             .parameters
             .iter()
@@ -106,7 +148,7 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
         Ok(())
     }
 
-    fn generate_prototype(&mut self, prototype: &TypedPrototype<'c>) -> Result<(), Error<'c>> {
+    fn generate_prototype(&mut self, prototype: &TypedPrototype) -> Result<(), Error> {
         let parameter_types = prototype
             .parameters
             .iter()
@@ -120,31 +162,100 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
             convert_type(prototype.return_type, self.context).fn_type(&parameter_types, false)
         };
 
+        let function_name = self.get_symbol_name(prototype.name);
         self.module
-            .add_function(&self.interner.get(prototype.name), function_type, None);
+            .add_function(&function_name, function_type, None);
         Ok(())
     }
 
-    fn generate_function(&mut self, defined_function: &TypedFunction<'c>) -> Result<(), Error<'c>> {
+    /// Generates the `main` function that is called by the C runtime.
+    /// It stores the command-line arguments so that they can be accessed by
+    /// the builtins, calls the `main` function defined by the user, and
+    /// converts its result into the exit code of the program.
+    ///
+    /// # Arguments
+    /// * `prototype` - The `TypedPrototype` of the `main` function defined by the user.
+    fn generate_entry_point(&mut self, prototype: &TypedPrototype) {
+        let user_main = self
+            .module
+            .get_function(&self.get_symbol_name(prototype.name))
+            .unwrap();
+        let i32_type = self.context.i32_type();
+        let argv_type = self
+            .context
+            .i8_type()
+            .ptr_type(AddressSpace::Generic)
+            .ptr_type(AddressSpace::Generic);
+        let entry_point_type = i32_type.fn_type(&[i32_type.into(), argv_type.into()], false);
+        let entry_point = self.module.add_function("main", entry_point_type, None);
+        let entry_block = self.context.append_basic_block(entry_point, "entry");
+        self.builder.position_at_end(entry_block);
+
+        let (argc, argv) = self.get_argument_globals();
+        self.builder.build_store(
+            argc.as_pointer_value(),
+            entry_point.get_nth_param(0).unwrap(),
+        );
+        self.builder.build_store(
+            argv.as_pointer_value(),
+            entry_point.get_nth_param(1).unwrap(),
+        );
+
+        let result = self.builder.build_call(user_main, &[], "call_main");
+        let exit_code = if prototype.return_type == Type::Int {
+            let value = result.try_as_basic_value().left().unwrap().into_int_value();
+            self.builder
+                .build_int_truncate(value, i32_type, "exit_code")
+        } else {
+            i32_type.const_zero()
+        };
+
+        self.builder.build_return(Some(&exit_code));
+    }
+
+    fn generate_function(&mut self, defined_function: &TypedFunction) -> Result<(), Error> {
         let function = self
             .module
-            .get_function(self.interner.get(defined_function.prototype.name))
-            .ok_or(Error::UnknownFunction(defined_function.prototype.span))?;
+            .get_function(&self.get_symbol_name(defined_function.prototype.name))
+            .ok_or(Error::UnknownFunction {
+                span: defined_function.prototype.span,
+                suggestion: None,
+            })?;
         let entry_block = self.context.append_basic_block(function, "entry");
         let return_block = self.context.append_basic_block(function, "return");
         self.builder.position_at_end(entry_block);
+        if let Some(ref mut debug_info) = self.debug_info {
+            debug_info.create_function(
+                function,
+                &defined_function.prototype,
+                self.interner.get(defined_function.prototype.name),
+                &function.get_name().to_string_lossy(),
+            );
+        }
 
+        self.set_debug_location(defined_function.prototype.span);
         self.env.new_scope();
         function
             .get_param_iter()
             .zip(defined_function.prototype.parameters.iter())
-            .map(|(llvm_param, param)| (llvm_param, param.name))
-            .for_each(|(llvm_param, param_name)| {
-                let name = self.interner.get(param_name);
+            .enumerate()
+            .for_each(|(index, (llvm_param, param))| {
+                let name = self.interner.get(param.name);
                 llvm_param.set_name(name);
                 let pointer = self.builder.build_alloca(llvm_param.get_type(), name);
                 self.builder.build_store(pointer, llvm_param);
-                self.env.define(param_name, pointer);
+                if let Some(ref debug_info) = self.debug_info {
+                    debug_info.declare_variable(
+                        pointer,
+                        name,
+                        Some(index as u32 + 1),
+                        param.ty,
+                        param.span,
+                        entry_block,
+                    );
+                }
+
+                self.env.define(param.name, pointer);
             });
 
         let mut function_context =
@@ -182,25 +293,46 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
             self.builder.build_return(None);
         }
 
+        // The blocks that follow a `panic` or a `return` can not be reached,
+        // but the ones that are not terminated yet must still end with a terminator.
+        for block in function.get_basic_blocks() {
+            if block.get_terminator().is_none() {
+                self.builder.position_at_end(block);
+                self.builder.build_unreachable();
+            }
+        }
+
         self.env.remove_top_scope();
 
-        if function.verify(true) {
+        if function.verify(false) {
             Ok(())
         } else {
-            unsafe {
-                function.delete();
-            }
+            // The function is kept in the module, since it may already be called
+            // by other functions and so that it can be inspected in the IR.
+            let message = match self.module.verify() {
+                Err(message) => message.to_string(),
+                Ok(()) => String::from("the verifier did not give a reason"),
+            };
 
-            Err(Error::LLVMFunctionFailure)
+            Err(Error::LLVMFunctionFailure {
+                name: self.interner.get(defined_function.prototype.name).clone(),
+                span: defined_function.prototype.span,
+                message,
+            })
         }
     }
 
     fn compile_expression(
         &mut self,
-        expression: &TypedExpression<'c>,
+        expression: &TypedExpression,
         current_function: FunctionValue<'ctx>,
         function_context: &mut FunctionContext<'ctx>,
-    ) -> Result<BasicValueEnum<'ctx>, Error<'c>> {
+    ) -> Result<BasicValueEnum<'ctx>, Error> {
+        // The expressions of a block set their own locations.
+        if !matches!(expression.1, TypedExpressionKind::Block(_)) {
+            self.set_debug_location(expression.0);
+        }
+
         match expression.1 {
             TypedExpressionKind::Int(value) => {
                 let int = self.context.i64_type().const_int(value.abs() as u64, false);
@@ -221,7 +353,7 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
             )),
             TypedExpressionKind::Identifier(ref inner) => self.compile_identifier(inner),
             TypedExpressionKind::Unary(ref inner) => {
-                self.compile_unary(inner, current_function, function_context)
+                self.compile_unary(inner, expression.0, current_function, function_context)
             }
             TypedExpressionKind::Binary(ref inner) => {
                 self.compile_binary(inner, current_function, function_context)
@@ -264,6 +396,7 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
                     .add_return_block(self.builder.get_insert_block().unwrap(), return_value);
                 self.builder
                     .build_unconditional_branch(function_context.return_block);
+                self.position_at_dead_block(current_function);
                 if let Some(value) = return_value {
                     Ok(value)
                 } else {
@@ -272,30 +405,84 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
                     ))
                 }
             }
+            TypedExpressionKind::Panic(message) => {
+                let message = self.interner.get(message).clone();
+                self.compile_panic(expression.0, &message);
+                self.position_at_dead_block(current_function);
+                Ok(BasicValueEnum::IntValue(
+                    self.context.i64_type().const_zero(),
+                ))
+            }
+            TypedExpressionKind::Assert(ref condition) => {
+                let condition =
+                    self.compile_expression(condition, current_function, function_context)?;
+                self.compile_assertion(
+                    condition.into_int_value(),
+                    expression.0,
+                    "assertion failed",
+                    current_function,
+                );
+                Ok(BasicValueEnum::IntValue(
+                    self.context.i64_type().const_zero(),
+                ))
+            }
+            TypedExpressionKind::AssertEq(ref inner) => {
+                self.compile_assert_eq(inner, expression.0, current_function, function_context)?;
+                Ok(BasicValueEnum::IntValue(
+                    self.context.i64_type().const_zero(),
+                ))
+            }
+            // Programs with syntax errors are never compiled.
+            TypedExpressionKind::Error => unreachable!(),
         }
     }
 
     fn compile_identifier(
         &mut self,
         identifier: &TypedIdentifier,
-    ) -> Result<BasicValueEnum<'ctx>, Error<'c>> {
+    ) -> Result<BasicValueEnum<'ctx>, Error> {
         let value = self.env.get(identifier.id).unwrap();
         Ok(self
             .builder
             .build_load(value, self.interner.get(identifier.id)))
     }
 
+    /// Compiles a unary operation. With checked arithmetic, negating the
+    /// minimum integer panics instead of wrapping around.
+    ///
+    /// # Arguments
+    /// * `unary` - The `TypedUnary` being compiled.
+    /// * `span` - The `Span` of the unary expression.
+    /// * `current_function` - The function the operation is compiled into.
+    /// * `function_context` - The context of the function being compiled.
     fn compile_unary(
         &mut self,
-        unary: &TypedUnary<'c>,
+        unary: &TypedUnary,
+        span: Span,
         current_function: FunctionValue<'ctx>,
         function_context: &mut FunctionContext<'ctx>,
-    ) -> Result<BasicValueEnum<'ctx>, Error<'c>> {
+    ) -> Result<BasicValueEnum<'ctx>, Error> {
         let expression =
             self.compile_expression(&unary.expression, current_function, function_context)?;
         let value = match (unary.operation, expression) {
             (UnaryOperation::Plus, value) => value,
             (UnaryOperation::Minus, BasicValueEnum::IntValue(value)) => {
+                if self.checked_arithmetic {
+                    let minimum = value.get_type().const_int(i64::MIN as u64, false);
+                    let is_not_minimum = self.builder.build_int_compare(
+                        IntPredicate::NE,
+                        value,
+                        minimum,
+                        "negand_not_min",
+                    );
+                    self.compile_assertion(
+                        is_not_minimum,
+                        span,
+                        "attempt to negate with overflow",
+                        current_function,
+                    );
+                }
+
                 BasicValueEnum::IntValue(self.builder.build_int_neg(value, "intneg"))
             }
             (UnaryOperation::Minus, BasicValueEnum::FloatValue(value)) => {
@@ -312,12 +499,23 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
 
     fn compile_binary(
         &mut self,
-        binary: &TypedBinary<'c>,
+        binary: &TypedBinary,
         current_function: FunctionValue<'ctx>,
         function_context: &mut FunctionContext<'ctx>,
-    ) -> Result<BasicValueEnum<'ctx>, Error<'c>> {
+    ) -> Result<BasicValueEnum<'ctx>, Error> {
         let left = self.compile_expression(&binary.left, current_function, function_context)?;
         let right = self.compile_expression(&binary.right, current_function, function_context)?;
+        if self.checked_arithmetic {
+            if let (BasicValueEnum::IntValue(left), BasicValueEnum::IntValue(right)) = (left, right)
+            {
+                if let Some(value) =
+                    self.compile_checked_binary(binary, left, right, current_function)
+                {
+                    return Ok(BasicValueEnum::IntValue(value));
+                }
+            }
+        }
+
         let value = match (binary.operation, left, right) {
             (
                 BinaryOperation::Plus,
@@ -402,12 +600,112 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
         Ok(value)
     }
 
+    /// Compiles an integer operation that traps instead of overflowing or
+    /// dividing by zero. Returns `None` if the operation can not fail.
+    ///
+    /// # Arguments
+    /// * `binary` - The `TypedBinary` being compiled.
+    /// * `left` - The compiled left operand.
+    /// * `right` - The compiled right operand.
+    /// * `current_function` - The function the operation is compiled into.
+    fn compile_checked_binary(
+        &mut self,
+        binary: &TypedBinary,
+        left: IntValue<'ctx>,
+        right: IntValue<'ctx>,
+        current_function: FunctionValue<'ctx>,
+    ) -> Option<IntValue<'ctx>> {
+        let (intrinsic, message) = match binary.operation {
+            BinaryOperation::Plus => ("sadd", "attempt to add with overflow"),
+            BinaryOperation::Minus => ("ssub", "attempt to subtract with overflow"),
+            BinaryOperation::Multiply => ("smul", "attempt to multiply with overflow"),
+            BinaryOperation::Divide => {
+                let int_type = left.get_type();
+                let is_non_zero = self.builder.build_int_compare(
+                    IntPredicate::NE,
+                    right,
+                    int_type.const_zero(),
+                    "divisor_non_zero",
+                );
+                self.compile_assertion(
+                    is_non_zero,
+                    binary.operation_span,
+                    "attempt to divide by zero",
+                    current_function,
+                );
+
+                let minimum = int_type.const_int(i64::MIN as u64, false);
+                let is_minimum =
+                    self.builder
+                        .build_int_compare(IntPredicate::EQ, left, minimum, "dividend_min");
+                let is_negative_one = self.builder.build_int_compare(
+                    IntPredicate::EQ,
+                    right,
+                    int_type.const_all_ones(),
+                    "divisor_negative_one",
+                );
+                let overflows = self
+                    .builder
+                    .build_and(is_minimum, is_negative_one, "div_overflow");
+                let no_overflow = self.builder.build_not(overflows, "div_no_overflow");
+                self.compile_assertion(
+                    no_overflow,
+                    binary.operation_span,
+                    "attempt to divide with overflow",
+                    current_function,
+                );
+
+                return Some(self.builder.build_int_signed_div(left, right, "intdiv"));
+            }
+            _ => return None,
+        };
+
+        let int_type = left.get_type();
+        let result_type = self
+            .context
+            .struct_type(&[int_type.into(), self.context.bool_type().into()], false);
+        let overflow_intrinsic = self.get_external_function(
+            &format!(
+                "llvm.{}.with.overflow.i{}",
+                intrinsic,
+                int_type.get_bit_width()
+            ),
+            result_type.fn_type(&[int_type.into(), int_type.into()], false),
+        );
+        let result = self
+            .builder
+            .build_call(overflow_intrinsic, &[left.into(), right.into()], "checked")
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_struct_value();
+        let value = self
+            .builder
+            .build_extract_value(result, 0, "checked_value")
+            .unwrap()
+            .into_int_value();
+        let overflows = self
+            .builder
+            .build_extract_value(result, 1, "overflow")
+            .unwrap()
+            .into_int_value();
+        let no_overflow = self.builder.build_not(overflows, "no_overflow");
+        self.compile_assertion(
+            no_overflow,
+            binary.operation_span,
+            message,
+            current_function,
+        );
+
+        Some(value)
+    }
+
     fn compile_if(
         &mut self,
-        typed_if: &TypedIf<'c>,
+        typed_if: &TypedIf,
         current_function: FunctionValue<'ctx>,
         function_context: &mut FunctionContext<'ctx>,
-    ) -> Result<BasicValueEnum<'ctx>, Error<'c>> {
+    ) -> Result<BasicValueEnum<'ctx>, Error> {
         let then_block = self.context.append_basic_block(current_function, "ifthen");
         let else_block = self.context.append_basic_block(current_function, "ifelse");
         let end_block = self.context.append_basic_block(current_function, "ifend");
@@ -421,6 +719,7 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
         let then_branch =
             self.compile_expression(&typed_if.then_branch, current_function, function_context)?;
 
+        let then_end_block = self.builder.get_insert_block().unwrap();
         if typed_if.then_branch.1.get_type() != Type::Never {
             self.builder.build_unconditional_branch(end_block);
         }
@@ -430,6 +729,7 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
             let else_branch_gen =
                 self.compile_expression(else_branch, current_function, function_context)?;
 
+            let else_end_block = self.builder.get_insert_block().unwrap();
             if else_branch.1.get_type() != Type::Never {
                 self.builder.build_unconditional_branch(end_block);
             }
@@ -441,7 +741,10 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
                 Ok(then_branch)
             } else {
                 let phi = self.builder.build_phi(then_branch.get_type(), "ifphi");
-                phi.add_incoming(&[(&then_branch, then_block), (&else_branch_gen, else_block)]);
+                phi.add_incoming(&[
+                    (&then_branch, then_end_block),
+                    (&else_branch_gen, else_end_block),
+                ]);
                 Ok(phi.as_basic_value())
             }
         } else {
@@ -456,17 +759,27 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
 
     fn compile_let(
         &mut self,
-        typed_let: &TypedLet<'c>,
+        typed_let: &TypedLet,
         current_function: FunctionValue<'ctx>,
         function_context: &mut FunctionContext<'ctx>,
-    ) -> Result<(), Error<'c>> {
+    ) -> Result<(), Error> {
         let value =
             self.compile_expression(&typed_let.expression, current_function, function_context)?;
         let id = typed_let.name.1.id;
         if self.env.get(id).is_none() {
-            let pointer = self
-                .builder
-                .build_alloca(value.get_type(), self.interner.get(id));
+            let name = self.interner.get(id);
+            let pointer = self.builder.build_alloca(value.get_type(), name);
+            if let Some(ref debug_info) = self.debug_info {
+                debug_info.declare_variable(
+                    pointer,
+                    name,
+                    None,
+                    typed_let.ty,
+                    typed_let.name.0,
+                    self.builder.get_insert_block().unwrap(),
+                );
+            }
+
             self.env.define(id, pointer);
         }
 
@@ -478,13 +791,12 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
 
     fn compile_application(
         &mut self,
-        application: &TypedApplication<'c>,
+        application: &TypedApplication,
         current_function: FunctionValue<'ctx>,
         function_context: &mut FunctionContext<'ctx>,
-    ) -> Result<BasicValueEnum<'ctx>, Error<'c>> {
-        let function_name = self.interner.get(application.function_name.1);
+    ) -> Result<BasicValueEnum<'ctx>, Error> {
+        let function_name = self.get_symbol_name(application.function_name.1);
         let function_call = format!("call_{}", function_name);
-        let function = self.module.get_function(&function_name).unwrap();
         let mut arguments = Vec::new();
         for parameter in &application.parameters {
             arguments.push(self.compile_expression(
@@ -494,6 +806,14 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
             )?);
         }
 
+        let function = match self.module.get_function(&function_name) {
+            Some(function) => function,
+            None => {
+                let builtin = Builtin::from_name(&function_name).unwrap();
+                return Ok(self.compile_builtin(builtin, &arguments, current_function));
+            }
+        };
+
         Ok(self
             .builder
             .build_call(function, &arguments, &function_call)
@@ -502,12 +822,119 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
             .unwrap_or_else(|| BasicValueEnum::IntValue(self.context.i64_type().const_zero())))
     }
 
+    /// Compiles a call to a function that is provided by the compiler.
+    ///
+    /// # Arguments
+    /// * `builtin` - The `Builtin` being called.
+    /// * `arguments` - The compiled arguments passed to the builtin.
+    /// * `current_function` - The function that the call occurs in.
+    fn compile_builtin(
+        &mut self,
+        builtin: Builtin,
+        arguments: &[BasicValueEnum<'ctx>],
+        current_function: FunctionValue<'ctx>,
+    ) -> BasicValueEnum<'ctx> {
+        let i64_type = self.context.i64_type();
+        match builtin {
+            // The first argument is always the name of the program, which is skipped.
+            Builtin::ArgCount => BasicValueEnum::IntValue(self.builder.build_int_sub(
+                self.load_argument_count(),
+                i64_type.const_int(1, false),
+                "arg_count",
+            )),
+            // LLVM integer addition already wraps around on overflow.
+            Builtin::WrappingAdd => BasicValueEnum::IntValue(self.builder.build_int_add(
+                arguments[0].into_int_value(),
+                arguments[1].into_int_value(),
+                "wrapping_add",
+            )),
+            Builtin::SaturatingAdd => {
+                let saturating_add = self.get_external_function(
+                    "llvm.sadd.sat.i64",
+                    i64_type.fn_type(&[i64_type.into(), i64_type.into()], false),
+                );
+                self.builder
+                    .build_call(
+                        saturating_add,
+                        &[arguments[0], arguments[1]],
+                        "saturating_add",
+                    )
+                    .try_as_basic_value()
+                    .left()
+                    .unwrap()
+            }
+            Builtin::Arg => {
+                let argc = self.load_argument_count();
+                let (_, argv) = self.get_argument_globals();
+                let index = arguments[0].into_int_value();
+                let position =
+                    self.builder
+                        .build_int_add(index, i64_type.const_int(1, false), "arg_position");
+                let is_positive = self.builder.build_int_compare(
+                    IntPredicate::SGE,
+                    index,
+                    i64_type.const_zero(),
+                    "arg_is_positive",
+                );
+                let is_below_count = self.builder.build_int_compare(
+                    IntPredicate::SLT,
+                    position,
+                    argc,
+                    "arg_is_below_count",
+                );
+                let in_bounds =
+                    self.builder
+                        .build_and(is_positive, is_below_count, "arg_in_bounds");
+
+                let check_block = self.builder.get_insert_block().unwrap();
+                let load_block = self
+                    .context
+                    .append_basic_block(current_function, "arg_load");
+                let end_block = self.context.append_basic_block(current_function, "arg_end");
+                self.builder
+                    .build_conditional_branch(in_bounds, load_block, end_block);
+
+                self.builder.position_at_end(load_block);
+                let argv = self
+                    .builder
+                    .build_load(argv.as_pointer_value(), "argv")
+                    .into_pointer_value();
+                let argument_pointer =
+                    unsafe { self.builder.build_gep(argv, &[position], "arg_pointer") };
+                let argument = self.builder.build_load(argument_pointer, "arg_string");
+                let atoll = self.get_external_function(
+                    "atoll",
+                    i64_type.fn_type(
+                        &[self
+                            .context
+                            .i8_type()
+                            .ptr_type(AddressSpace::Generic)
+                            .into()],
+                        false,
+                    ),
+                );
+                let value = self
+                    .builder
+                    .build_call(atoll, &[argument], "arg_value")
+                    .try_as_basic_value()
+                    .left()
+                    .unwrap();
+                self.builder.build_unconditional_branch(end_block);
+
+                self.builder.position_at_end(end_block);
+                let phi = self.builder.build_phi(i64_type, "arg");
+                phi.add_incoming(&[(&i64_type.const_zero(), check_block), (&value, load_block)]);
+                phi.as_basic_value()
+            }
+        }
+    }
+
     fn compile_while(
         &mut self,
-        typed_while: &TypedWhile<'c>,
+        typed_while: &TypedWhile,
         current_function: FunctionValue<'ctx>,
         function_context: &mut FunctionContext<'ctx>,
-    ) -> Result<(), Error<'c>> {
+    ) -> Result<(), Error> {
         let condition_check_block = self
             .context
             .append_basic_block(current_function, "condition_check");
@@ -538,6 +965,275 @@ impl<'a, 'b, 'c, 'ctx> CodeGenerator<'a, 'b, 'ctx> {
         self.builder.position_at_end(after_loop_block);
         Ok(())
     }
+
+    fn compile_assert_eq(
+        &mut self,
+        assert_eq: &TypedAssertEq,
+        span: Span,
+        current_function: FunctionValue<'ctx>,
+        function_context: &mut FunctionContext<'ctx>,
+    ) -> Result<(), Error> {
+        let left = self.compile_expression(&assert_eq.left, current_function, function_context)?;
+        let right =
+            self.compile_expression(&assert_eq.right, current_function, function_context)?;
+        let condition = match assert_eq.left.1.get_type() {
+            Type::Float => self.builder.build_float_compare(
+                FloatPredicate::OEQ,
+                left.into_float_value(),
+                right.into_float_value(),
+                "assert_eq",
+            ),
+            _ => self.builder.build_int_compare(
+                IntPredicate::EQ,
+                left.into_int_value(),
+                right.into_int_value(),
+                "assert_eq",
+            ),
+        };
+
+        self.compile_assertion(
+            condition,
+            span,
+            "assertion failed: left == right",
+            current_function,
+        );
+        Ok(())
+    }
+
+    /// Branches to a panic with the given message when the condition is false.
+    /// The builder is left positioned in the block where the condition held.
+    ///
+    /// # Arguments
+    /// * `condition` - The condition that must hold.
+    /// * `span` - The `Span` reported when the condition does not hold.
+    /// * `message` - The message reported when the condition does not hold.
+    /// * `current_function` - The function the assertion is compiled into.
+    fn compile_assertion(
+        &mut self,
+        condition: IntValue<'ctx>,
+        span: Span,
+        message: &str,
+        current_function: FunctionValue<'ctx>,
+    ) {
+        let failed_block = self
+            .context
+            .append_basic_block(current_function, "assert_failed");
+        let passed_block = self
+            .context
+            .append_basic_block(current_function, "assert_passed");
+        self.builder
+            .build_conditional_branch(condition, passed_block, failed_block);
+
+        self.builder.position_at_end(failed_block);
+        self.compile_panic(span, message);
+
+        self.builder.position_at_end(passed_block);
+    }
+
+    /// Calls the panic routine with the location of the given span.
+    /// The file name and message are embedded in the module as constant strings.
+    /// Since the call never returns, the current block is terminated afterwards.
+    ///
+    /// # Arguments
+    /// * `span` - The `Span` reported as the location of the panic.
+    /// * `message` - The message printed after the location.
+    fn compile_panic(&mut self, span: Span, message: &str) {
+        // Defining the panic routine clears the location of the instructions.
+        let panic_function = self.get_panic_function();
+        self.set_debug_location(span);
+        let i64_type = self.context.i64_type();
+        let (line, column) = self.source_map.line_column(span.file_id, span.start);
+        let file_name = self
+            .builder
+            .build_global_string_ptr(self.source_map.name(span.file_id), "panic_file");
+        let message = self
+            .builder
+            .build_global_string_ptr(message, "panic_message");
+        self.builder.build_call(
+            panic_function,
+            &[
+                file_name.as_pointer_value().into(),
+                i64_type.const_int(line as u64, false).into(),
+                i64_type.const_int(column as u64, false).into(),
+                message.as_pointer_value().into(),
+            ],
+            "",
+        );
+        self.builder.build_unreachable();
+    }
+
+    /// Positions the builder at the end of a new block without predecessors.
+    /// The code that follows an expression which terminates the current block,
+    /// such as the rest of the block in `{ panic("x") f() }`, is compiled into
+    /// it, since nothing can be added to a block after its terminator.
+    ///
+    /// # Arguments
+    /// * `current_function` - The function that the block is added to.
+    fn position_at_dead_block(&self, current_function: FunctionValue<'ctx>) {
+        let block = self.context.append_basic_block(current_function, "dead");
+        self.builder.position_at_end(block);
+    }
+
+    /// Gets the runtime routine that reports a panic, defining it if it has
+    /// not been defined in the module yet. The routine prints
+    /// `file:line:column: message` to stderr and exits with `PANIC_EXIT_CODE`.
+    /// Every module defines its own copy, which the linker merges.
+    fn get_panic_function(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function("envy_panic") {
+            return function;
+        }
+
+        let i8_pointer_type = self.context.i8_type().ptr_type(AddressSpace::Generic);
+        let i64_type = self.context.i64_type();
+        let i32_type = self.context.i32_type();
+        let void_type = self.context.void_type();
+        let panic_type = void_type.fn_type(
+            &[
+                i8_pointer_type.into(),
+                i64_type.into(),
+                i64_type.into(),
+                i8_pointer_type.into(),
+            ],
+            false,
+        );
+        let function =
+            self.module
+                .add_function("envy_panic", panic_type, Some(Linkage::LinkOnceODR));
+        let dprintf = self.get_external_function(
+            "dprintf",
+            i32_type.fn_type(&[i32_type.into(), i8_pointer_type.into()], true),
+        );
+        let exit = self.get_external_function("exit", void_type.fn_type(&[i32_type.into()], false));
+
+        // The routine has no debug information, so none of its instructions have a location.
+        self.builder.unset_current_debug_location();
+        let previous_block = self.builder.get_insert_block();
+        let entry_block = self.context.append_basic_block(function, "entry");
+        self.builder.position_at_end(entry_block);
+        let format = self
+            .builder
+            .build_global_string_ptr("%s:%lld:%lld: %s\n", "panic_format");
+        let mut arguments = vec![
+            i32_type.const_int(2, false).into(),
+            format.as_pointer_value().into(),
+        ];
+        arguments.extend(function.get_param_iter());
+        self.builder.build_call(dprintf, &arguments, "");
+        self.builder.build_call(
+            exit,
+            &[i32_type.const_int(PANIC_EXIT_CODE, false).into()],
+            "",
+        );
+        self.builder.build_unreachable();
+
+        if let Some(block) = previous_block {
+            self.builder.position_at_end(block);
+        }
+
+        function
+    }
+
+    /// Sets the location of the instructions built afterwards to the start
+    /// of the span, when debug information is enabled.
+    ///
+    /// # Arguments
+    /// * `span` - The `Span` of the source that the instructions are generated from.
+    fn set_debug_location(&self, span: Span) {
+        if let Some(ref debug_info) = self.debug_info {
+            debug_info.set_location(self.builder, span);
+        }
+    }
+
+    /// Gets the name of the LLVM symbol for the given function.
+    ///
+    /// # Arguments
+    /// * `name` - The id of the name of the function.
+    fn get_symbol_name(&self, name: usize) -> String {
+        symbol_name(self.interner.get(name))
+    }
+
+    /// Determines whether the given prototype is the entry point of the program.
+    /// The entry point must be named `main`, take no parameters and return
+    /// either `Void` or an `Int` exit code.
+    ///
+    /// # Arguments
+    /// * `prototype` - The `TypedPrototype` to check.
+    fn is_entry_point(&self, prototype: &TypedPrototype) -> bool {
+        self.interner.get(prototype.name) == "main"
+            && prototype.parameters.is_empty()
+            && matches!(prototype.return_type, Type::Void | Type::Int)
+    }
+
+    /// Gets the globals that store the command-line arguments of the program,
+    /// defining them if they have not been defined in the module yet. Every
+    /// module that uses them defines its own copy, which the linker merges,
+    /// so that a library that reads the arguments links without the module
+    /// containing the entry point.
+    fn get_argument_globals(&self) -> (GlobalValue<'ctx>, GlobalValue<'ctx>) {
+        let argc = self.module.get_global("envy_argc").unwrap_or_else(|| {
+            let argc_type = self.context.i32_type();
+            let argc = self.module.add_global(argc_type, None, "envy_argc");
+            argc.set_linkage(Linkage::LinkOnceODR);
+            argc.set_initializer(&argc_type.const_zero());
+            argc
+        });
+        let argv = self.module.get_global("envy_argv").unwrap_or_else(|| {
+            let argv_type = self
+                .context
+                .i8_type()
+                .ptr_type(AddressSpace::Generic)
+                .ptr_type(AddressSpace::Generic);
+            let argv = self.module.add_global(argv_type, None, "envy_argv");
+            argv.set_linkage(Linkage::LinkOnceODR);
+            argv.set_initializer(&argv_type.const_null());
+            argv
+        });
+
+        (argc, argv)
+    }
+
+    /// Loads the number of command-line arguments, including the name
+    /// of the program, as an `Int`.
+    fn load_argument_count(&self) -> IntValue<'ctx> {
+        let (argc, _) = self.get_argument_globals();
+        let argc = self
+            .builder
+            .build_load(argc.as_pointer_value(), "argc")
+            .into_int_value();
+        self.builder
+            .build_int_s_extend(argc, self.context.i64_type(), "argc_ext")
+    }
+
+    /// Gets an external function, such as one from the C standard library
+    /// or an LLVM intrinsic, declaring it if it has not been declared in
+    /// the module yet.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the function.
+    /// * `function_type` - The type of the function.
+    fn get_external_function(
+        &self,
+        name: &str,
+        function_type: FunctionType<'ctx>,
+    ) -> FunctionValue<'ctx> {
+        self.module
+            .get_function(name)
+            .unwrap_or_else(|| self.module.add_function(name, function_type, None))
+    }
+}
+
+/// Gets the name of the LLVM symbol for the function with the given name.
+/// The `main` function defined by the user is renamed so that the
+/// generated entry point can be called by the C runtime instead.
+///
+/// # Arguments
+/// * `name` - The name of the function.
+pub fn symbol_name(name: &str) -> String {
+    if name == "main" {
+        "envy_main".to_string()
+    } else {
+        name.to_string()
+    }
 }
 
 fn convert_type(ty: Type, context: &Context) -> Box<dyn BasicType + '_> {
@@ -0,0 +1,261 @@
+use std::path::Path;
+
+use inkwell::{
+    basic_block::BasicBlock,
+    builder::Builder,
+    context::Context,
+    debug_info::{
+        debug_metadata_version, AsDIScope, DICompileUnit, DIFile, DIFlags, DIFlagsConstants,
+        DILocation, DISubprogram, DIType, DWARFEmissionKind, DWARFSourceLanguage, DebugInfoBuilder,
+    },
+    module::{FlagBehavior, Module},
+    values::{FunctionValue, PointerValue},
+};
+
+use crate::{
+    error::{FileId, SourceMap, Span},
+    parser::typed_ast::TypedPrototype,
+    semantic_analyzer::types::Type,
+};
+
+/// The DWARF encoding of signed integers.
+const DW_ATE_SIGNED: u32 = 0x05;
+/// The DWARF encoding of floating point numbers.
+const DW_ATE_FLOAT: u32 = 0x04;
+/// The DWARF encoding of booleans.
+const DW_ATE_BOOLEAN: u32 = 0x02;
+/// The DWARF encoding of signed characters.
+const DW_ATE_SIGNED_CHAR: u32 = 0x06;
+
+/// Struct that builds the DWARF debug information of a module, so that the
+/// compiled program can be stepped through in a debugger such as gdb or lldb.
+pub struct DebugInfo<'b, 'ctx> {
+    context: &'ctx Context,
+    /// The source of the files, used to find the line and column of each span.
+    source_map: &'b SourceMap,
+    builder: DebugInfoBuilder<'ctx>,
+    compile_unit: DICompileUnit<'ctx>,
+    file: DIFile<'ctx>,
+    is_optimized: bool,
+    /// The subprogram of the function that is currently being generated.
+    subprogram: Option<DISubprogram<'ctx>>,
+}
+
+impl<'b, 'ctx> DebugInfo<'b, 'ctx> {
+    /// Creates the compile unit of the file that the module is generated from.
+    ///
+    /// # Arguments
+    /// * `context` - The LLVM context of the module.
+    /// * `module` - The module that the debug information is added to.
+    /// * `source_map` - The `SourceMap` containing the file.
+    /// * `file_id` - The id of the file.
+    /// * `is_optimized` - Whether the module is optimized.
+    pub fn new(
+        context: &'ctx Context,
+        module: &Module<'ctx>,
+        source_map: &'b SourceMap,
+        file_id: FileId,
+        is_optimized: bool,
+    ) -> Self {
+        module.add_basic_value_flag(
+            "Debug Info Version",
+            FlagBehavior::Warning,
+            context
+                .i32_type()
+                .const_int(debug_metadata_version() as u64, false),
+        );
+
+        let path = Path::new(source_map.name(file_id));
+        let file_name = path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .unwrap_or_else(|| source_map.name(file_id));
+        let directory = path
+            .parent()
+            .and_then(|directory| directory.to_str())
+            .unwrap_or("");
+        let (builder, compile_unit) = module.create_debug_info_builder(
+            true,
+            DWARFSourceLanguage::C,
+            file_name,
+            directory,
+            concat!("envyc ", env!("CARGO_PKG_VERSION")),
+            is_optimized,
+            "",
+            0,
+            "",
+            DWARFEmissionKind::Full,
+            0,
+            false,
+            false,
+        );
+
+        Self {
+            context,
+            source_map,
+            builder,
+            file: compile_unit.get_file(),
+            compile_unit,
+            is_optimized,
+            subprogram: None,
+        }
+    }
+
+    /// Creates the subprogram of a function defined in the file and attaches it
+    /// to the function. Every location created afterwards is inside this function.
+    ///
+    /// # Arguments
+    /// * `function` - The LLVM function.
+    /// * `prototype` - The `TypedPrototype` of the function.
+    /// * `name` - The name of the function in the source.
+    /// * `symbol_name` - The name of the LLVM symbol of the function.
+    pub fn create_function(
+        &mut self,
+        function: FunctionValue<'ctx>,
+        prototype: &TypedPrototype,
+        name: &str,
+        symbol_name: &str,
+    ) {
+        let parameter_types = prototype
+            .parameters
+            .iter()
+            .filter_map(|parameter| self.get_type(parameter.ty))
+            .collect::<Vec<_>>();
+        let subroutine_type = self.builder.create_subroutine_type(
+            self.file,
+            self.get_type(prototype.return_type),
+            &parameter_types,
+            DIFlags::PUBLIC,
+        );
+
+        let (line, _) = self
+            .source_map
+            .line_column(prototype.span.file_id, prototype.span.start);
+        let subprogram = self.builder.create_function(
+            self.compile_unit.as_debug_info_scope(),
+            name,
+            Some(symbol_name),
+            self.file,
+            line as u32,
+            subroutine_type,
+            false,
+            true,
+            line as u32,
+            DIFlags::PUBLIC,
+            self.is_optimized,
+        );
+
+        function.set_subprogram(subprogram);
+        self.subprogram = Some(subprogram);
+    }
+
+    /// Sets the location of the instructions built afterwards to the start of the span.
+    /// Nothing is changed when no function is being generated.
+    ///
+    /// # Arguments
+    /// * `builder` - The builder of the instructions.
+    /// * `span` - The `Span` of the source that the instructions are generated from.
+    pub fn set_location(&self, builder: &Builder<'ctx>, span: Span) {
+        if let Some(location) = self.create_location(span) {
+            builder.set_current_debug_location(self.context, location);
+        }
+    }
+
+    /// Declares a variable of the current function, which is stored at the pointer,
+    /// so that the debugger can print it.
+    ///
+    /// # Arguments
+    /// * `pointer` - The pointer that the variable is stored at.
+    /// * `name` - The name of the variable.
+    /// * `argument_number` - The position of the variable in the parameters of
+    ///   the function, starting at 1, or `None` if it is a local variable.
+    /// * `ty` - The type of the variable.
+    /// * `span` - The `Span` of the name of the variable.
+    /// * `block` - The block that the declaration is added to.
+    pub fn declare_variable(
+        &self,
+        pointer: PointerValue<'ctx>,
+        name: &str,
+        argument_number: Option<u32>,
+        ty: Type,
+        span: Span,
+        block: BasicBlock<'ctx>,
+    ) {
+        let (subprogram, ty, location) = match (
+            self.subprogram,
+            self.get_type(ty),
+            self.create_location(span),
+        ) {
+            (Some(subprogram), Some(ty), Some(location)) => (subprogram, ty, location),
+            _ => return,
+        };
+
+        let variable = match argument_number {
+            Some(argument_number) => self.builder.create_parameter_variable(
+                subprogram.as_debug_info_scope(),
+                name,
+                argument_number,
+                self.file,
+                location.get_line(),
+                ty,
+                true,
+                DIFlags::ZERO,
+            ),
+            None => self.builder.create_auto_variable(
+                subprogram.as_debug_info_scope(),
+                name,
+                self.file,
+                location.get_line(),
+                ty,
+                true,
+                DIFlags::ZERO,
+                0,
+            ),
+        };
+
+        self.builder
+            .insert_declare_at_end(pointer, Some(variable), None, location, block);
+    }
+
+    /// Resolves every temporary node of the debug information.
+    /// This must be called before the module is verified or emitted.
+    pub fn finalize(&self) {
+        self.builder.finalize();
+    }
+
+    /// Creates the location of the start of the span inside the current function.
+    /// Returns `None` when no function is being generated.
+    ///
+    /// # Arguments
+    /// * `span` - The `Span` of the location.
+    fn create_location(&self, span: Span) -> Option<DILocation<'ctx>> {
+        let subprogram = self.subprogram?;
+        let (line, column) = self.source_map.line_column(span.file_id, span.start);
+        Some(self.builder.create_debug_location(
+            self.context,
+            line as u32,
+            column as u32,
+            subprogram.as_debug_info_scope(),
+            None,
+        ))
+    }
+
+    /// Gets the debug type of a type, or `None` if values of the type are never stored.
+    ///
+    /// # Arguments
+    /// * `ty` - The type.
+    fn get_type(&self, ty: Type) -> Option<DIType<'ctx>> {
+        let (name, size_in_bits, encoding) = match ty {
+            Type::Int => ("Int", 64, DW_ATE_SIGNED),
+            Type::Float => ("Float", 64, DW_ATE_FLOAT),
+            Type::Boolean => ("Boolean", 8, DW_ATE_BOOLEAN),
+            Type::Char => ("Char", 8, DW_ATE_SIGNED_CHAR),
+            Type::Void | Type::Never | Type::Error => return None,
+        };
+
+        self.builder
+            .create_basic_type(name, size_in_bits, encoding, DIFlags::ZERO)
+            .ok()
+            .map(|basic_type| basic_type.as_type())
+    }
+}
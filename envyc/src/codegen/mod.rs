@@ -1,2 +1,10 @@
+pub mod c_generator;
+#[cfg(feature = "llvm")]
 pub mod code_generator;
+#[cfg(feature = "llvm")]
 pub mod context;
+#[cfg(feature = "llvm")]
+pub mod debug_info;
+
+/// The exit code used when the program panics or an assertion fails.
+pub const PANIC_EXIT_CODE: u64 = 101;
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Environment<T> {
     scopes: Vec<Scope<T>>,
 }
@@ -25,6 +25,11 @@ impl<T: Copy> Environment<T> {
     pub fn get(&self, id: usize) -> Option<T> {
         self.scopes.iter().rev().find_map(|scope| scope.get(id))
     }
+
+    /// Returns the ids of every name that is defined in any of the scopes.
+    pub fn names(&self) -> impl Iterator<Item = usize> + '_ {
+        self.scopes.iter().flat_map(|scope| scope.names())
+    }
 }
 
 impl<T> Default for Environment<T> {
@@ -33,7 +38,7 @@ impl<T> Default for Environment<T> {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Scope<T> {
     inner: HashMap<usize, T>,
 }
@@ -46,6 +51,10 @@ impl<T: Copy> Scope<T> {
     pub fn get(&self, id: usize) -> Option<T> {
         self.inner.get(&id).copied()
     }
+
+    pub fn names(&self) -> impl Iterator<Item = usize> + '_ {
+        self.inner.keys().copied()
+    }
 }
 
 impl<T> Default for Scope<T> {
@@ -0,0 +1,137 @@
+use std::ops::Range;
+
+use codespan_reporting::diagnostic::{Diagnostic, LabelStyle, Severity};
+
+use super::{FileId, SourceMap};
+
+/// Struct that describes a change to the source that would resolve a diagnostic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuggestedFix {
+    // The message that describes the fix.
+    pub message: String,
+    // The id of the file that the fix applies to.
+    pub file_id: FileId,
+    // The range of bytes that should be replaced.
+    pub range: Range<usize>,
+    // The text that should replace the range.
+    pub replacement: String,
+}
+
+/// Renders the diagnostic as a JSON object on a single line. The object
+/// has the code, severity, message, labels, notes and suggested fixes of
+/// the diagnostic. Every position has both the line and column, which
+/// start at 1, and the range of bytes in the file.
+///
+/// # Arguments
+/// * `diagnostic` - The diagnostic to render.
+/// * `fixes` - The fixes suggested for the diagnostic.
+/// * `source_map` - The files that the labels of the diagnostic refer to.
+pub fn render_json(
+    diagnostic: &Diagnostic<FileId>,
+    fixes: &[SuggestedFix],
+    source_map: &SourceMap,
+) -> String {
+    let severity = match diagnostic.severity {
+        Severity::Bug => "bug",
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note => "note",
+        Severity::Help => "help",
+    };
+
+    let code = diagnostic
+        .code
+        .as_ref()
+        .map_or_else(|| "null".to_string(), |code| string(code));
+
+    let labels = diagnostic
+        .labels
+        .iter()
+        .map(|label| {
+            let style = match label.style {
+                LabelStyle::Primary => "primary",
+                LabelStyle::Secondary => "secondary",
+            };
+
+            format!(
+                "{{\"style\":\"{}\",\"message\":{},{}}}",
+                style,
+                string(&label.message),
+                position(source_map, label.file_id, &label.range)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let notes = diagnostic
+        .notes
+        .iter()
+        .map(|note| string(note))
+        .collect::<Vec<_>>();
+
+    let fixes = fixes
+        .iter()
+        .map(|fix| {
+            format!(
+                "{{\"message\":{},\"replacement\":{},{}}}",
+                string(&fix.message),
+                string(&fix.replacement),
+                position(source_map, fix.file_id, &fix.range)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        "{{\"code\":{},\"severity\":\"{}\",\"message\":{},\"labels\":[{}],\"notes\":[{}],\"fixes\":[{}]}}",
+        code,
+        severity,
+        string(&diagnostic.message),
+        labels.join(","),
+        notes.join(","),
+        fixes.join(",")
+    )
+}
+
+/// Renders the fields that describe a range of bytes in a file.
+///
+/// # Arguments
+/// * `source_map` - The files that the range refers to.
+/// * `file_id` - The id of the file.
+/// * `range` - The range of bytes in the file.
+fn position(source_map: &SourceMap, file_id: FileId, range: &Range<usize>) -> String {
+    let (line_start, column_start) = source_map.line_column(file_id, range.start);
+    let (line_end, column_end) = source_map.line_column(file_id, range.end);
+    format!(
+        "\"file\":{},\"line_start\":{},\"column_start\":{},\"line_end\":{},\"column_end\":{},\"byte_start\":{},\"byte_end\":{}",
+        string(source_map.name(file_id)),
+        line_start,
+        column_start,
+        line_end,
+        column_end,
+        range.start,
+        range.end
+    )
+}
+
+/// Renders the value as a JSON string, escaping the characters that
+/// can not appear in a JSON string as they are.
+///
+/// # Arguments
+/// * `value` - The value to render.
+fn string(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => result.push(ch),
+        }
+    }
+
+    result.push('"');
+    result
+}
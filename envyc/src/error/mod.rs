@@ -5,40 +5,42 @@ use crate::{lexer::token::TokenKind, semantic_analyzer::types::Type};
 /// to provide a better representation when reported to the user,
 /// unless the error stems from the LLVM compiler, which is not
 /// derived from the user's code.
-#[derive(Debug)]
-pub enum Error<'a> {
+#[derive(Debug, PartialEq)]
+pub enum Error {
     // Occurs when an integer that exceeeds the maximum possible value of an integer.
-    IntegerOverflow(Span<'a>),
+    IntegerOverflow(Span),
     // Occurs when a float that exceeeds the maximum possible value of a float.
-    FloatOverflow(Span<'a>),
-    UnterminatedChar(Span<'a>),
+    FloatOverflow(Span),
+    UnterminatedChar(Span),
+    // Occurs when a string literal is not closed before the end of the line.
+    UnterminatedString(Span),
     // Occurs when a character that is not recognized by the `Lexer`.
-    UnrecognizedCharacter(Span<'a>),
+    UnrecognizedCharacter(Span),
 
     // Occurs when an expression was expected by the `Parser` but
     // there were no more tokens to inspect.
-    UnexpectedEndOfInput(Span<'a>),
+    UnexpectedEndOfInput(Span),
     // Occurs when a token does not have a corresponding expression.
     ExpectedPrefixExpression {
-        span: Span<'a>,
+        span: Span,
         found_kind: TokenKind,
     },
     // Occurs when a certain token was expected by an expression but
     // a different token was found.
     ExpectedKind {
-        span: Span<'a>,
+        span: Span,
         expected_kinds: Vec<TokenKind>,
         actual_kind: TokenKind,
     },
 
     // Occurs when the specified operation could not be applied to operands.
     UnsupportedOperation {
-        operation_span: Span<'a>,
-        operands: Vec<(Span<'a>, Type)>,
+        operation_span: Span,
+        operands: Vec<(Span, Type)>,
     },
     // Occurs when the type of an expression does not match the expected type.
     TypeMismatch {
-        span: Span<'a>,
+        span: Span,
         expected_type: Type,
         actual_type: Type,
     },
@@ -46,33 +48,155 @@ pub enum Error<'a> {
     // of the then branch and the type of the else branch do not match, this error
     // is returned.
     ConflictingType {
-        first_span: Span<'a>,
+        first_span: Span,
         first_type: Type,
-        second_span: Span<'a>,
+        second_span: Span,
         second_type: Type,
     },
     // Occurs when the type of a variable does not match its previous definitions.
     ConflictingPreviousType {
-        name_span: Span<'a>,
+        name_span: Span,
         previous_type: Type,
-        second_span: Span<'a>,
+        second_span: Span,
         second_type: Type,
     },
     // Occurs when a type was found that could not be used.
-    IllegalType(Span<'a>),
-    UndefinedVariable(Span<'a>),
+    IllegalType(Span),
+    // Occurs when a variable that is not in scope is used. The suggestion
+    // is the closest name in scope, if there is one.
+    UndefinedVariable {
+        span: Span,
+        suggestion: Option<Suggestion>,
+    },
     ParameterMismatch {
-        span: Span<'a>,
+        span: Span,
         expected_parameter_count: usize,
         actual_parameter_count: usize,
     },
 
-    UnknownFunction(Span<'a>),
+    // Occurs when a function that was not defined is called. The suggestion
+    // is the closest defined function, if there is one.
+    UnknownFunction {
+        span: Span,
+        suggestion: Option<Suggestion>,
+    },
     /// Occurs when a function was expected during the LLVM compilation.
     ExpectedFunction,
-    LLVMFunctionFailure,
+    /// Occurs when LLVM could not verify the IR generated for a function.
+    /// This is an internal compiler error, and the message is the output
+    /// of the LLVM verifier.
+    LLVMFunctionFailure {
+        name: String,
+        span: Span,
+        message: String,
+    },
+    /// Occurs when the target triple is not supported by LLVM.
+    /// The message is the reason given by LLVM.
+    UnknownTarget {
+        triple: String,
+        message: String,
+    },
+    /// Occurs when LLVM could not create a machine for the target
+    /// with the given CPU and features.
+    UnsupportedTargetMachine {
+        triple: String,
+        cpu: String,
+        features: String,
+    },
+    /// Occurs when LLVM could not write the assembly or the object file
+    /// for the target. The message is the reason given by LLVM.
+    EmissionFailure {
+        triple: String,
+        message: String,
+    },
+    /// Occurs when LLVM could not link the program or create the JIT
+    /// to run it. The message is the reason given by LLVM.
+    JitFailure {
+        message: String,
+    },
+    /// Occurs when a program that is run without native code, by the interpreter
+    /// or the bytecode VM, declares a function with `extern` that is neither
+    /// defined by another file nor one of the C functions they provide. The
+    /// signature is the one the C function must be declared with, if it is provided.
+    UnsupportedExtern {
+        span: Span,
+        name: String,
+        signature: Option<String>,
+    },
+    /// Occurs when a program is run, but none of its files define a `main`
+    /// function that takes no parameters and returns `Void` or an `Int`.
+    MissingEntryPoint,
+    /// Occurs when the bytes of a string literal are not valid UTF-8.
+    InvalidString(Span),
+    /// Occurs when the files that are linked together define the same
+    /// function more than once.
+    DuplicateDefinition {
+        span: Span,
+        name: String,
+        previous_span: Span,
+    },
+    /// Occurs when a function or an extern declaration is defined again in
+    /// the REPL with a different signature while another definition calls it.
+    ChangedSignature {
+        span: Span,
+        name: String,
+        previous_signature: String,
+        caller_span: Span,
+        caller: String,
+    },
+}
+
+impl Error {
+    /// Gets the stable code of this error. The code is shown when the error
+    /// is reported and can be passed to `envious explain` to get a longer
+    /// explanation of the error. Codes are never reused or reordered.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::IntegerOverflow(_) => "E0001",
+            Error::FloatOverflow(_) => "E0002",
+            Error::UnterminatedChar(_) => "E0003",
+            Error::UnterminatedString(_) => "E0004",
+            Error::UnrecognizedCharacter(_) => "E0005",
+            Error::UnexpectedEndOfInput(_) => "E0006",
+            Error::ExpectedPrefixExpression { .. } => "E0007",
+            Error::ExpectedKind { .. } => "E0008",
+            Error::UnsupportedOperation { .. } => "E0009",
+            Error::TypeMismatch { .. } => "E0010",
+            Error::ConflictingType { .. } => "E0011",
+            Error::ConflictingPreviousType { .. } => "E0012",
+            Error::IllegalType(_) => "E0013",
+            Error::UndefinedVariable { .. } => "E0014",
+            Error::ParameterMismatch { .. } => "E0015",
+            Error::UnknownFunction { .. } => "E0016",
+            Error::ExpectedFunction => "E0017",
+            Error::LLVMFunctionFailure { .. } => "E0018",
+            Error::UnknownTarget { .. } => "E0019",
+            Error::UnsupportedTargetMachine { .. } => "E0020",
+            Error::EmissionFailure { .. } => "E0021",
+            Error::JitFailure { .. } => "E0022",
+            Error::UnsupportedExtern { .. } => "E0023",
+            Error::MissingEntryPoint => "E0024",
+            Error::InvalidString(_) => "E0025",
+            Error::DuplicateDefinition { .. } => "E0026",
+            Error::ChangedSignature { .. } => "E0027",
+        }
+    }
+}
+
+/// Struct that describes a name that is similar to a name that could not
+/// be found, which is shown to the user as a possible fix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    // The similar name.
+    pub name: String,
+    // The signature of the similar name, if it names a function.
+    pub signature: Option<String>,
 }
 
+pub mod explanations;
+pub mod json;
 pub mod reporter;
+pub mod source_map;
 pub mod span;
+pub use source_map::{FileId, SourceMap};
 pub use span::Span;
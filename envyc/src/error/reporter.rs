@@ -1,48 +1,63 @@
-use std::{
-    collections::HashMap,
-    io::{self, Write},
-};
+use std::io::Write;
 
 use codespan_reporting::{
     diagnostic::{Diagnostic, Label},
-    files::SimpleFiles,
     term::termcolor::{BufferWriter, ColorChoice},
 };
 
-use crate::{lexer::token::TokenKind, semantic_analyzer::types::Type};
+use crate::{
+    lexer::token::TokenKind,
+    lint::{LintDiagnostic, Severity},
+    semantic_analyzer::types::Type,
+};
 
-use super::{Error, Span};
+use super::{
+    json::{render_json, SuggestedFix},
+    Error, FileId, SourceMap, Span, Suggestion,
+};
+
+/// The format that the errors are reported in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorFormat {
+    /// Errors are rendered with the source that caused them, for people to read.
+    Human,
+    /// Each error is rendered as a JSON object on its own line, for tools to read.
+    Json,
+}
 
 /// Struct that handles reporting the different errors that occur.
 pub struct ErrorReporter<'a> {
     /// The files being reported.
-    files: SimpleFiles<&'a str, &'a str>,
-    /// The ids of the files compiled.
     /// This is used by the codespan_reporting crate.
-    /// This map goes from the name of the file to its id.
-    file_ids: HashMap<&'a str, usize>,
+    source_map: &'a SourceMap,
+    /// The format that the errors are reported in.
+    format: ErrorFormat,
 }
 
 impl<'a> ErrorReporter<'a> {
-    pub fn new(input_files: Vec<(&'a str, &'a str)>) -> Self {
-        let mut files = SimpleFiles::new();
-        let mut file_ids = HashMap::new();
-        for (name, source) in input_files {
-            let id = files.add(name.as_ref(), source.as_ref());
-            file_ids.insert(name.as_ref(), id);
+    pub fn new(source_map: &'a SourceMap) -> Self {
+        Self {
+            source_map,
+            format: ErrorFormat::Human,
         }
-
-        Self { files, file_ids }
     }
 
-    /// Adds a file to the input files.
+    /// Sets the format that the errors are reported in.
     ///
     /// # Arguments
-    /// * `file_name` - The name of the file.
-    /// * `source` - The source of the input.
-    pub fn add(&mut self, file_name: &'a str, source: &'a str) {
-        let id = self.files.add(file_name, source);
-        self.file_ids.insert(file_name, id);
+    /// * `format` - The format to report the errors in.
+    pub fn set_format(&mut self, format: ErrorFormat) {
+        self.format = format;
+    }
+
+    /// Gets the format that the errors are reported in.
+    pub fn format(&self) -> ErrorFormat {
+        self.format
+    }
+
+    /// Gets the `SourceMap` of the files being reported.
+    pub fn source_map(&self) -> &'a SourceMap {
+        self.source_map
     }
 
     /// Reports the error to the user. Note that this method does not consume the error.
@@ -50,11 +65,13 @@ impl<'a> ErrorReporter<'a> {
     ///
     /// # Arguments
     /// * `error` - The error to report.
+    /// * `color` - Whether the error should be colored. This is ignored for JSON.
     pub fn report(&self, error: &Error, color: bool) -> Vec<u8> {
         let diagnostic = match error {
             Error::IntegerOverflow(span) => self.handle_integer_overflow(*span),
             Error::FloatOverflow(span) => self.handle_float_overflow(*span),
             Error::UnterminatedChar(span) => self.handle_unterminated_char(*span),
+            Error::UnterminatedString(span) => self.handle_unterminated_string(*span),
             Error::UnrecognizedCharacter(span) => self.handle_unrecognized_character(*span),
             Error::UnexpectedEndOfInput(span) => self.handle_end_of_input(*span),
             Error::ExpectedPrefixExpression {
@@ -93,7 +110,9 @@ impl<'a> ErrorReporter<'a> {
                 *second_type,
             ),
             Error::IllegalType(span) => self.handle_illegal_type(*span),
-            Error::UndefinedVariable(span) => self.handle_undefined_variable(*span),
+            Error::UndefinedVariable { span, suggestion } => {
+                self.handle_undefined_variable(*span, suggestion.as_ref())
+            }
             Error::ParameterMismatch {
                 span,
                 expected_parameter_count,
@@ -103,19 +122,139 @@ impl<'a> ErrorReporter<'a> {
                 *expected_parameter_count,
                 *actual_parameter_count,
             ),
-            Error::UnknownFunction(span) => self.handle_unknown_function(*span),
-            Error::ExpectedFunction => {
-                return "Expected a function to be selected when compiling to LLVM."
-                    .as_bytes()
-                    .to_vec()
-            }
-            Error::LLVMFunctionFailure => {
-                return "An unexpected error occurred when compiling a function to LLVM."
-                    .as_bytes()
-                    .to_vec()
+            Error::UnknownFunction { span, suggestion } => {
+                self.handle_unknown_function(*span, suggestion.as_ref())
             }
+            Error::ExpectedFunction => Diagnostic::error()
+                .with_message("Expected a function to be selected when compiling to LLVM."),
+            Error::LLVMFunctionFailure {
+                name,
+                span,
+                message,
+            } => self.handle_llvm_function_failure(name, *span, message),
+            Error::UnknownTarget { triple, message } => Diagnostic::error()
+                .with_message(format!("unknown target `{}`", triple))
+                .with_notes(vec![
+                    message.clone(),
+                    String::from("use `--print targets` to list the targets in this build"),
+                ]),
+            Error::UnsupportedTargetMachine {
+                triple,
+                cpu,
+                features,
+            } => Diagnostic::error()
+                .with_message(format!(
+                    "could not create a target machine for `{}`",
+                    triple
+                ))
+                .with_notes(vec![format!(
+                    "the CPU was `{}` and the features were `{}`",
+                    cpu, features
+                )]),
+            Error::EmissionFailure { triple, message } => Diagnostic::error()
+                .with_message(format!("could not emit the output for `{}`", triple))
+                .with_notes(vec![message.clone()]),
+            Error::JitFailure { message } => Diagnostic::error()
+                .with_message("could not run the program in the JIT")
+                .with_notes(vec![message.clone()]),
+            Error::UnsupportedExtern {
+                span,
+                name,
+                signature,
+            } => self.handle_unsupported_extern(*span, name, signature.as_deref()),
+            Error::MissingEntryPoint => Diagnostic::error()
+                .with_message("no file defines a `main` function that can be run")
+                .with_notes(vec![String::from(
+                    "`main` must take no parameters and return `Void` or `Int`",
+                )]),
+            Error::InvalidString(span) => Diagnostic::error()
+                .with_message("string literal is not valid UTF-8")
+                .with_labels(vec![Label::primary(span.file_id, span.range())])
+                .with_notes(vec![String::from("save the file with the UTF-8 encoding")]),
+            Error::DuplicateDefinition {
+                span,
+                name,
+                previous_span,
+            } => self.handle_duplicate_definition(*span, name, *previous_span),
+            Error::ChangedSignature {
+                span,
+                name,
+                previous_signature,
+                caller_span,
+                caller,
+            } => Diagnostic::error()
+                .with_message(format!(
+                    "the signature of `{}` can not change while `{}` calls it",
+                    name, caller
+                ))
+                .with_labels(vec![
+                    Label::primary(span.file_id, span.range())
+                        .with_message("defined again with a different signature"),
+                    Label::secondary(caller_span.file_id, caller_span.range())
+                        .with_message(format!("calls `{}`", previous_signature)),
+                ])
+                .with_notes(vec![format!(
+                    "define `{}` again first, or keep the signature `{}`",
+                    caller, previous_signature
+                )]),
         };
 
+        self.emit(
+            &diagnostic.with_code(error.code()),
+            &self.suggested_fixes(error),
+            color,
+        )
+    }
+
+    /// Reports the diagnostic of a lint to the user. The diagnostic is reported
+    /// as a warning or as an error depending on its severity.
+    ///
+    /// # Arguments
+    /// * `lint_diagnostic` - The diagnostic to report.
+    pub fn report_lint(&self, lint_diagnostic: &LintDiagnostic, color: bool) -> Vec<u8> {
+        let span = lint_diagnostic.span;
+        let lint_name = lint_diagnostic.lint.name();
+        let (diagnostic, note) = match lint_diagnostic.severity {
+            Severity::Warning => (
+                Diagnostic::warning(),
+                format!("this can be silenced with `#[allow({})]`", lint_name),
+            ),
+            Severity::Error => (
+                Diagnostic::error(),
+                format!("the `{}` lint is denied", lint_name),
+            ),
+        };
+
+        let diagnostic = diagnostic
+            .with_message(&lint_diagnostic.message)
+            .with_labels(vec![Label::primary(span.file_id, span.range())])
+            .with_code(lint_name)
+            .with_notes(vec![note]);
+
+        self.emit(&diagnostic, &[], color)
+    }
+
+    /// Renders the diagnostic into bytes in the format of this reporter.
+    /// Both formats are rendered from the same diagnostic, so they always
+    /// contain the same information.
+    ///
+    /// # Arguments
+    /// * `diagnostic` - The diagnostic to render.
+    /// * `fixes` - The fixes suggested for the diagnostic. These are only
+    ///   rendered in JSON, since the notes already describe them to people.
+    /// * `color` - Whether the diagnostic should be colored.
+    fn emit(
+        &self,
+        diagnostic: &Diagnostic<FileId>,
+        fixes: &[SuggestedFix],
+        color: bool,
+    ) -> Vec<u8> {
+        if self.format == ErrorFormat::Json {
+            let mut json = render_json(diagnostic, fixes, self.source_map);
+            json.push('\n');
+            return json.into_bytes();
+        }
+
         let color_choice = if color {
             ColorChoice::Always
         } else {
@@ -125,7 +264,7 @@ impl<'a> ErrorReporter<'a> {
         let buffer_writer = BufferWriter::stderr(color_choice);
         let mut buffer = buffer_writer.buffer();
         let config = codespan_reporting::term::Config::default();
-        codespan_reporting::term::emit(&mut buffer, &config, &self.files, &diagnostic).unwrap();
+        codespan_reporting::term::emit(&mut buffer, &config, self.source_map, diagnostic).unwrap();
 
         buffer.as_slice().to_vec()
     }
@@ -134,14 +273,10 @@ impl<'a> ErrorReporter<'a> {
     ///
     /// # Arguments
     /// * `span` - The `Span` of this error.
-    fn handle_integer_overflow(&self, span: Span) -> Diagnostic<usize> {
-        let (start_column, end_column) = self.construct_source(span);
+    fn handle_integer_overflow(&self, span: Span) -> Diagnostic<FileId> {
         Diagnostic::error()
             .with_message("integer overflowed")
-            .with_labels(vec![Label::primary(
-                self.get_file_id(&span.file_name),
-                start_column..end_column,
-            )])
+            .with_labels(vec![Label::primary(span.file_id, span.range())])
             .with_notes(vec![format!(
                 "integers must be >= {} and <= {}",
                 i64::MIN,
@@ -153,14 +288,10 @@ impl<'a> ErrorReporter<'a> {
     ///
     /// # Arguments
     /// * `span` - The `Span` of this error.
-    fn handle_float_overflow(&self, span: Span) -> Diagnostic<usize> {
-        let (start_column, end_column) = self.construct_source(span);
+    fn handle_float_overflow(&self, span: Span) -> Diagnostic<FileId> {
         Diagnostic::error()
             .with_message("float overflow")
-            .with_labels(vec![Label::primary(
-                self.get_file_id(&span.file_name),
-                start_column..end_column,
-            )])
+            .with_labels(vec![Label::primary(span.file_id, span.range())])
             .with_notes(vec![format!(
                 "floats must be >= {} and <= {}",
                 f64::MIN,
@@ -172,43 +303,42 @@ impl<'a> ErrorReporter<'a> {
     ///
     /// # Arguments
     /// * `span` - The `Span` of this error.
-    fn handle_unterminated_char(&self, span: Span) -> Diagnostic<usize> {
-        let (start_column, end_column) = self.construct_source(span);
+    fn handle_unterminated_char(&self, span: Span) -> Diagnostic<FileId> {
         Diagnostic::error()
             .with_message("unterminated char")
-            .with_labels(vec![Label::primary(
-                self.get_file_id(&span.file_name),
-                start_column..end_column,
-            )])
+            .with_labels(vec![Label::primary(span.file_id, span.range())])
             .with_notes(vec!["try ending the char with a \'".to_string()])
     }
 
+    /// Handles an unterminated string error.
+    ///
+    /// # Arguments
+    /// * `span` - The `Span` of this error.
+    fn handle_unterminated_string(&self, span: Span) -> Diagnostic<FileId> {
+        Diagnostic::error()
+            .with_message("unterminated string")
+            .with_labels(vec![Label::primary(span.file_id, span.range())])
+            .with_notes(vec!["try ending the string with a \"".to_string()])
+    }
+
     /// Handles an unrecognized character error.
     ///
     /// # Arguments
     /// * `span` - The `Span` of this error.
-    fn handle_unrecognized_character(&self, span: Span) -> Diagnostic<usize> {
-        let (start_column, end_column) = self.construct_source(span);
+    fn handle_unrecognized_character(&self, span: Span) -> Diagnostic<FileId> {
         Diagnostic::error()
             .with_message("unrecognized character")
-            .with_labels(vec![Label::primary(
-                self.get_file_id(&span.file_name),
-                start_column..end_column,
-            )])
+            .with_labels(vec![Label::primary(span.file_id, span.range())])
     }
 
     /// Handles an unexpected end of input error.
     ///
     /// # Arguments
     /// * `span` - The `Span` of this error.
-    fn handle_end_of_input(&self, span: Span) -> Diagnostic<usize> {
-        let (start_column, end_column) = self.construct_source(span);
+    fn handle_end_of_input(&self, span: Span) -> Diagnostic<FileId> {
         Diagnostic::error()
             .with_message("expected an expression")
-            .with_labels(vec![Label::primary(
-                self.get_file_id(&span.file_name),
-                start_column..end_column,
-            )])
+            .with_labels(vec![Label::primary(span.file_id, span.range())])
     }
 
     /// Handles an expected prefix expression error.
@@ -216,18 +346,14 @@ impl<'a> ErrorReporter<'a> {
     /// # Arguments
     /// * `span` - The `Span` of this error.
     /// * `kind` - The `TokenKind` found.
-    fn handle_expected_prefix_expression(&self, span: Span, kind: TokenKind) -> Diagnostic<usize> {
-        let (start_column, end_column) = self.construct_source(span);
+    fn handle_expected_prefix_expression(&self, span: Span, kind: TokenKind) -> Diagnostic<FileId> {
         Diagnostic::error()
             .with_message("expected prefix expression")
-            .with_labels(vec![Label::primary(
-                self.get_file_id(&span.file_name),
-                start_column..end_column,
-            )
-            .with_message(format!(
-                "the token `{}` does not correspond to any prefix expression",
-                kind
-            ))])
+            .with_labels(vec![Label::primary(span.file_id, span.range())
+                .with_message(format!(
+                    "the token `{}` does not correspond to any prefix expression",
+                    kind
+                ))])
     }
 
     /// Handles an expected kind error.
@@ -241,8 +367,7 @@ impl<'a> ErrorReporter<'a> {
         span: Span,
         expected_kinds: &[TokenKind],
         actual_kind: TokenKind,
-    ) -> Diagnostic<usize> {
-        let (start_column, end_column) = self.construct_source(span);
+    ) -> Diagnostic<FileId> {
         let expected_kinds = expected_kinds
             .iter()
             .map(|kind| format!("{}", kind))
@@ -250,11 +375,8 @@ impl<'a> ErrorReporter<'a> {
             .join(", or ");
         Diagnostic::error()
             .with_message(format!("expected {}", expected_kinds))
-            .with_labels(vec![Label::primary(
-                self.get_file_id(&span.file_name),
-                start_column..end_column,
-            )
-            .with_message(format!("but found {}", actual_kind))])
+            .with_labels(vec![Label::primary(span.file_id, span.range())
+                .with_message(format!("but found {}", actual_kind))])
     }
 
     /// Handles an unsupported operation error.
@@ -266,20 +388,15 @@ impl<'a> ErrorReporter<'a> {
         &self,
         operation_span: Span,
         operands: &[(Span, Type)],
-    ) -> Diagnostic<usize> {
-        let (operation_start, operation_end) = self.construct_source(operation_span);
+    ) -> Diagnostic<FileId> {
         let mut labels = vec![Label::primary(
-            self.get_file_id(&operation_span.file_name),
-            operation_start..operation_end,
+            operation_span.file_id,
+            operation_span.range(),
         )];
         for operand in operands {
-            let (operand_start, operand_end) = self.construct_source(operand.0);
             labels.push(
-                Label::secondary(
-                    self.get_file_id(&operand.0.file_name),
-                    operand_start..operand_end,
-                )
-                .with_message(format!("has a type of {}", operand.1)),
+                Label::secondary(operand.0.file_id, operand.0.range())
+                    .with_message(format!("has a type of {}", operand.1)),
             )
         }
 
@@ -299,18 +416,14 @@ impl<'a> ErrorReporter<'a> {
         span: Span,
         expected_type: Type,
         actual_type: Type,
-    ) -> Diagnostic<usize> {
-        let (start_column, end_column) = self.construct_source(span);
+    ) -> Diagnostic<FileId> {
         Diagnostic::error()
             .with_message("type mismatch")
-            .with_labels(vec![Label::primary(
-                self.get_file_id(&span.file_name),
-                start_column..end_column,
-            )
-            .with_message(format!(
-                "expected `{}` but found `{}`",
-                expected_type, actual_type
-            ))])
+            .with_labels(vec![Label::primary(span.file_id, span.range())
+                .with_message(format!(
+                    "expected `{}` but found `{}`",
+                    expected_type, actual_type
+                ))])
     }
 
     /// Handles a conflicting type error.
@@ -326,22 +439,14 @@ impl<'a> ErrorReporter<'a> {
         first_type: Type,
         second_span: Span,
         second_type: Type,
-    ) -> Diagnostic<usize> {
-        let (first_start_column, first_end_column) = self.construct_source(first_span);
-        let (second_start_column, second_end_column) = self.construct_source(second_span);
+    ) -> Diagnostic<FileId> {
         Diagnostic::error()
             .with_message("type conflict occurred")
             .with_labels(vec![
-                Label::primary(
-                    self.get_file_id(&first_span.file_name),
-                    first_start_column..first_end_column,
-                )
-                .with_message(format!("results in `{}`", first_type)),
-                Label::primary(
-                    self.get_file_id(&second_span.file_name),
-                    second_start_column..second_end_column,
-                )
-                .with_message(format!("results in `{}`", second_type)),
+                Label::primary(first_span.file_id, first_span.range())
+                    .with_message(format!("results in `{}`", first_type)),
+                Label::primary(second_span.file_id, second_span.range())
+                    .with_message(format!("results in `{}`", second_type)),
             ])
     }
 
@@ -358,22 +463,14 @@ impl<'a> ErrorReporter<'a> {
         previous_type: Type,
         second_span: Span,
         second_type: Type,
-    ) -> Diagnostic<usize> {
-        let (first_start_column, first_end_column) = self.construct_source(name_span);
-        let (second_start_column, second_end_column) = self.construct_source(second_span);
+    ) -> Diagnostic<FileId> {
         Diagnostic::error()
             .with_message("type conflict occurred")
             .with_labels(vec![
-                Label::primary(
-                    self.get_file_id(&name_span.file_name),
-                    first_start_column..first_end_column,
-                )
-                .with_message(format!("was defined as `{}`", previous_type)),
-                Label::primary(
-                    self.get_file_id(&second_span.file_name),
-                    second_start_column..second_end_column,
-                )
-                .with_message(format!("results in `{}`", second_type)),
+                Label::primary(name_span.file_id, name_span.range())
+                    .with_message(format!("was defined as `{}`", previous_type)),
+                Label::primary(second_span.file_id, second_span.range())
+                    .with_message(format!("results in `{}`", second_type)),
             ])
     }
 
@@ -381,28 +478,26 @@ impl<'a> ErrorReporter<'a> {
     ///
     /// # Arguments
     /// * `span` - The `Span` of this error.
-    fn handle_illegal_type(&self, span: Span) -> Diagnostic<usize> {
-        let (start_column, end_column) = self.construct_source(span);
+    fn handle_illegal_type(&self, span: Span) -> Diagnostic<FileId> {
         Diagnostic::error()
             .with_message("placed a type where it was not allowed")
-            .with_labels(vec![Label::primary(
-                self.get_file_id(&span.file_name),
-                start_column..end_column,
-            )])
+            .with_labels(vec![Label::primary(span.file_id, span.range())])
     }
 
     /// Handles an undefined variable error.
     ///
     /// # Arguments
     /// * `span` - The `Span` of this error.
-    fn handle_undefined_variable(&self, span: Span) -> Diagnostic<usize> {
-        let (start_column, end_column) = self.construct_source(span);
+    /// * `suggestion` - The closest variable in scope, if there is one.
+    fn handle_undefined_variable(
+        &self,
+        span: Span,
+        suggestion: Option<&Suggestion>,
+    ) -> Diagnostic<FileId> {
         Diagnostic::error()
             .with_message("found undefined variable")
-            .with_labels(vec![Label::primary(
-                self.get_file_id(&span.file_name),
-                start_column..end_column,
-            )])
+            .with_labels(vec![Label::primary(span.file_id, span.range())])
+            .with_notes(self.suggestion_notes(suggestion))
     }
 
     /// Handles a parameter mismatch error.
@@ -416,100 +511,182 @@ impl<'a> ErrorReporter<'a> {
         span: Span,
         expected_parameter_count: usize,
         actual_parameter_count: usize,
-    ) -> Diagnostic<usize> {
-        let (start_column, end_column) = self.construct_source(span);
+    ) -> Diagnostic<FileId> {
         Diagnostic::error()
             .with_message(&format!(
                 "this function expected {} parameters but received {} parameters",
                 expected_parameter_count, actual_parameter_count
             ))
-            .with_labels(vec![Label::primary(
-                self.get_file_id(&span.file_name),
-                start_column..end_column,
-            )])
+            .with_labels(vec![Label::primary(span.file_id, span.range())])
     }
 
     /// Handles an unknown function error.
     ///
     /// # Arguments
     /// * `span` - The `Span` of this error.
-    fn handle_unknown_function(&self, span: Span) -> Diagnostic<usize> {
-        let (start_column, end_column) = self.construct_source(span);
+    /// * `suggestion` - The closest defined function, if there is one.
+    fn handle_unknown_function(
+        &self,
+        span: Span,
+        suggestion: Option<&Suggestion>,
+    ) -> Diagnostic<FileId> {
         Diagnostic::error()
             .with_message("found unknown function")
-            .with_labels(vec![Label::primary(
-                self.get_file_id(&span.file_name),
-                start_column..end_column,
-            )])
+            .with_labels(vec![Label::primary(span.file_id, span.range())])
+            .with_notes(self.suggestion_notes(suggestion))
     }
 
-    /// Takes the span of the error and
-    /// calculates the beginning column and the ending column
-    /// with respect to the entire file.
+    /// Handles an internal compiler error caused by LLVM failing to verify a function.
+    /// Each line of the output of the verifier is shown as its own note.
     ///
     /// # Arguments
-    /// * `span` - The `Span` referenced by this error.
-    fn construct_source(&self, span: Span) -> (usize, usize) {
-        let mut start_column = 0;
-        let mut end_column = 0;
-        let mut current_line = 1;
-        let mut found_start = false;
-        let bytes = self.get_file_source(&span.file_name).as_bytes();
-        let mut index = 0;
-        while index < bytes.len() {
-            let byte = bytes[index];
-            if byte == b'\n' {
-                current_line += 1;
-            } else if current_line == span.line_start && !found_start {
-                start_column += span.column_start;
-                end_column += span.column_start;
-                index += span.column_start;
-                found_start = true;
-                continue;
-            } else if current_line == span.line_end {
-                if current_line != span.line_start {
-                    end_column += span.column_end;
-                } else if span.line_start == span.line_end {
-                    end_column += span.column_end - span.column_start;
-                }
-
-                break;
-            }
+    /// * `name` - The name of the function.
+    /// * `span` - The `Span` of the prototype of the function.
+    /// * `message` - The output of the LLVM verifier.
+    fn handle_llvm_function_failure(
+        &self,
+        name: &str,
+        span: Span,
+        message: &str,
+    ) -> Diagnostic<FileId> {
+        let mut notes = message
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty())
+            .map(|line| format!("LLVM: {}", line))
+            .collect::<Vec<_>>();
+        notes.push(String::from(
+            "this is a bug in the compiler, please report it along with the code that was being compiled",
+        ));
+        notes.push(String::from(
+            "use `--emit-ir-on-ice` to write the partially built module for the report",
+        ));
 
-            if !found_start {
-                start_column += 1;
-            }
+        Diagnostic::error()
+            .with_message(format!(
+                "internal compiler error: LLVM could not verify the function `{}`",
+                name
+            ))
+            .with_labels(vec![Label::primary(span.file_id, span.range())
+                .with_message("the IR generated for this function is invalid")])
+            .with_notes(notes)
+    }
 
-            end_column += 1;
-            index += 1;
-        }
+    /// Handles a function declared with `extern` that can not be called without native code.
+    ///
+    /// # Arguments
+    /// * `span` - The `Span` of the declaration.
+    /// * `name` - The name of the function.
+    /// * `signature` - The signature the function must be declared with, if it is known.
+    fn handle_unsupported_extern(
+        &self,
+        span: Span,
+        name: &str,
+        signature: Option<&str>,
+    ) -> Diagnostic<FileId> {
+        let (label, notes) = match signature {
+            Some(signature) => (
+                "declared with a signature that can not be called",
+                vec![format!("`{}` can only be called as `{}`", name, signature)],
+            ),
+            None => (
+                "not defined by any of the files",
+                vec![String::from(
+                    "only the C functions putchar, abs, labs, fabs, sqrt, pow, sin, cos, exp, log, floor, ceil and exit can be called without native code",
+                )],
+            ),
+        };
 
-        (start_column - 1, end_column)
+        Diagnostic::error()
+            .with_message(format!(
+                "the extern function `{}` can not be called without native code",
+                name
+            ))
+            .with_labels(vec![
+                Label::primary(span.file_id, span.range()).with_message(label)
+            ])
+            .with_notes(notes)
     }
 
-    /// Gets the source of the file associated with the given file.
-    /// This function unwraps the result, thus it expects
-    /// that the file actually exists.
+    /// Handles a function that is defined more than once by the files that are linked together.
     ///
     /// # Arguments
-    /// * `file_name` - The name of the file.
-    fn get_file_source(&self, file_name: &str) -> &str {
-        let file_id = self.get_file_id(file_name);
-        self.files.get(file_id).unwrap().source()
+    /// * `span` - The `Span` of the later definition.
+    /// * `name` - The name of the function.
+    /// * `previous_span` - The `Span` of the earlier definition.
+    fn handle_duplicate_definition(
+        &self,
+        span: Span,
+        name: &str,
+        previous_span: Span,
+    ) -> Diagnostic<FileId> {
+        Diagnostic::error()
+            .with_message(format!("the function `{}` is defined more than once", name))
+            .with_labels(vec![
+                Label::primary(span.file_id, span.range()).with_message("defined again here"),
+                Label::secondary(previous_span.file_id, previous_span.range())
+                    .with_message("first defined here"),
+            ])
+            .with_notes(vec![String::from(
+                "every function must be defined by only one of the files",
+            )])
+    }
+
+    /// Finds the fixes suggested by the error. These are the replacements of
+    /// misspelled names with the names suggested for them.
+    ///
+    /// # Arguments
+    /// * `error` - The error to find the fixes of.
+    fn suggested_fixes(&self, error: &Error) -> Vec<SuggestedFix> {
+        match error {
+            Error::UndefinedVariable {
+                span,
+                suggestion: Some(suggestion),
+            }
+            | Error::UnknownFunction {
+                span,
+                suggestion: Some(suggestion),
+            } => {
+                vec![SuggestedFix {
+                    message: suggestion_message(suggestion),
+                    file_id: span.file_id,
+                    range: span.range(),
+                    replacement: suggestion.name.clone(),
+                }]
+            }
+            _ => vec![],
+        }
     }
 
-    /// Gets the file id associated with the given file.
-    /// This function unwraps the result, thus it expects
-    /// that the file actually exists.
+    /// Constructs the notes that show a suggestion to the user. If the
+    /// suggestion is a function, its signature is also shown.
     ///
     /// # Arguments
-    /// * `file_name` - The name of the file.
-    #[inline]
-    fn get_file_id(&self, file_name: &str) -> usize {
-        *self.file_ids.get(file_name).unwrap()
+    /// * `suggestion` - The suggestion to show, if there is one.
+    fn suggestion_notes(&self, suggestion: Option<&Suggestion>) -> Vec<String> {
+        let mut notes = vec![];
+        if let Some(suggestion) = suggestion {
+            notes.push(format!("help: {}", suggestion_message(suggestion)));
+            if let Some(signature) = &suggestion.signature {
+                notes.push(format!(
+                    "`{}` is defined as `{}`",
+                    suggestion.name, signature
+                ));
+            }
+        }
+
+        notes
     }
 }
 
+/// Constructs the message that asks the user whether they meant the suggested name.
+///
+/// # Arguments
+/// * `suggestion` - The suggested name.
+fn suggestion_message(suggestion: &Suggestion) -> String {
+    format!("did you mean `{}`?", suggestion.name)
+}
+
 /// Trait to provide blanket implementations for containers of errors.
 pub trait Reporter {
     /// The value returned by the reporter once used.
@@ -523,7 +700,14 @@ pub trait Reporter {
     ///
     /// # Arguments
     /// * `error_reporter` - The `ErrorReporter` reference to use to report errors.
-    fn report(self, error_reporter: &ErrorReporter, color: bool) -> Option<Self::Output>;
+    /// * `color` - Whether the errors should be colored.
+    /// * `output` - The writer that the reported errors are written to.
+    fn report(
+        self,
+        error_reporter: &ErrorReporter,
+        color: bool,
+        output: &mut dyn Write,
+    ) -> Option<Self::Output>;
 }
 
 /// Trait to provide blanket implementations for containers of errors.
@@ -551,17 +735,22 @@ pub trait ReporterResult {
     ) -> Result<Self::Output, Self::Error>;
 }
 
-impl<'a> Reporter for Vec<Error<'a>> {
+impl Reporter for Vec<Error> {
     type Output = ();
 
     fn is_err(&self) -> bool {
         !self.is_empty()
     }
 
-    fn report(self, error_reporter: &ErrorReporter, color: bool) -> Option<Self::Output> {
+    fn report(
+        self,
+        error_reporter: &ErrorReporter,
+        color: bool,
+        output: &mut dyn Write,
+    ) -> Option<Self::Output> {
         for error in &self {
             let bytes = error_reporter.report(error, color);
-            io::stdout().write(&bytes).ok()?;
+            output.write_all(&bytes).ok()?;
         }
 
         if !self.is_empty() {
@@ -572,7 +761,7 @@ impl<'a> Reporter for Vec<Error<'a>> {
     }
 }
 
-impl<'a> ReporterResult for Vec<Error<'a>> {
+impl ReporterResult for Vec<Error> {
     type Output = ();
     type Error = Vec<String>;
 
@@ -598,17 +787,51 @@ impl<'a> ReporterResult for Vec<Error<'a>> {
     }
 }
 
-impl<'a> Reporter for Option<Error<'a>> {
+impl Reporter for Vec<LintDiagnostic> {
+    type Output = ();
+
+    fn is_err(&self) -> bool {
+        self.iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+
+    /// Reports every diagnostic. Warnings do not stop the compilation,
+    /// so the output is only missing if a lint was denied.
+    fn report(
+        self,
+        error_reporter: &ErrorReporter,
+        color: bool,
+        output: &mut dyn Write,
+    ) -> Option<Self::Output> {
+        for diagnostic in &self {
+            let bytes = error_reporter.report_lint(diagnostic, color);
+            output.write_all(&bytes).ok()?;
+        }
+
+        if Reporter::is_err(&self) {
+            None
+        } else {
+            Some(())
+        }
+    }
+}
+
+impl Reporter for Option<Error> {
     type Output = ();
 
     fn is_err(&self) -> bool {
         self.is_some()
     }
 
-    fn report(self, error_reporter: &ErrorReporter, color: bool) -> Option<Self::Output> {
+    fn report(
+        self,
+        error_reporter: &ErrorReporter,
+        color: bool,
+        output: &mut dyn Write,
+    ) -> Option<Self::Output> {
         if let Some(ref error) = self {
             let bytes = error_reporter.report(error, color);
-            io::stdout().write(&bytes).ok()?;
+            output.write_all(&bytes).ok()?;
             Some(())
         } else {
             None
@@ -616,7 +839,7 @@ impl<'a> Reporter for Option<Error<'a>> {
     }
 }
 
-impl<'a> ReporterResult for Option<Error<'a>> {
+impl ReporterResult for Option<Error> {
     type Output = ();
     type Error = String;
 
@@ -638,26 +861,31 @@ impl<'a> ReporterResult for Option<Error<'a>> {
     }
 }
 
-impl<'a, T> Reporter for Result<T, Error<'a>> {
+impl<T> Reporter for Result<T, Error> {
     type Output = T;
 
     fn is_err(&self) -> bool {
         self.is_err()
     }
 
-    fn report(self, error_reporter: &ErrorReporter, color: bool) -> Option<Self::Output> {
+    fn report(
+        self,
+        error_reporter: &ErrorReporter,
+        color: bool,
+        output: &mut dyn Write,
+    ) -> Option<Self::Output> {
         match self {
             Ok(val) => Some(val),
             Err(error) => {
                 let bytes = error_reporter.report(&error, color);
-                io::stdout().write(&bytes).ok()?;
+                output.write_all(&bytes).ok()?;
                 None
             }
         }
     }
 }
 
-impl<'a, T> ReporterResult for Result<T, Error<'a>> {
+impl<T> ReporterResult for Result<T, Error> {
     type Output = T;
     type Error = String;
 
@@ -680,25 +908,30 @@ impl<'a, T> ReporterResult for Result<T, Error<'a>> {
     }
 }
 
-impl<'a, T> Reporter for Result<T, Vec<Error<'a>>> {
+impl<T> Reporter for Result<T, Vec<Error>> {
     type Output = T;
 
     fn is_err(&self) -> bool {
         matches!(self, Err(errors) if !errors.is_empty())
     }
 
-    fn report(self, error_reporter: &ErrorReporter, color: bool) -> Option<Self::Output> {
+    fn report(
+        self,
+        error_reporter: &ErrorReporter,
+        color: bool,
+        output: &mut dyn Write,
+    ) -> Option<Self::Output> {
         match self {
             Ok(val) => Some(val),
             Err(errors) => {
-                errors.report(error_reporter, color)?;
+                errors.report(error_reporter, color, output)?;
                 None
             }
         }
     }
 }
 
-impl<'a, T> ReporterResult for Result<T, Vec<Error<'a>>> {
+impl<T> ReporterResult for Result<T, Vec<Error>> {
     type Output = T;
     type Error = Vec<String>;
 
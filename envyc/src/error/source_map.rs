@@ -0,0 +1,90 @@
+use std::ops::Range;
+
+use codespan_reporting::files::{self, Files, SimpleFiles};
+
+/// The id of a file stored in a `SourceMap`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FileId(usize);
+
+/// Struct that owns the name and source of every file being compiled.
+/// A `Span` only stores the id of its file and a range of bytes, so the
+/// `SourceMap` is used to find the line and column of a span when they
+/// are needed, such as when reporting errors.
+pub struct SourceMap {
+    files: SimpleFiles<String, String>,
+}
+
+impl SourceMap {
+    /// Adds a file to the `SourceMap` and returns the id of the file.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the file.
+    /// * `source` - The source of the file.
+    pub fn add(&mut self, name: impl Into<String>, source: impl Into<String>) -> FileId {
+        FileId(self.files.add(name.into(), source.into()))
+    }
+
+    /// Gets the name of the file with the given id.
+    /// This function expects that the file was added to this `SourceMap`.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the file.
+    pub fn name(&self, file_id: FileId) -> &str {
+        self.files.get(file_id.0).unwrap().name()
+    }
+
+    /// Gets the source of the file with the given id.
+    /// This function expects that the file was added to this `SourceMap`.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the file.
+    pub fn source(&self, file_id: FileId) -> &str {
+        self.files.get(file_id.0).unwrap().source()
+    }
+
+    /// Gets the line and the column of the byte in the file with the
+    /// given id. Both the line and the column start at 1.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the file.
+    /// * `byte_index` - The index of the byte in the file.
+    pub fn line_column(&self, file_id: FileId, byte_index: usize) -> (usize, usize) {
+        self.location(file_id, byte_index)
+            .map(|location| (location.line_number, location.column_number))
+            .unwrap_or((1, 1))
+    }
+}
+
+impl Default for SourceMap {
+    fn default() -> Self {
+        Self {
+            files: SimpleFiles::new(),
+        }
+    }
+}
+
+impl<'a> Files<'a> for SourceMap {
+    type FileId = FileId;
+    type Name = &'a str;
+    type Source = &'a str;
+
+    fn name(&'a self, file_id: FileId) -> Result<&'a str, files::Error> {
+        Ok(self.files.get(file_id.0)?.name())
+    }
+
+    fn source(&'a self, file_id: FileId) -> Result<&'a str, files::Error> {
+        Ok(self.files.get(file_id.0)?.source())
+    }
+
+    fn line_index(&'a self, file_id: FileId, byte_index: usize) -> Result<usize, files::Error> {
+        self.files.line_index(file_id.0, byte_index)
+    }
+
+    fn line_range(
+        &'a self,
+        file_id: FileId,
+        line_index: usize,
+    ) -> Result<Range<usize>, files::Error> {
+        self.files.line_range(file_id.0, line_index)
+    }
+}
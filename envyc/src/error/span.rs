@@ -1,58 +1,55 @@
-use std::cmp::{max, min};
+use std::{
+    cmp::{max, min},
+    ops::Range,
+};
+
+use super::FileId;
 
 /// Struct used by all parts of the program to note the location information
 /// of the different tokens and expressions generated by the `Lexer` and the
-/// `Parser` respectively.
+/// `Parser` respectively. The location is stored as a range of bytes in the
+/// file, and the line and column are found through the `SourceMap` only
+/// when they are needed.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Span<'a> {
-    // The name of the file.
-    pub file_name: &'a str,
-    // The line where this span starts.
-    pub line_start: usize,
-    // The column where this span starts.
-    pub column_start: usize,
-    // The line where this span ends.
-    pub line_end: usize,
-    // The column where this span ends.
-    pub column_end: usize,
+pub struct Span {
+    // The id of the file.
+    pub file_id: FileId,
+    // The index of the first byte of this span.
+    pub start: usize,
+    // The index after the last byte of this span.
+    pub end: usize,
 }
 
-impl<'a> Span<'a> {
+impl Span {
     /// Creates a new `Span`.
-    pub fn new(
-        file_name: &'a str,
-        line_start: usize,
-        column_start: usize,
-        line_end: usize,
-        column_end: usize,
-    ) -> Self {
+    pub fn new(file_id: FileId, start: usize, end: usize) -> Self {
         Self {
-            file_name,
-            line_start,
-            column_start,
-            line_end,
-            column_end,
+            file_id,
+            start,
+            end,
         }
     }
 
-    /// Combines two spans together.
-    /// This function expects that the spans passed
-    /// are from the same file. If this is not true,
-    /// the generated span may not be correct.
+    /// Combines two spans together into the smallest span that contains both.
+    /// Spans from different files can not be combined, in which case this
+    /// span is returned unchanged.
     ///
     /// # Arguments
     /// * `other` - The other span to use when combining.
-    pub fn combine(&self, other: Span<'a>) -> Span<'a> {
-        if self.file_name != other.file_name {
-            panic!("The file names of the Span's do not match!");
+    pub fn combine(&self, other: Span) -> Span {
+        if self.file_id != other.file_id {
+            return *self;
         }
 
         Span::new(
-            self.file_name,
-            min(self.line_start, other.line_start),
-            min(self.column_start, other.column_start),
-            max(self.line_end, other.line_end),
-            max(self.column_end, other.column_end),
+            self.file_id,
+            min(self.start, other.start),
+            max(self.end, other.end),
         )
     }
+
+    /// Gets the range of bytes of this span.
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
 }
@@ -5,6 +5,7 @@ use crate::{
     semantic_analyzer::types::Type,
 };
 
+#[derive(Clone)]
 pub struct FunctionTable {
     function_parameter_types: HashMap<usize, Vec<Type>>,
 }
@@ -19,17 +20,30 @@ impl FunctionTable {
             .insert(function_name, function_parameter_types);
     }
 
-    pub fn get_function_definition<'a>(
+    pub fn get_function_definition(
         &self,
         function_name: usize,
-        function_span: Span<'a>,
-    ) -> Result<&Vec<Type>, Error<'a>> {
-        if let Some(function_parameter_types) = self.function_parameter_types.get(&function_name) {
+        function_span: Span,
+    ) -> Result<&Vec<Type>, Error> {
+        if let Some(function_parameter_types) = self.get_parameter_types(function_name) {
             Ok(function_parameter_types)
         } else {
-            Err(Error::UnknownFunction(function_span))
+            Err(Error::UnknownFunction {
+                span: function_span,
+                suggestion: None,
+            })
         }
     }
+
+    /// Returns the parameter types of the function with the given id, if it exists.
+    pub fn get_parameter_types(&self, function_name: usize) -> Option<&Vec<Type>> {
+        self.function_parameter_types.get(&function_name)
+    }
+
+    /// Returns the ids of every function that has been defined.
+    pub fn function_names(&self) -> impl Iterator<Item = usize> + '_ {
+        self.function_parameter_types.keys().copied()
+    }
 }
 
 impl Default for FunctionTable {
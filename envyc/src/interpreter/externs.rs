@@ -0,0 +1,211 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+};
+
+use crate::{
+    error::Error, interner::Interner, parser::typed_ast::TypedProgram,
+    semantic_analyzer::types::Type,
+};
+
+use super::value::Value;
+
+/// Enum that details the functions of the C standard library that the
+/// `Interpreter` can call when a program declares them as `extern`.
+/// Compiled programs can call any C function, but the interpreter does not
+/// load native code, so it provides its own version of each of these.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExternFunction {
+    /// Writes a single byte to the output and returns it.
+    Putchar,
+    /// Returns the absolute value of an integer.
+    Abs,
+    /// Returns the absolute value of an integer.
+    Labs,
+    /// Returns the absolute value of a float.
+    Fabs,
+    Sqrt,
+    Pow,
+    Sin,
+    Cos,
+    Exp,
+    Log,
+    Floor,
+    Ceil,
+    /// Stops the program with the given exit code.
+    Exit,
+}
+
+impl ExternFunction {
+    /// All of the C functions known to the interpreter.
+    pub const ALL: [ExternFunction; 13] = [
+        ExternFunction::Putchar,
+        ExternFunction::Abs,
+        ExternFunction::Labs,
+        ExternFunction::Fabs,
+        ExternFunction::Sqrt,
+        ExternFunction::Pow,
+        ExternFunction::Sin,
+        ExternFunction::Cos,
+        ExternFunction::Exp,
+        ExternFunction::Log,
+        ExternFunction::Floor,
+        ExternFunction::Ceil,
+        ExternFunction::Exit,
+    ];
+
+    /// Gets the name of the C function.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExternFunction::Putchar => "putchar",
+            ExternFunction::Abs => "abs",
+            ExternFunction::Labs => "labs",
+            ExternFunction::Fabs => "fabs",
+            ExternFunction::Sqrt => "sqrt",
+            ExternFunction::Pow => "pow",
+            ExternFunction::Sin => "sin",
+            ExternFunction::Cos => "cos",
+            ExternFunction::Exp => "exp",
+            ExternFunction::Log => "log",
+            ExternFunction::Floor => "floor",
+            ExternFunction::Ceil => "ceil",
+            ExternFunction::Exit => "exit",
+        }
+    }
+
+    /// Finds the C function with the given name.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the function being declared.
+    pub fn from_name(name: &str) -> Option<ExternFunction> {
+        ExternFunction::ALL
+            .iter()
+            .copied()
+            .find(|extern_function| extern_function.name() == name)
+    }
+
+    /// Gets the signature that the function must be declared with, such as
+    /// `sqrt(Float) :: Float`. The result of any function can be ignored
+    /// by declaring it to return `Void`, and `putchar` also takes a `Char`.
+    pub fn signature(&self) -> (Vec<Type>, Type) {
+        match self {
+            ExternFunction::Putchar | ExternFunction::Abs | ExternFunction::Labs => {
+                (vec![Type::Int], Type::Int)
+            }
+            ExternFunction::Pow => (vec![Type::Float, Type::Float], Type::Float),
+            ExternFunction::Exit => (vec![Type::Int], Type::Void),
+            _ => (vec![Type::Float], Type::Float),
+        }
+    }
+
+    /// Determines whether the function can be called with the types it was declared with.
+    ///
+    /// # Arguments
+    /// * `parameter_types` - The types of the parameters of the declaration.
+    /// * `return_type` - The return type of the declaration.
+    pub fn accepts(&self, parameter_types: &[Type], return_type: Type) -> bool {
+        let (expected_parameter_types, expected_return_type) = self.signature();
+        let parameters_match = parameter_types == expected_parameter_types.as_slice()
+            || (*self == ExternFunction::Putchar && parameter_types == [Type::Char]);
+        parameters_match && (return_type == expected_return_type || return_type == Type::Void)
+    }
+
+    /// Calls the function with arguments that match its signature. Returns
+    /// the exit code as the error when the function stops the program.
+    ///
+    /// # Arguments
+    /// * `arguments` - The values of the arguments.
+    /// * `output` - The writer that the standard output of the program is written to.
+    pub fn call(&self, arguments: &[Value], output: &mut dyn Write) -> Result<Value, i32> {
+        let value = match self {
+            ExternFunction::Putchar => {
+                let byte = arguments[0].as_int() as u8;
+                match output.write_all(&[byte]) {
+                    Ok(()) => Value::Int(byte as i64),
+                    // `putchar` returns `EOF` when the byte could not be written.
+                    Err(_) => Value::Int(-1),
+                }
+            }
+            ExternFunction::Abs | ExternFunction::Labs => {
+                Value::Int(arguments[0].as_int().wrapping_abs())
+            }
+            ExternFunction::Fabs => Value::Float(arguments[0].as_float().abs()),
+            ExternFunction::Sqrt => Value::Float(arguments[0].as_float().sqrt()),
+            ExternFunction::Pow => {
+                Value::Float(arguments[0].as_float().powf(arguments[1].as_float()))
+            }
+            ExternFunction::Sin => Value::Float(arguments[0].as_float().sin()),
+            ExternFunction::Cos => Value::Float(arguments[0].as_float().cos()),
+            ExternFunction::Exp => Value::Float(arguments[0].as_float().exp()),
+            ExternFunction::Log => Value::Float(arguments[0].as_float().ln()),
+            ExternFunction::Floor => Value::Float(arguments[0].as_float().floor()),
+            ExternFunction::Ceil => Value::Float(arguments[0].as_float().ceil()),
+            ExternFunction::Exit => return Err(arguments[0].as_int() as i32),
+        };
+
+        Ok(value)
+    }
+}
+
+/// Finds the C function called by each `extern` declaration of the programs
+/// that is not defined by one of them, along with the return type it was
+/// declared with, by the id of its name. Returns an error for each declaration
+/// that is not one of the `ExternFunction`s or that has the wrong signature.
+///
+/// # Arguments
+/// * `programs` - The type checked programs, which are linked together.
+/// * `interner` - The `Interner` of the names in the programs.
+pub fn resolve_externs(
+    programs: &[&TypedProgram],
+    interner: &Interner<String>,
+) -> Result<HashMap<usize, (ExternFunction, Type)>, Vec<Error>> {
+    let defined_functions = programs
+        .iter()
+        .flat_map(|program| program.functions.iter())
+        .map(|function| function.prototype.name)
+        .collect::<HashSet<_>>();
+
+    let mut externs = HashMap::new();
+    let mut errors = vec![];
+    let extern_declarations = programs
+        .iter()
+        .flat_map(|program| program.extern_declarations.iter())
+        .filter(|extern_declaration| !defined_functions.contains(&extern_declaration.name));
+    for extern_declaration in extern_declarations {
+        let name = interner.get(extern_declaration.name);
+        let parameter_types = extern_declaration
+            .parameters
+            .iter()
+            .map(|parameter| parameter.0)
+            .collect::<Vec<_>>();
+        let return_type = extern_declaration.return_type.0;
+        match ExternFunction::from_name(name) {
+            Some(extern_function) if extern_function.accepts(&parameter_types, return_type) => {
+                externs.insert(extern_declaration.name, (extern_function, return_type));
+            }
+            extern_function => errors.push(Error::UnsupportedExtern {
+                span: extern_declaration.span,
+                name: name.clone(),
+                signature: extern_function.map(|extern_function| {
+                    let (parameter_types, return_type) = extern_function.signature();
+                    let parameter_types = parameter_types
+                        .iter()
+                        .map(Type::to_string)
+                        .collect::<Vec<_>>();
+                    format!(
+                        "{}({}) :: {}",
+                        name,
+                        parameter_types.join(", "),
+                        return_type
+                    )
+                }),
+            }),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(externs)
+    } else {
+        Err(errors)
+    }
+}
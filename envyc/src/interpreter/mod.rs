@@ -0,0 +1,598 @@
+use std::{collections::HashMap, hint, io::Write};
+
+use crate::{
+    builtins::Builtin,
+    codegen::PANIC_EXIT_CODE,
+    error::{Error, SourceMap, Span},
+    interner::Interner,
+    linker::check_duplicate_definitions,
+    parser::{
+        expression::{BinaryOperation, UnaryOperation},
+        typed_ast::{TypedFunction, TypedProgram},
+        typed_expression::{
+            TypedApplication, TypedBinary, TypedExpression, TypedExpressionKind, TypedUnary,
+        },
+    },
+    semantic_analyzer::types::Type,
+};
+
+use self::{
+    externs::{resolve_externs, ExternFunction},
+    value::Value,
+};
+
+pub mod externs;
+pub mod value;
+
+/// The size of the stack that `Interpreter::run_main` must run on. Each call
+/// of the program uses several frames of the interpreter's own stack, and how
+/// many depends on how deeply the expressions of the function are nested, so
+/// a call is stopped when it gets close to the end of the stack instead of
+/// after a fixed number of calls.
+pub const STACK_SIZE: usize = 1 << 30;
+
+/// The part of the stack that is kept free when a function is called, for
+/// the frames used to evaluate its body and to report the panic.
+const STACK_RESERVE: usize = 1 << 24;
+
+/// A runtime error that stopped the program, such as a failed assertion.
+/// The message is the same as the one printed by the compiled program.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Panic {
+    pub span: Span,
+    pub message: String,
+}
+
+impl Panic {
+    /// Renders the panic as `file:line:column: message`, which is how
+    /// the compiled program prints it to stderr.
+    ///
+    /// # Arguments
+    /// * `source_map` - The `SourceMap` containing the file of the panic.
+    pub fn render(&self, source_map: &SourceMap) -> String {
+        let (line, column) = source_map.line_column(self.span.file_id, self.span.start);
+        format!(
+            "{}:{}:{}: {}",
+            source_map.name(self.span.file_id),
+            line,
+            column,
+            self.message
+        )
+    }
+}
+
+/// Enum that details how an interpreted program stopped.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Exit {
+    /// The program returned from `main` or called `exit` with the exit code.
+    Code(i32),
+    /// The program panicked.
+    Panic(Panic),
+}
+
+impl Exit {
+    /// Gets the exit code of the program. A panic exits with
+    /// `PANIC_EXIT_CODE`, like the compiled program.
+    pub fn code(&self) -> i32 {
+        match self {
+            Exit::Code(code) => *code,
+            Exit::Panic(_) => PANIC_EXIT_CODE as i32,
+        }
+    }
+}
+
+/// Enum that details why the evaluation of an expression stopped early.
+#[derive(Debug)]
+enum Interrupt {
+    /// A `return` expression was evaluated with the value returned.
+    Return(Value),
+    /// The program called `exit` with the exit code.
+    Exit(i32),
+    Panic(Panic),
+}
+
+/// Struct that runs type checked programs by walking their expressions,
+/// without generating any code. It gives the same results and stops with
+/// the same runtime errors as the code generated by LLVM, so it can be used
+/// where LLVM is not available. Functions declared with `extern` are either
+/// defined by another of the programs or are one of the `ExternFunction`s.
+///
+/// Dividing by zero is undefined in the generated code when checked
+/// arithmetic is disabled, while the interpreter always panics.
+pub struct Interpreter<'a> {
+    interner: &'a Interner<String>,
+    /// The functions defined by every program, by the id of their name.
+    functions: HashMap<usize, &'a TypedFunction>,
+    /// The C functions declared by the programs, along with the return type
+    /// they were declared with, by the id of their name.
+    externs: HashMap<usize, (ExternFunction, Type)>,
+    checked_arithmetic: bool,
+    /// The command-line arguments of the program, starting with its name.
+    arguments: Vec<String>,
+    /// The writer that the standard output of the program is written to.
+    output: &'a mut dyn Write,
+    /// The address of the stack when `run_main` was called, which the
+    /// stack used by the calls of the program is measured from.
+    stack_start: usize,
+    /// The number of expressions that can be evaluated before the program
+    /// is stopped, or `None` if the program can run forever.
+    step_limit: Option<usize>,
+    /// The number of expressions that have been evaluated.
+    steps: usize,
+}
+
+impl<'a> Interpreter<'a> {
+    /// Creates an `Interpreter` for the programs, which are linked together
+    /// like the object files of compiled programs. Returns an error for each
+    /// function that is defined more than once and for each `extern`
+    /// declaration that is neither defined by one of the programs nor a C
+    /// function known to the interpreter.
+    ///
+    /// # Arguments
+    /// * `programs` - The type checked programs.
+    /// * `interner` - The `Interner` of the names in the programs.
+    /// * `checked_arithmetic` - Whether integer arithmetic panics on overflow.
+    /// * `step_limit` - The number of expressions that can be evaluated before
+    ///   the program panics, or `None` if the program can run forever.
+    /// * `output` - The writer that the standard output of the program is written to.
+    pub fn new(
+        programs: &[&'a TypedProgram],
+        interner: &'a Interner<String>,
+        checked_arithmetic: bool,
+        step_limit: Option<usize>,
+        output: &'a mut dyn Write,
+    ) -> Result<Self, Vec<Error>> {
+        check_duplicate_definitions(programs, interner)?;
+        let functions = programs
+            .iter()
+            .flat_map(|program| program.functions.iter())
+            .map(|function| (function.prototype.name, function))
+            .collect::<HashMap<_, _>>();
+
+        let externs = resolve_externs(programs, interner)?;
+        Ok(Self {
+            interner,
+            functions,
+            externs,
+            checked_arithmetic,
+            arguments: vec![],
+            output,
+            stack_start: 0,
+            step_limit,
+            steps: 0,
+        })
+    }
+
+    /// Runs the `main` function of the programs and returns how the program
+    /// stopped. Returns an error if none of the programs define a `main`
+    /// function that takes no parameters and returns `Void` or an `Int`.
+    /// The current thread must have a stack of at least `STACK_SIZE` bytes.
+    ///
+    /// # Arguments
+    /// * `arguments` - The command-line arguments of the program, starting with its name.
+    pub fn run_main(&mut self, arguments: &[&str]) -> Result<Exit, Vec<Error>> {
+        let main = self
+            .functions
+            .values()
+            .copied()
+            .find(|function| {
+                self.interner.get(function.prototype.name) == "main"
+                    && function.prototype.parameters.is_empty()
+                    && matches!(function.prototype.return_type, Type::Void | Type::Int)
+            })
+            .ok_or_else(|| vec![Error::MissingEntryPoint])?;
+
+        self.arguments = arguments
+            .iter()
+            .map(|argument| argument.to_string())
+            .collect();
+        self.stack_start = stack_address();
+        let exit = match self.call(main, vec![], main.prototype.span) {
+            // The exit code is truncated to the `int` returned by the C `main`.
+            Ok(Value::Int(code)) => Exit::Code(code as i32),
+            Ok(_) => Exit::Code(0),
+            Err(Interrupt::Exit(code)) => Exit::Code(code),
+            Err(Interrupt::Panic(panic)) => Exit::Panic(panic),
+            Err(Interrupt::Return(_)) => unreachable!(),
+        };
+
+        let _ = self.output.flush();
+        Ok(exit)
+    }
+
+    /// Calls a function defined by one of the programs.
+    ///
+    /// # Arguments
+    /// * `function` - The function to call.
+    /// * `arguments` - The values of the arguments.
+    /// * `span` - The `Span` of the call, reported if the calls are nested too deeply.
+    fn call(
+        &mut self,
+        function: &'a TypedFunction,
+        arguments: Vec<Value>,
+        span: Span,
+    ) -> Result<Value, Interrupt> {
+        let stack_used = self.stack_start.abs_diff(stack_address());
+        if stack_used > STACK_SIZE - STACK_RESERVE {
+            return Err(self.panic(span, "stack overflow"));
+        }
+
+        let mut variables = function
+            .prototype
+            .parameters
+            .iter()
+            .map(|parameter| parameter.name)
+            .zip(arguments)
+            .collect::<HashMap<_, _>>();
+        let value = self.evaluate(&function.body, &mut variables);
+
+        let value = match value {
+            Ok(value) | Err(Interrupt::Return(value)) => value,
+            Err(interrupt) => return Err(interrupt),
+        };
+
+        if function.prototype.return_type == Type::Void {
+            Ok(Value::Void)
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Evaluates an expression of the function that is being called.
+    ///
+    /// # Arguments
+    /// * `expression` - The expression to evaluate.
+    /// * `variables` - The parameters and the `let` variables of the function.
+    ///   Like in the generated code, a variable lives until the function returns.
+    fn evaluate(
+        &mut self,
+        expression: &'a TypedExpression,
+        variables: &mut HashMap<usize, Value>,
+    ) -> Result<Value, Interrupt> {
+        if Some(self.steps) == self.step_limit {
+            return Err(self.panic(expression.0, "the step limit was reached"));
+        }
+
+        self.steps += 1;
+        match expression.1 {
+            TypedExpressionKind::Int(value) => Ok(Value::Int(value)),
+            TypedExpressionKind::Float(value) => Ok(Value::Float(value)),
+            TypedExpressionKind::Boolean(value) => Ok(Value::Boolean(value)),
+            TypedExpressionKind::Char(value) => Ok(Value::Char(value as u8)),
+            TypedExpressionKind::Identifier(ref identifier) => Ok(variables[&identifier.id]),
+            TypedExpressionKind::Unary(ref unary) => {
+                self.evaluate_unary(unary, expression.0, variables)
+            }
+            TypedExpressionKind::Binary(ref binary) => self.evaluate_binary(binary, variables),
+            TypedExpressionKind::If(ref typed_if) => {
+                if self.evaluate(&typed_if.condition, variables)?.as_boolean() {
+                    self.evaluate(&typed_if.then_branch, variables)
+                } else if let Some(ref else_branch) = typed_if.else_branch {
+                    self.evaluate(else_branch, variables)
+                } else {
+                    Ok(Value::Void)
+                }
+            }
+            TypedExpressionKind::Let(ref typed_let) => {
+                let value = self.evaluate(&typed_let.expression, variables)?;
+                variables.insert(typed_let.name.1.id, value);
+                Ok(Value::Void)
+            }
+            TypedExpressionKind::Block(ref expressions) => {
+                expressions.iter().try_fold(Value::Void, |_, expression| {
+                    self.evaluate(expression, variables)
+                })
+            }
+            TypedExpressionKind::Application(ref application) => {
+                self.evaluate_application(application, expression.0, variables)
+            }
+            TypedExpressionKind::While(ref typed_while) => {
+                while self
+                    .evaluate(&typed_while.condition, variables)?
+                    .as_boolean()
+                {
+                    self.evaluate(&typed_while.expression, variables)?;
+                }
+
+                Ok(Value::Void)
+            }
+            TypedExpressionKind::Return(ref value) => {
+                let value = match value {
+                    Some(value) => self.evaluate(value, variables)?,
+                    None => Value::Void,
+                };
+
+                Err(Interrupt::Return(value))
+            }
+            TypedExpressionKind::Panic(message) => {
+                Err(self.panic(expression.0, self.interner.get(message)))
+            }
+            TypedExpressionKind::Assert(ref condition) => {
+                if self.evaluate(condition, variables)?.as_boolean() {
+                    Ok(Value::Void)
+                } else {
+                    Err(self.panic(expression.0, "assertion failed"))
+                }
+            }
+            TypedExpressionKind::AssertEq(ref assert_eq) => {
+                let left = self.evaluate(&assert_eq.left, variables)?;
+                let right = self.evaluate(&assert_eq.right, variables)?;
+                if left == right {
+                    Ok(Value::Void)
+                } else {
+                    Err(self.panic(expression.0, "assertion failed: left == right"))
+                }
+            }
+            // Programs with syntax errors are never interpreted.
+            TypedExpressionKind::Error => unreachable!(),
+        }
+    }
+
+    /// Evaluates a unary operation. Negating the minimum integer overflows.
+    ///
+    /// # Arguments
+    /// * `unary` - The `TypedUnary` to evaluate.
+    /// * `span` - The `Span` of the unary expression.
+    /// * `variables` - The parameters and the `let` variables of the function.
+    fn evaluate_unary(
+        &mut self,
+        unary: &'a TypedUnary,
+        span: Span,
+        variables: &mut HashMap<usize, Value>,
+    ) -> Result<Value, Interrupt> {
+        let value = self.evaluate(&unary.expression, variables)?;
+        let value = match (unary.operation, value) {
+            (UnaryOperation::Plus, value) => value,
+            (UnaryOperation::Minus, Value::Int(value)) => Value::Int(self.check_overflow(
+                value.overflowing_neg(),
+                span,
+                "attempt to negate with overflow",
+            )?),
+            (UnaryOperation::Minus, Value::Float(value)) => Value::Float(-value),
+            (UnaryOperation::Not, Value::Boolean(value)) => Value::Boolean(!value),
+            _ => unreachable!(),
+        };
+
+        Ok(value)
+    }
+
+    /// Evaluates a binary operation. Both operands are always evaluated,
+    /// including those of `and` and `or`. Characters are compared and added
+    /// as signed bytes, as they are in the generated code.
+    ///
+    /// # Arguments
+    /// * `binary` - The `TypedBinary` to evaluate.
+    /// * `variables` - The parameters and the `let` variables of the function.
+    fn evaluate_binary(
+        &mut self,
+        binary: &'a TypedBinary,
+        variables: &mut HashMap<usize, Value>,
+    ) -> Result<Value, Interrupt> {
+        let left = self.evaluate(&binary.left, variables)?;
+        let right = self.evaluate(&binary.right, variables)?;
+        let span = binary.operation_span;
+        let value = match (binary.operation, left, right) {
+            (BinaryOperation::Plus, Value::Int(left), Value::Int(right)) => {
+                Value::Int(self.check_overflow(
+                    left.overflowing_add(right),
+                    span,
+                    "attempt to add with overflow",
+                )?)
+            }
+            (BinaryOperation::Plus, Value::Char(left), Value::Char(right)) => {
+                let value = self.check_overflow(
+                    (left as i8).overflowing_add(right as i8),
+                    span,
+                    "attempt to add with overflow",
+                )?;
+                Value::Char(value as u8)
+            }
+            (BinaryOperation::Minus, Value::Int(left), Value::Int(right)) => {
+                Value::Int(self.check_overflow(
+                    left.overflowing_sub(right),
+                    span,
+                    "attempt to subtract with overflow",
+                )?)
+            }
+            (BinaryOperation::Multiply, Value::Int(left), Value::Int(right)) => {
+                Value::Int(self.check_overflow(
+                    left.overflowing_mul(right),
+                    span,
+                    "attempt to multiply with overflow",
+                )?)
+            }
+            (BinaryOperation::Divide, Value::Int(_), Value::Int(0)) => {
+                return Err(self.panic(span, "attempt to divide by zero"));
+            }
+            (BinaryOperation::Divide, Value::Int(left), Value::Int(right)) => {
+                Value::Int(self.check_overflow(
+                    left.overflowing_div(right),
+                    span,
+                    "attempt to divide with overflow",
+                )?)
+            }
+            (BinaryOperation::Plus, Value::Float(left), Value::Float(right)) => {
+                Value::Float(left + right)
+            }
+            (BinaryOperation::Minus, Value::Float(left), Value::Float(right)) => {
+                Value::Float(left - right)
+            }
+            (BinaryOperation::Multiply, Value::Float(left), Value::Float(right)) => {
+                Value::Float(left * right)
+            }
+            (BinaryOperation::Divide, Value::Float(left), Value::Float(right)) => {
+                Value::Float(left / right)
+            }
+            (BinaryOperation::Equals, left, right) => Value::Boolean(left == right),
+            (BinaryOperation::Or, Value::Boolean(left), Value::Boolean(right)) => {
+                Value::Boolean(left || right)
+            }
+            (BinaryOperation::And, Value::Boolean(left), Value::Boolean(right)) => {
+                Value::Boolean(left && right)
+            }
+            (operation, Value::Float(left), Value::Float(right)) => {
+                Value::Boolean(compare(operation, left, right))
+            }
+            (operation, Value::Char(left), Value::Char(right)) => {
+                Value::Boolean(compare(operation, left as i8, right as i8))
+            }
+            (operation, Value::Int(left), Value::Int(right)) => {
+                Value::Boolean(compare(operation, left, right))
+            }
+            _ => unreachable!(),
+        };
+
+        Ok(value)
+    }
+
+    fn evaluate_application(
+        &mut self,
+        application: &'a TypedApplication,
+        span: Span,
+        variables: &mut HashMap<usize, Value>,
+    ) -> Result<Value, Interrupt> {
+        let mut arguments = vec![];
+        for parameter in &application.parameters {
+            arguments.push(self.evaluate(parameter, variables)?);
+        }
+
+        let name = application.function_name.1;
+        if let Some(function) = self.functions.get(&name).copied() {
+            return self.call(function, arguments, span);
+        }
+
+        if let Some((extern_function, return_type)) = self.externs.get(&name).copied() {
+            let value = extern_function
+                .call(&arguments, self.output)
+                .map_err(Interrupt::Exit)?;
+            return Ok(if return_type == Type::Void {
+                Value::Void
+            } else {
+                value
+            });
+        }
+
+        let builtin = Builtin::from_name(self.interner.get(name)).unwrap();
+        Ok(self.evaluate_builtin(builtin, &arguments))
+    }
+
+    /// Evaluates a call to a function that is provided by the compiler.
+    ///
+    /// # Arguments
+    /// * `builtin` - The `Builtin` being called.
+    /// * `arguments` - The values of the arguments.
+    fn evaluate_builtin(&self, builtin: Builtin, arguments: &[Value]) -> Value {
+        match builtin {
+            // The first argument is always the name of the program, which is skipped.
+            Builtin::ArgCount => Value::Int(self.arguments.len() as i64 - 1),
+            Builtin::Arg => {
+                let index = arguments[0].as_int();
+                let argument = if index >= 0 {
+                    self.arguments.get(index as usize + 1)
+                } else {
+                    None
+                };
+
+                Value::Int(argument.map_or(0, |argument| parse_integer(argument)))
+            }
+            Builtin::WrappingAdd => {
+                Value::Int(arguments[0].as_int().wrapping_add(arguments[1].as_int()))
+            }
+            Builtin::SaturatingAdd => {
+                Value::Int(arguments[0].as_int().saturating_add(arguments[1].as_int()))
+            }
+        }
+    }
+
+    /// Gets the result of an integer operation, which panics with the message
+    /// when the operation overflowed and checked arithmetic is enabled.
+    /// Otherwise, the result wraps around.
+    ///
+    /// # Arguments
+    /// * `result` - The result of the operation and whether it overflowed.
+    /// * `span` - The `Span` of the operator.
+    /// * `message` - The message of the panic.
+    fn check_overflow<T>(
+        &self,
+        (value, overflows): (T, bool),
+        span: Span,
+        message: &str,
+    ) -> Result<T, Interrupt> {
+        if overflows && self.checked_arithmetic {
+            Err(self.panic(span, message))
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Creates the interrupt of a panic at the given span.
+    ///
+    /// # Arguments
+    /// * `span` - The `Span` reported as the location of the panic.
+    /// * `message` - The message of the panic.
+    fn panic(&self, span: Span, message: &str) -> Interrupt {
+        Interrupt::Panic(Panic {
+            span,
+            message: message.to_string(),
+        })
+    }
+}
+
+/// Gets the address of a variable on the stack of the current thread,
+/// which shows how far the stack has grown.
+fn stack_address() -> usize {
+    let marker = 0u8;
+    hint::black_box(&marker) as *const u8 as usize
+}
+
+/// Compares two values with a comparison operator. The comparisons of
+/// floats are ordered, so they are false when either float is NaN.
+///
+/// # Arguments
+/// * `operation` - The comparison operator.
+/// * `left` - The left operand.
+/// * `right` - The right operand.
+fn compare<T: PartialOrd>(operation: BinaryOperation, left: T, right: T) -> bool {
+    match operation {
+        BinaryOperation::LessThan => left < right,
+        BinaryOperation::GreaterThan => left > right,
+        BinaryOperation::LessThanEquals => left <= right,
+        BinaryOperation::GreaterThanEquals => left >= right,
+        _ => unreachable!(),
+    }
+}
+
+/// Parses the integer at the start of a command-line argument like `atoll`,
+/// which the generated code uses for the `arg` builtin. Leading whitespace
+/// is skipped, parsing stops at the first character that is not a digit,
+/// and values that do not fit in an `Int` are clamped to its bounds.
+///
+/// # Arguments
+/// * `argument` - The command-line argument.
+pub fn parse_integer(argument: &str) -> i64 {
+    let argument = argument.trim_start();
+    let (is_negative, digits) = match argument.as_bytes().first() {
+        Some(b'-') => (true, &argument[1..]),
+        Some(b'+') => (false, &argument[1..]),
+        _ => (false, argument),
+    };
+
+    let mut value: i64 = 0;
+    for digit in digits.bytes().take_while(u8::is_ascii_digit) {
+        let digit = (digit - b'0') as i64;
+        let next = value.checked_mul(10).and_then(|value| {
+            if is_negative {
+                value.checked_sub(digit)
+            } else {
+                value.checked_add(digit)
+            }
+        });
+        match next {
+            Some(next) => value = next,
+            None if is_negative => return i64::MIN,
+            None => return i64::MAX,
+        }
+    }
+
+    value
+}
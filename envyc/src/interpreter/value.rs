@@ -0,0 +1,41 @@
+/// Enum that details the values produced by the `Interpreter`.
+/// Each value has the same representation as in the generated code,
+/// so a `Char` is a single byte, as it is truncated to an `i8` by LLVM.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Boolean(bool),
+    Char(u8),
+    /// The value of expressions of type `Void`, such as a `while` expression.
+    Void,
+}
+
+impl Value {
+    /// Gets the value as an integer. Characters are widened to an integer,
+    /// as they are when they are passed to a C function that takes an `int`.
+    pub fn as_int(self) -> i64 {
+        match self {
+            Value::Int(value) => value,
+            Value::Char(value) => value as i64,
+            Value::Boolean(value) => value as i64,
+            Value::Float(_) | Value::Void => unreachable!(),
+        }
+    }
+
+    /// Gets the value as a floating point number.
+    pub fn as_float(self) -> f64 {
+        match self {
+            Value::Float(value) => value,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Gets the value as a boolean.
+    pub fn as_boolean(self) -> bool {
+        match self {
+            Value::Boolean(value) => value,
+            _ => unreachable!(),
+        }
+    }
+}
@@ -1,11 +1,14 @@
 pub mod token;
 
-use crate::{error::Error, error::Span, interner::Interner};
+use crate::{
+    error::{Error, FileId, Span},
+    interner::Interner,
+};
 
 use self::token::{Token, TokenKind};
 
 /// Represents an internal type to simplify the code.
-type LexResult<'a> = Result<Token<'a>, Error<'a>>;
+type LexResult = Result<Token, Error>;
 
 /// Struct that transforms the input into a vector of tokens.
 /// The `Lexer` operates on the slice of bytes to
@@ -16,28 +19,21 @@ type LexResult<'a> = Result<Token<'a>, Error<'a>>;
 /// the same token type are grouped together; finally, the
 /// `Token` is constructed.
 pub struct Lexer<'a> {
-    // The name of the file currently being analyzed.
-    file_name: &'a str,
+    // The id of the file currently being analyzed.
+    file_id: FileId,
     // The bytes of the file being analyzed.
     bytes: &'a [u8],
     // The current index in the bytes slice.
+    // This is used to construct `Span` information.
     index: usize,
-    // The current line in the input.
-    // This, along with the current_column, is used to construct
-    // `Span` information.
-    current_line: usize,
-    // The current column in the input.
-    current_column: usize,
 }
 
 impl<'a> Lexer<'a> {
-    pub fn new(file_name: &'a str, bytes: &'a [u8]) -> Self {
+    pub fn new(file_id: FileId, bytes: &'a [u8]) -> Self {
         Self {
-            file_name,
+            file_id,
             bytes,
             index: 0,
-            current_line: 1,
-            current_column: 0,
         }
     }
 
@@ -50,31 +46,27 @@ impl<'a> Lexer<'a> {
     pub fn get_tokens(
         &mut self,
         interner: &mut Interner<String>,
-    ) -> Result<Vec<Token<'a>>, Vec<Error<'a>>> {
+    ) -> Result<Vec<Token>, Vec<Error>> {
         let mut tokens = vec![];
         let mut errors = vec![];
         while let Some(byte) = self.next() {
             match byte {
                 whitespace if whitespace.is_ascii_whitespace() => {
                     tokens.push((
-                        self.make_span(self.current_column),
+                        self.make_span(self.index - 1),
                         TokenKind::Whitespace(whitespace as char),
                     ));
-                    if whitespace == b'\n' {
-                        self.current_line += 1;
-                        self.current_column = 0;
-                    }
                 }
                 b'-' if self.peek().map_or(false, |digit| digit.is_ascii_digit()) => {
-                    let start_column = self.current_column;
+                    let start = self.index - 1;
                     let digit: i64 = (self.next().unwrap() - b'0').into();
-                    match self.form_number(-digit, start_column) {
+                    match self.form_number(-digit, start) {
                         Ok(token) => tokens.push(token),
                         Err(error) => errors.push(error),
                     }
                 }
                 digit if digit.is_ascii_digit() => {
-                    match self.form_number((digit - b'0').into(), self.current_column) {
+                    match self.form_number((digit - b'0').into(), self.index - 1) {
                         Ok(token) => tokens.push(token),
                         Err(error) => errors.push(error),
                     }
@@ -83,80 +75,64 @@ impl<'a> Lexer<'a> {
                     Ok(token) => tokens.push(token),
                     Err(error) => errors.push(error),
                 },
+                b'"' => match self.form_string(interner) {
+                    Ok(token) => tokens.push(token),
+                    Err(error) => errors.push(error),
+                },
                 letter if letter.is_ascii_alphabetic() || letter == b'_' => {
                     match self.form_word(letter as char, interner) {
                         Ok(token) => tokens.push(token),
                         Err(error) => errors.push(error),
                     }
                 }
-                b'+' => tokens.push((self.make_span(self.current_column), TokenKind::Plus)),
-                b'-' => tokens.push((self.make_span(self.current_column), TokenKind::Minus)),
-                b'*' => tokens.push((self.make_span(self.current_column), TokenKind::Star)),
-                b'/' => tokens.push((self.make_span(self.current_column), TokenKind::Slash)),
-                b'%' => tokens.push((self.make_span(self.current_column), TokenKind::PercentSign)),
+                b'+' => tokens.push((self.make_span(self.index - 1), TokenKind::Plus)),
+                b'-' => tokens.push((self.make_span(self.index - 1), TokenKind::Minus)),
+                b'*' => tokens.push((self.make_span(self.index - 1), TokenKind::Star)),
+                b'/' => tokens.push((self.make_span(self.index - 1), TokenKind::Slash)),
+                b'%' => tokens.push((self.make_span(self.index - 1), TokenKind::PercentSign)),
                 b'!' if self.peek() == Some(b'=') => {
-                    let start_column = self.current_column;
+                    let start = self.index - 1;
                     self.next();
-                    tokens.push((
-                        self.make_span(start_column),
-                        TokenKind::ExclamationEqualSign,
-                    ))
+                    tokens.push((self.make_span(start), TokenKind::ExclamationEqualSign))
                 }
-                b'=' => tokens.push((self.make_span(self.current_column), TokenKind::EqualSign)),
-                b'(' => tokens.push((
-                    self.make_span(self.current_column),
-                    TokenKind::LeftParenthesis,
-                )),
-                b')' => tokens.push((
-                    self.make_span(self.current_column),
-                    TokenKind::RightParenthesis,
-                )),
-                b'{' => tokens.push((
-                    self.make_span(self.current_column),
-                    TokenKind::LeftCurlyBrace,
-                )),
-                b'}' => tokens.push((
-                    self.make_span(self.current_column),
-                    TokenKind::RightCurlyBrace,
+                b'=' => tokens.push((self.make_span(self.index - 1), TokenKind::EqualSign)),
+                b'(' => tokens.push((self.make_span(self.index - 1), TokenKind::LeftParenthesis)),
+                b')' => tokens.push((self.make_span(self.index - 1), TokenKind::RightParenthesis)),
+                b'{' => tokens.push((self.make_span(self.index - 1), TokenKind::LeftCurlyBrace)),
+                b'}' => tokens.push((self.make_span(self.index - 1), TokenKind::RightCurlyBrace)),
+                b'[' => tokens.push((self.make_span(self.index - 1), TokenKind::LeftSquareBracket)),
+                b']' => tokens.push((
+                    self.make_span(self.index - 1),
+                    TokenKind::RightSquareBracket,
                 )),
                 b'<' if self.peek() == Some(b'=') => {
-                    let start_column = self.current_column;
+                    let start = self.index - 1;
                     self.next();
-                    tokens.push((self.make_span(start_column), TokenKind::LessThanEqualSign))
+                    tokens.push((self.make_span(start), TokenKind::LessThanEqualSign))
                 }
-                b'<' => tokens.push((
-                    self.make_span(self.current_column),
-                    TokenKind::LeftAngleBracket,
-                )),
+                b'<' => tokens.push((self.make_span(self.index - 1), TokenKind::LeftAngleBracket)),
                 b'>' if self.peek() == Some(b'=') => {
-                    let start_column = self.current_column;
+                    let start = self.index - 1;
                     self.next();
-                    tokens.push((
-                        self.make_span(start_column),
-                        TokenKind::GreaterThanEqualSign,
-                    ))
+                    tokens.push((self.make_span(start), TokenKind::GreaterThanEqualSign))
                 }
-                b'>' => tokens.push((
-                    self.make_span(self.current_column),
-                    TokenKind::RightAngleBracket,
-                )),
-                b',' => tokens.push((self.make_span(self.current_column), TokenKind::Comma)),
+                b'>' => tokens.push((self.make_span(self.index - 1), TokenKind::RightAngleBracket)),
+                b',' => tokens.push((self.make_span(self.index - 1), TokenKind::Comma)),
                 b':' if self.peek() == Some(b'=') => {
-                    let start_column = self.current_column;
+                    let start = self.index - 1;
                     self.next();
-                    tokens.push((self.make_span(start_column), TokenKind::ColonEqualSign))
+                    tokens.push((self.make_span(start), TokenKind::ColonEqualSign))
                 }
                 b':' if self.peek() == Some(b':') => {
-                    let start_column = self.current_column;
+                    let start = self.index - 1;
                     self.next();
-                    tokens.push((self.make_span(start_column), TokenKind::ColonColon))
+                    tokens.push((self.make_span(start), TokenKind::ColonColon))
                 }
-                b':' => tokens.push((self.make_span(self.current_column), TokenKind::Colon)),
-                b';' => tokens.push((self.make_span(self.current_column), TokenKind::SemiColon)),
+                b':' => tokens.push((self.make_span(self.index - 1), TokenKind::Colon)),
+                b';' => tokens.push((self.make_span(self.index - 1), TokenKind::SemiColon)),
+                b'#' => tokens.push((self.make_span(self.index - 1), TokenKind::Hash)),
                 b'\0' => break,
-                _ => errors.push(Error::UnrecognizedCharacter(
-                    self.make_span(self.current_column),
-                )),
+                _ => errors.push(Error::UnrecognizedCharacter(self.make_span(self.index - 1))),
             }
         }
 
@@ -173,8 +149,8 @@ impl<'a> Lexer<'a> {
     ///
     /// # Arguments
     /// * `digit` - The first digit of the number.
-    /// * `start_column` - The starting column of the number. This changes when dealing with negative numbers.
-    fn form_number(&mut self, digit: i64, start_column: usize) -> LexResult<'a> {
+    /// * `start` - The index of the first byte of the number. This changes when dealing with negative numbers.
+    fn form_number(&mut self, digit: i64, start: usize) -> LexResult {
         let mut number = digit.to_string();
         let mut seen_decimal_point = false;
         while let Some(next) = self.peek() {
@@ -192,7 +168,7 @@ impl<'a> Lexer<'a> {
             self.next();
         }
 
-        let span = self.make_span(start_column);
+        let span = self.make_span(start);
         if seen_decimal_point {
             match number.parse::<f64>() {
                 Ok(float) => Ok((span, TokenKind::FloatLiteral(float))),
@@ -207,48 +183,70 @@ impl<'a> Lexer<'a> {
     }
 
     /// Walks through the character and ensures that exactly one character is represented.
-    fn form_char(&mut self) -> LexResult<'a> {
-        let (start_line, start_column) = (self.current_line, self.current_column);
+    fn form_char(&mut self) -> LexResult {
+        let start = self.index - 1;
         let ch = if let Some(ch) = self.next() {
             ch as char
         } else {
-            return Err(Error::UnexpectedEndOfInput(Span::new(
-                self.file_name,
-                start_line,
-                start_column,
-                self.current_line,
-                self.current_column,
-            )));
+            return Err(Error::UnexpectedEndOfInput(self.make_span(start)));
         };
 
         if let Some(b'\'') = self.next() {
-            let span = Span::new(
-                self.file_name,
-                start_line,
-                start_column,
-                self.current_line,
-                self.current_column,
-            );
-            Ok((span, TokenKind::CharLiteral(ch)))
+            Ok((self.make_span(start), TokenKind::CharLiteral(ch)))
         } else {
-            return Err(Error::UnterminatedChar(Span::new(
-                self.file_name,
-                start_line,
-                start_column,
-                self.current_line,
-                self.current_column,
-            )));
+            Err(Error::UnterminatedChar(self.make_span(start)))
         }
     }
 
+    /// Walks through the bytes until the closing quote and forms a string literal.
+    /// The escape sequences `\\`, `\"`, `\n` and `\t` are supported. A string
+    /// literal may not span multiple lines.
+    ///
+    /// # Arguments
+    /// * `interner` - The `Interner` which stores the different string literals.
+    fn form_string(&mut self, interner: &mut Interner<String>) -> LexResult {
+        let start = self.index - 1;
+        let mut string = Vec::new();
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.next();
+                    break;
+                }
+                Some(b'\\') => {
+                    self.next();
+                    match self.peek() {
+                        Some(b'n') => string.push(b'\n'),
+                        Some(b't') => string.push(b'\t'),
+                        Some(b'"') => string.push(b'"'),
+                        Some(b'\\') => string.push(b'\\'),
+                        _ => return Err(Error::UnterminatedString(self.make_span(start))),
+                    }
+
+                    self.next();
+                }
+                Some(b'\n') | None => return Err(Error::UnterminatedString(self.make_span(start))),
+                Some(byte) => {
+                    string.push(byte);
+                    self.next();
+                }
+            }
+        }
+
+        let string =
+            String::from_utf8(string).map_err(|_| Error::InvalidString(self.make_span(start)))?;
+        let id = interner.insert(string);
+        Ok((self.make_span(start), TokenKind::StringLiteral(id)))
+    }
+
     /// Greedily walks through consecutive bytes and forms the largest possible word.
     /// This word may represent a type, a literal, or an identifier.
     ///
     /// # Arguments
     /// * `leter` - The character with which the word started with.
     /// * `interner` - The `Interner` which stores the different string literals.
-    fn form_word(&mut self, letter: char, interner: &mut Interner<String>) -> LexResult<'a> {
-        let start_column = self.current_column;
+    fn form_word(&mut self, letter: char, interner: &mut Interner<String>) -> LexResult {
+        let start = self.index - 1;
         let mut word = letter.to_string();
         while let Some(next) = self.peek() {
             if next.is_ascii_whitespace() {
@@ -261,33 +259,30 @@ impl<'a> Lexer<'a> {
         }
 
         match word.as_str() {
-            "Void" => Ok((self.make_span(start_column), TokenKind::Void)),
-            "Int" => Ok((self.make_span(start_column), TokenKind::Int)),
-            "Float" => Ok((self.make_span(start_column), TokenKind::Float)),
-            "Boolean" => Ok((self.make_span(start_column), TokenKind::Boolean)),
-            "Char" => Ok((self.make_span(start_column), TokenKind::Char)),
-            "true" => Ok((
-                self.make_span(start_column),
-                TokenKind::BooleanLiteral(true),
-            )),
-            "false" => Ok((
-                self.make_span(start_column),
-                TokenKind::BooleanLiteral(false),
-            )),
-            "not" => Ok((self.make_span(start_column), TokenKind::Not)),
-            "or" => Ok((self.make_span(start_column), TokenKind::Or)),
-            "and" => Ok((self.make_span(start_column), TokenKind::And)),
-            "let" => Ok((self.make_span(start_column), TokenKind::Let)),
-            "if" => Ok((self.make_span(start_column), TokenKind::If)),
-            "then" => Ok((self.make_span(start_column), TokenKind::Then)),
-            "else" => Ok((self.make_span(start_column), TokenKind::Else)),
-            "while" => Ok((self.make_span(start_column), TokenKind::While)),
-            "define" => Ok((self.make_span(start_column), TokenKind::Define)),
-            "extern" => Ok((self.make_span(start_column), TokenKind::Extern)),
-            "return" => Ok((self.make_span(start_column), TokenKind::Return)),
+            "Void" => Ok((self.make_span(start), TokenKind::Void)),
+            "Int" => Ok((self.make_span(start), TokenKind::Int)),
+            "Float" => Ok((self.make_span(start), TokenKind::Float)),
+            "Boolean" => Ok((self.make_span(start), TokenKind::Boolean)),
+            "Char" => Ok((self.make_span(start), TokenKind::Char)),
+            "true" => Ok((self.make_span(start), TokenKind::BooleanLiteral(true))),
+            "false" => Ok((self.make_span(start), TokenKind::BooleanLiteral(false))),
+            "not" => Ok((self.make_span(start), TokenKind::Not)),
+            "or" => Ok((self.make_span(start), TokenKind::Or)),
+            "and" => Ok((self.make_span(start), TokenKind::And)),
+            "let" => Ok((self.make_span(start), TokenKind::Let)),
+            "if" => Ok((self.make_span(start), TokenKind::If)),
+            "then" => Ok((self.make_span(start), TokenKind::Then)),
+            "else" => Ok((self.make_span(start), TokenKind::Else)),
+            "while" => Ok((self.make_span(start), TokenKind::While)),
+            "define" => Ok((self.make_span(start), TokenKind::Define)),
+            "extern" => Ok((self.make_span(start), TokenKind::Extern)),
+            "return" => Ok((self.make_span(start), TokenKind::Return)),
+            "panic" => Ok((self.make_span(start), TokenKind::Panic)),
+            "assert" => Ok((self.make_span(start), TokenKind::Assert)),
+            "assert_eq" => Ok((self.make_span(start), TokenKind::AssertEq)),
             _ => {
                 let id = interner.insert(word);
-                Ok((self.make_span(start_column), TokenKind::Identifier(id)))
+                Ok((self.make_span(start), TokenKind::Identifier(id)))
             }
         }
     }
@@ -297,26 +292,20 @@ impl<'a> Lexer<'a> {
         self.bytes.get(self.index).copied()
     }
 
-    /// Consumes the next byte and increment both the index and
-    /// the current column.
+    /// Consumes the next byte and increments the index.
     fn next(&mut self) -> Option<u8> {
         self.index += 1;
-        self.current_column += 1;
         self.bytes.get(self.index - 1).copied()
     }
 
-    /// Helper method that creates a `Span` based on
-    /// the start_column.
+    /// Helper method that creates a `Span` from the given start
+    /// to the current index. The span never extends past the end
+    /// of the input, even after the end of the input was reached.
     ///
     /// # Arguments
-    /// * `start_column` - The starting column of the `Token`.
-    fn make_span(&self, start_column: usize) -> Span<'a> {
-        Span::new(
-            self.file_name,
-            self.current_line,
-            start_column,
-            self.current_line,
-            self.current_column,
-        )
+    /// * `start` - The index of the first byte of the `Token`.
+    fn make_span(&self, start: usize) -> Span {
+        let end = self.index.min(self.bytes.len());
+        Span::new(self.file_id, start.min(end), end)
     }
 }
@@ -5,7 +5,7 @@ use crate::error::Span;
 /// Represents a token that is generated by the `Lexer`.
 /// Each token consists of a span (the location information of the token)
 /// and the kind of the token.
-pub type Token<'a> = (Span<'a>, TokenKind);
+pub type Token = (Span, TokenKind);
 
 /// Enum that details the different types of tokens that can be produced
 /// by the `Lexer`. The `TokenKind` should strive to only store types that
@@ -24,6 +24,9 @@ pub enum TokenKind {
     FloatLiteral(f64),
     BooleanLiteral(bool),
     CharLiteral(char),
+    // The actual value for the `StringLiteral` is
+    // stored in the `Interner`, similar to the `Identifier`.
+    StringLiteral(usize),
     // The actual value for the `Identifier` are
     // stored in the `Interner` to reduce redundency in values. Instead,
     // the id's are stored in the variant.
@@ -33,6 +36,8 @@ pub enum TokenKind {
     RightParenthesis,
     LeftCurlyBrace,
     RightCurlyBrace,
+    LeftSquareBracket,
+    RightSquareBracket,
     LeftAngleBracket,
     RightAngleBracket,
     Plus,
@@ -49,6 +54,7 @@ pub enum TokenKind {
     Colon,
     SemiColon,
     ColonColon,
+    Hash,
 
     Not,
     Or,
@@ -61,6 +67,9 @@ pub enum TokenKind {
     Define,
     Extern,
     Return,
+    Panic,
+    Assert,
+    AssertEq,
 }
 
 impl Display for TokenKind {
@@ -76,11 +85,14 @@ impl Display for TokenKind {
             TokenKind::FloatLiteral(_) => write!(f, "float literal"),
             TokenKind::BooleanLiteral(_) => write!(f, "boolean literal"),
             TokenKind::CharLiteral(_) => write!(f, "char literal"),
+            TokenKind::StringLiteral(_) => write!(f, "string literal"),
             TokenKind::Identifier(_) => write!(f, "identifier"),
             TokenKind::LeftParenthesis => write!(f, "("),
             TokenKind::RightParenthesis => write!(f, ")"),
             TokenKind::LeftCurlyBrace => write!(f, "{{"),
             TokenKind::RightCurlyBrace => write!(f, "}}"),
+            TokenKind::LeftSquareBracket => write!(f, "["),
+            TokenKind::RightSquareBracket => write!(f, "]"),
             TokenKind::LeftAngleBracket => write!(f, "<"),
             TokenKind::RightAngleBracket => write!(f, ">"),
             TokenKind::Plus => write!(f, "+"),
@@ -97,6 +109,7 @@ impl Display for TokenKind {
             TokenKind::Colon => write!(f, ":"),
             TokenKind::SemiColon => write!(f, ";"),
             TokenKind::ColonColon => write!(f, "::"),
+            TokenKind::Hash => write!(f, "#"),
             TokenKind::Not => write!(f, "not"),
             TokenKind::Or => write!(f, "or"),
             TokenKind::And => write!(f, "and"),
@@ -108,6 +121,9 @@ impl Display for TokenKind {
             TokenKind::Define => write!(f, "define"),
             TokenKind::Extern => write!(f, "extern"),
             TokenKind::Return => write!(f, "return"),
+            TokenKind::Panic => write!(f, "panic"),
+            TokenKind::Assert => write!(f, "assert"),
+            TokenKind::AssertEq => write!(f, "assert_eq"),
         }
     }
 }
@@ -1,37 +1,59 @@
-use std::{iter::Peekable, path::Path};
+use std::iter::Peekable;
+#[cfg(feature = "llvm")]
+use std::{path::Path, sync::Once};
 
+use builtins::define_builtins;
+#[cfg(feature = "llvm")]
 use codegen::code_generator::CodeGenerator;
 use environment::Environment;
-use error::Error;
+#[cfg(feature = "llvm")]
+use error::SourceMap;
+use error::{Error, FileId};
 use function_table::FunctionTable;
+#[cfg(feature = "llvm")]
 use inkwell::{
     context::Context,
-    passes::{PassManager, PassManagerBuilder},
-    targets::{CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine},
-    OptimizationLevel,
+    module::Module,
+    targets::{
+        CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple,
+    },
 };
 use interner::Interner;
 use lexer::{token::Token, Lexer};
+#[cfg(feature = "llvm")]
+use linker::check_duplicate_definitions;
+use lint::{linter::Linter, LintConfig, LintDiagnostic};
+#[cfg(feature = "llvm")]
+use optimization::{optimize, OptimizationLevel, Pass};
 use parser::{ast::Program, typed_ast::TypedProgram, Parser};
 use semantic_analyzer::{type_check::TypeCheck, types::Type};
 
 use crate::lexer::token::TokenKind;
 
+pub mod builtins;
 pub mod codegen;
 pub mod environment;
 pub mod error;
 pub mod function_table;
 pub mod interner;
+pub mod interpreter;
 pub mod lexer;
+pub mod linker;
+pub mod lint;
+pub mod optimization;
 pub mod parser;
+pub mod printer;
+#[cfg(feature = "llvm")]
+pub mod repl;
 pub mod semantic_analyzer;
+pub mod session;
 
-pub fn lex<'a>(
-    file_path: &'a str,
-    bytes: &'a [u8],
+pub fn lex(
+    file_id: FileId,
+    bytes: &[u8],
     interner: &mut Interner<String>,
-) -> Result<Vec<Token<'a>>, Vec<Error<'a>>> {
-    Lexer::new(file_path, bytes).get_tokens(interner)
+) -> Result<Vec<Token>, Vec<Error>> {
+    Lexer::new(file_id, bytes).get_tokens(interner)
 }
 
 pub fn filter_tokens(tokens: Vec<Token>) -> Peekable<impl Iterator<Item = Token>> {
@@ -41,89 +63,410 @@ pub fn filter_tokens(tokens: Vec<Token>) -> Peekable<impl Iterator<Item = Token>
         .peekable()
 }
 
-pub fn parse<'a>(
-    filtered_tokens: Peekable<impl Iterator<Item = Token<'a>>>,
-) -> Result<Program<'a>, Vec<Error<'a>>> {
+pub fn parse(
+    filtered_tokens: Peekable<impl Iterator<Item = Token>>,
+) -> Result<Program, Vec<Error>> {
     Parser::new(filtered_tokens).parse()
 }
 
-pub fn type_check<'a>(
-    program: Program<'a>,
+pub fn type_check(
+    program: Program,
     env: &mut Environment<Type>,
     function_table: &mut FunctionTable,
-) -> Result<TypedProgram<'a>, Vec<Error<'a>>> {
-    program.check(env, function_table)
+    interner: &mut Interner<String>,
+) -> Result<TypedProgram, Vec<Error>> {
+    define_builtins(env, function_table, interner);
+    program.check(env, function_table, interner)
+}
+
+/// Checks the type checked program for the lints that are not allowed
+/// by the configuration or by the attributes of each function.
+pub fn check_lints(
+    program: &TypedProgram,
+    interner: &Interner<String>,
+    config: &LintConfig,
+    library: bool,
+) -> Vec<LintDiagnostic> {
+    Linter::new(interner, config, library).lint_program(program)
+}
+
+/// Initializes every target supported by LLVM. Registering the targets
+/// is not thread safe, so this only registers them the first time it is
+/// called, which allows files to be compiled on multiple threads.
+#[cfg(feature = "llvm")]
+pub fn initialize_targets() {
+    static INITIALIZE_TARGETS: Once = Once::new();
+    INITIALIZE_TARGETS.call_once(|| {
+        Target::initialize_all(&InitializationConfig {
+            asm_parser: true,
+            asm_printer: true,
+            base: true,
+            disassembler: true,
+            info: true,
+            machine_code: true,
+        })
+    });
+}
+
+/// The kinds of output that can be emitted for a file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EmitKind {
+    /// The tokens produced by the lexer.
+    Tokens,
+    /// The program produced by the parser.
+    Ast,
+    /// The program produced by the type checker, with the type of every expression.
+    TypedAst,
+    /// The textual LLVM IR of the module.
+    LlvmIr,
+    /// The LLVM bitcode of the module.
+    LlvmBitcode,
+    /// The assembly for the target.
+    Assembly,
+    /// An object file for the target.
+    Object,
+}
+
+impl EmitKind {
+    /// All of the kinds of output, in the order the stages produce them.
+    pub const ALL: [EmitKind; 7] = [
+        EmitKind::Tokens,
+        EmitKind::Ast,
+        EmitKind::TypedAst,
+        EmitKind::LlvmIr,
+        EmitKind::LlvmBitcode,
+        EmitKind::Assembly,
+        EmitKind::Object,
+    ];
+
+    /// Gets the name used to refer to this kind of output on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EmitKind::Tokens => "tokens",
+            EmitKind::Ast => "ast",
+            EmitKind::TypedAst => "typed-ast",
+            EmitKind::LlvmIr => "llvm-ir",
+            EmitKind::LlvmBitcode => "llvm-bc",
+            EmitKind::Assembly => "asm",
+            EmitKind::Object => "obj",
+        }
+    }
+
+    /// Finds the kind of output with the given name.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the kind of output.
+    pub fn from_name(name: &str) -> Option<EmitKind> {
+        EmitKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.name() == name)
+    }
+
+    /// Determines whether this kind of output is produced before code
+    /// generation, so that it is printed by the `Session` instead of LLVM.
+    pub fn is_front_end(&self) -> bool {
+        matches!(self, EmitKind::Tokens | EmitKind::Ast | EmitKind::TypedAst)
+    }
+
+    /// Gets the extension of the file that this kind of output is written to.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            EmitKind::Tokens => "tokens",
+            EmitKind::Ast => "ast",
+            EmitKind::TypedAst => "typed-ast",
+            EmitKind::LlvmIr => "ll",
+            EmitKind::LlvmBitcode => "bc",
+            EmitKind::Assembly => "s",
+            EmitKind::Object => "o",
+        }
+    }
 }
 
+#[cfg(feature = "llvm")]
 pub struct Config<'a> {
-    pub writing_to_file: bool,
-    pub output_file_path: &'a str,
+    /// The kinds of output to emit. The tokens and the programs are emitted
+    /// before code generation by the `Session`, so when one of them is
+    /// given, the textual LLVM IR is returned instead.
+    pub emit: &'a [EmitKind],
+    /// How much the module is optimized.
+    pub optimization_level: OptimizationLevel,
+    /// The passes to run instead of the pipeline of the optimization level.
+    pub passes: Option<&'a [Pass]>,
+    /// The triple of the target to compile for, or `None` to compile for the host.
+    pub target_triple: Option<&'a str>,
+    /// The CPU of the target, or `None` for a generic CPU.
+    pub target_cpu: Option<&'a str>,
+    /// The features of the target to enable or disable, such as `+avx2`.
+    pub target_features: &'a [String],
+    /// Whether the generated code should be position independent.
+    /// This is required when the object files are linked into a shared library.
+    pub position_independent: bool,
+    /// Whether integer arithmetic traps on overflow and division by zero
+    /// instead of silently wrapping around.
+    pub checked_arithmetic: bool,
+    /// Whether DWARF debug information is generated for debuggers.
+    pub debug_info: bool,
 }
 
-pub fn compile<'a>(
-    program: &TypedProgram<'a>,
-    module_name: &str,
+/// Compiles the type checked program into an LLVM module and returns
+/// the bytes of each kind of output requested by the configuration, in the
+/// order they were requested. The module is generated and optimized once,
+/// and every kind of output is written from it.
+///
+/// # Arguments
+/// * `program` - The type checked program to compile.
+/// * `file_id` - The id of the file that the program is from, which names the LLVM module.
+/// * `interner` - The `Interner` of the names in the program.
+/// * `source_map` - The `SourceMap` containing the file of the program.
+/// * `config` - The configuration of the output.
+/// * `ir_dumps` - The list that the IR is added to after each optimization pass.
+/// * `ice_dumps` - The list that the IR of the partially built module is added
+///   to when LLVM fails to verify a function.
+#[cfg(feature = "llvm")]
+pub fn compile(
+    program: &TypedProgram,
+    file_id: FileId,
     interner: &mut Interner<String>,
-    config: Option<Config<'a>>,
-) -> Result<String, Vec<Error<'a>>> {
+    source_map: &SourceMap,
+    config: &Config,
+    ir_dumps: Option<&mut Vec<String>>,
+    ice_dumps: Option<&mut Vec<String>>,
+) -> Result<Vec<Vec<u8>>, Vec<Error>> {
     let context = Context::create();
+    let module = generate_module(
+        &context, program, file_id, interner, source_map, config, ice_dumps,
+    )?;
+
+    let target_machine = create_target_machine(config).map_err(|error| vec![error])?;
+    module.set_triple(&target_machine.get_triple());
+    module.set_data_layout(&target_machine.get_target_data().get_data_layout());
+    optimize(&module, config.optimization_level, config.passes, ir_dumps);
+
+    config
+        .emit
+        .iter()
+        .map(|kind| match kind {
+            EmitKind::LlvmBitcode => Ok(module.write_bitcode_to_memory().as_slice().to_vec()),
+            EmitKind::Assembly => write_to_memory(&target_machine, &module, FileType::Assembly),
+            EmitKind::Object => write_to_memory(&target_machine, &module, FileType::Object),
+            EmitKind::Tokens | EmitKind::Ast | EmitKind::TypedAst | EmitKind::LlvmIr => {
+                Ok(module.print_to_string().to_string().into_bytes())
+            }
+        })
+        .collect()
+}
+
+/// Compiles the type checked programs into a single module in memory and
+/// runs their `main` function with LLVM's JIT, returning its exit code.
+/// The functions that the programs declare as `extern` are resolved in the
+/// current process, so the C standard library is always available. The
+/// kind of output and the target of the configuration are ignored, since
+/// the code always runs on the host. A function that is defined by more
+/// than one of the programs is reported as an error.
+///
+/// # Arguments
+/// * `programs` - The type checked programs to run, along with the id of the file of each.
+/// * `interner` - The `Interner` of the names in the programs.
+/// * `source_map` - The `SourceMap` containing the files of the programs.
+/// * `config` - The configuration of the code generation.
+/// * `arguments` - The command-line arguments of the program, starting with its name.
+#[cfg(feature = "llvm")]
+pub fn run_jit(
+    programs: &[(FileId, &TypedProgram)],
+    interner: &mut Interner<String>,
+    source_map: &SourceMap,
+    config: &Config,
+    arguments: &[&str],
+) -> Result<i32, Vec<Error>> {
+    let linked_programs = programs
+        .iter()
+        .map(|(_, program)| *program)
+        .collect::<Vec<_>>();
+    check_duplicate_definitions(&linked_programs, interner)?;
+
+    let context = Context::create();
+    let module = context.create_module("jit");
+    for (file_id, program) in programs {
+        let file_module = generate_module(
+            &context, program, *file_id, interner, source_map, config, None,
+        )?;
+        module.link_in_module(file_module).map_err(|message| {
+            vec![Error::JitFailure {
+                message: message.to_string(),
+            }]
+        })?;
+    }
+
+    initialize_targets();
+    optimize(&module, config.optimization_level, config.passes, None);
+    let execution_engine = module
+        .create_jit_execution_engine(config.optimization_level.llvm_level())
+        .map_err(|message| {
+            vec![Error::JitFailure {
+                message: message.to_string(),
+            }]
+        })?;
+    let main = execution_engine
+        .get_function_value("main")
+        .map_err(|_| vec![Error::MissingEntryPoint])?;
+
+    // The generated `main` only reads the arguments it is given and returns
+    // the exit code of the program, which is safe to call.
+    Ok(unsafe { execution_engine.run_function_as_main(main, arguments) })
+}
+
+/// Generates the LLVM module of a type checked program, without optimizing it.
+/// The name of the module is the name of the file without its extension.
+///
+/// # Arguments
+/// * `context` - The LLVM context that owns the module.
+/// * `program` - The type checked program to compile.
+/// * `file_id` - The id of the file that the program is from.
+/// * `interner` - The `Interner` of the names in the program.
+/// * `source_map` - The `SourceMap` containing the file of the program.
+/// * `config` - The configuration of the code generation.
+/// * `ice_dumps` - The list that the IR of the partially built module is added
+///   to when LLVM fails to verify a function.
+#[cfg(feature = "llvm")]
+fn generate_module<'ctx>(
+    context: &'ctx Context,
+    program: &TypedProgram,
+    file_id: FileId,
+    interner: &mut Interner<String>,
+    source_map: &SourceMap,
+    config: &Config,
+    ice_dumps: Option<&mut Vec<String>>,
+) -> Result<Module<'ctx>, Vec<Error>> {
+    let file_name = source_map.name(file_id);
+    let module_name = Path::new(file_name)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(file_name);
     let module = context.create_module(module_name);
     let builder = context.create_builder();
 
     let mut value_env = Environment::default();
-    CodeGenerator::new(&context, &module, &builder, interner, &mut value_env)
-        .generate_program(program)?;
-
-    let pass_manager_builder = PassManagerBuilder::create();
-    pass_manager_builder.set_optimization_level(OptimizationLevel::Default);
-    let pass_manager = PassManager::create(());
-    pass_manager_builder.populate_module_pass_manager(&pass_manager);
-
-    pass_manager.add_promote_memory_to_register_pass();
-    pass_manager.add_instruction_combining_pass();
-    pass_manager.add_reassociate_pass();
-    pass_manager.add_gvn_pass();
-    pass_manager.add_cfg_simplification_pass();
-    pass_manager.run_on(&module);
-
-    if let Some(config) = config {
-        let target_triple = TargetMachine::get_default_triple();
-        let init_config = InitializationConfig {
-            asm_parser: true,
-            asm_printer: true,
-            base: true,
-            disassembler: true,
-            info: true,
-            machine_code: true,
-        };
-
-        Target::initialize_all(&init_config);
-        let target = Target::from_triple(&target_triple).unwrap();
-        module.set_triple(&target_triple);
-        let target_machine = target
-            .create_target_machine(
-                &target_triple,
-                "generic",
-                "",
-                OptimizationLevel::Default,
-                RelocMode::Default,
-                CodeModel::Default,
-            )
-            .unwrap();
-
-        module.set_data_layout(&target_machine.get_target_data().get_data_layout());
-        target_machine.add_analysis_passes(&pass_manager);
-
-        if config.writing_to_file {
-            target_machine
-                .write_to_file(
-                    &module,
-                    FileType::Object,
-                    Path::new(config.output_file_path),
-                )
-                .unwrap();
+    let mut code_generator = CodeGenerator::new(
+        context,
+        &module,
+        &builder,
+        interner,
+        source_map,
+        &mut value_env,
+        config.checked_arithmetic,
+    );
+    if config.debug_info {
+        let is_optimized = config.optimization_level != OptimizationLevel::O0;
+        code_generator.enable_debug_info(file_id, is_optimized);
+    }
+
+    if let Err(errors) = code_generator.generate_program(program) {
+        let is_internal_error = errors
+            .iter()
+            .any(|error| matches!(error, Error::LLVMFunctionFailure { .. }));
+        if let (true, Some(ice_dumps)) = (is_internal_error, ice_dumps) {
+            ice_dumps.push(module.print_to_string().to_string());
+        }
+
+        return Err(errors);
+    }
+
+    Ok(module)
+}
+
+/// Creates the machine of the target requested by the configuration.
+/// The CPU defaults to `generic`, and the CPU `native` refers to the CPU
+/// of the host along with all of its features.
+///
+/// # Arguments
+/// * `config` - The configuration of the output.
+#[cfg(feature = "llvm")]
+fn create_target_machine(config: &Config) -> Result<TargetMachine, Error> {
+    let target_triple = config
+        .target_triple
+        .map(TargetTriple::create)
+        .unwrap_or_else(TargetMachine::get_default_triple);
+    let triple = target_triple.as_str().to_string_lossy().into_owned();
+    initialize_targets();
+    let target = Target::from_triple(&target_triple).map_err(|message| Error::UnknownTarget {
+        triple: triple.clone(),
+        message: message.to_string(),
+    })?;
+
+    let mut features = config.target_features.to_vec();
+    let cpu = match config.target_cpu {
+        Some("native") => {
+            features.insert(0, TargetMachine::get_host_cpu_features().to_string());
+            TargetMachine::get_host_cpu_name().to_string()
         }
+        Some(cpu) => cpu.to_string(),
+        None => String::from("generic"),
+    };
+
+    let features = features.join(",");
+    let reloc_mode = if config.position_independent {
+        RelocMode::PIC
+    } else {
+        RelocMode::Default
+    };
+
+    target
+        .create_target_machine(
+            &target_triple,
+            &cpu,
+            &features,
+            config.optimization_level.llvm_level(),
+            reloc_mode,
+            CodeModel::Default,
+        )
+        .ok_or(Error::UnsupportedTargetMachine {
+            triple,
+            cpu,
+            features,
+        })
+}
+
+/// Writes the assembly or the object file of the module for the target.
+///
+/// # Arguments
+/// * `target_machine` - The machine of the target.
+/// * `module` - The module to write.
+/// * `file_type` - Whether to write assembly or an object file.
+#[cfg(feature = "llvm")]
+fn write_to_memory(
+    target_machine: &TargetMachine,
+    module: &Module,
+    file_type: FileType,
+) -> Result<Vec<u8>, Vec<Error>> {
+    target_machine
+        .write_to_memory_buffer(module, file_type)
+        .map(|buffer| buffer.as_slice().to_vec())
+        .map_err(|message| {
+            vec![Error::EmissionFailure {
+                triple: target_machine
+                    .get_triple()
+                    .as_str()
+                    .to_string_lossy()
+                    .into_owned(),
+                message: message.to_string(),
+            }]
+        })
+}
+
+/// Gets the name and the description of every target that LLVM was built with.
+#[cfg(feature = "llvm")]
+pub fn available_targets() -> Vec<(String, String)> {
+    initialize_targets();
+    let mut targets = vec![];
+    let mut next_target = Target::get_first();
+    while let Some(target) = next_target {
+        targets.push((
+            target.get_name().to_string_lossy().into_owned(),
+            target.get_description().to_string_lossy().into_owned(),
+        ));
+        next_target = target.get_next();
     }
 
-    Ok(module.print_to_string().to_string())
+    targets
 }
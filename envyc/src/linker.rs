@@ -0,0 +1,165 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    error::Error,
+    interner::Interner,
+    parser::{
+        typed_ast::TypedProgram,
+        typed_expression::{TypedExpression, TypedExpressionKind},
+    },
+};
+
+/// Checks that no function is defined more than once in the programs, which
+/// are linked together into a single program. Otherwise, every call to the
+/// function would silently use only one of its definitions.
+///
+/// # Arguments
+/// * `programs` - The type checked programs that are linked together.
+/// * `interner` - The `Interner` of the names in the programs.
+pub fn check_duplicate_definitions(
+    programs: &[&TypedProgram],
+    interner: &Interner<String>,
+) -> Result<(), Vec<Error>> {
+    let mut definitions = HashMap::new();
+    let mut errors = vec![];
+    for function in programs.iter().flat_map(|program| &program.functions) {
+        let prototype = &function.prototype;
+        match definitions.get(&prototype.name) {
+            Some(&previous_span) => errors.push(Error::DuplicateDefinition {
+                span: prototype.span,
+                name: interner.get(prototype.name).clone(),
+                previous_span,
+            }),
+            None => {
+                definitions.insert(prototype.name, prototype.span);
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Links the programs of a library, such as the standard library, with the
+/// programs that use it. Every function of the library is renamed to
+/// `library.name`, which can not be written in Envy, along with every call
+/// to it in the library, so the library always calls its own functions.
+/// An `extern` declaration in the programs refers to the function of the
+/// library only when none of the programs define a function with that name,
+/// just like a linker only takes a function from a static library when
+/// nothing else defines it. Linking the same programs again has no effect.
+///
+/// # Arguments
+/// * `programs` - The type checked programs that use the library.
+/// * `library` - The type checked programs of the library.
+/// * `library_name` - The name of the library.
+/// * `interner` - The `Interner` of the names in the programs.
+pub fn link_library(
+    programs: &mut [&mut TypedProgram],
+    library: &mut [&mut TypedProgram],
+    library_name: &str,
+    interner: &mut Interner<String>,
+) {
+    let prefix = format!("{}.", library_name);
+    let mut names = HashMap::new();
+    for function in library.iter().flat_map(|program| &program.functions) {
+        let name = interner.get(function.prototype.name).clone();
+        let name = name.strip_prefix(&prefix).unwrap_or(&name).to_string();
+        let linked_name = interner.insert(format!("{}{}", prefix, name));
+        names.insert(interner.insert(name), linked_name);
+    }
+
+    for program in library.iter_mut() {
+        rename_functions(program, &names);
+    }
+
+    let defined_functions = programs
+        .iter()
+        .flat_map(|program| &program.functions)
+        .map(|function| function.prototype.name)
+        .collect::<HashSet<_>>();
+    names.retain(|name, _| !defined_functions.contains(name));
+    for program in programs.iter_mut() {
+        rename_functions(program, &names);
+    }
+}
+
+/// Renames the functions that the program defines, declares or calls.
+///
+/// # Arguments
+/// * `program` - The type checked program.
+/// * `names` - The new name of each function that is renamed.
+fn rename_functions(program: &mut TypedProgram, names: &HashMap<usize, usize>) {
+    for extern_declaration in &mut program.extern_declarations {
+        if let Some(&name) = names.get(&extern_declaration.name) {
+            extern_declaration.name = name;
+        }
+    }
+
+    for function in &mut program.functions {
+        if let Some(&name) = names.get(&function.prototype.name) {
+            function.prototype.name = name;
+        }
+
+        rename_calls(&mut function.body, names);
+    }
+}
+
+/// Renames the functions called by the expression.
+///
+/// # Arguments
+/// * `expression` - The expression to walk through.
+/// * `names` - The new name of each function that is renamed.
+fn rename_calls(expression: &mut TypedExpression, names: &HashMap<usize, usize>) {
+    match &mut expression.1 {
+        TypedExpressionKind::Int(_)
+        | TypedExpressionKind::Float(_)
+        | TypedExpressionKind::Boolean(_)
+        | TypedExpressionKind::Char(_)
+        | TypedExpressionKind::Identifier(_)
+        | TypedExpressionKind::Panic(_)
+        | TypedExpressionKind::Return(None)
+        | TypedExpressionKind::Error => {}
+        TypedExpressionKind::Unary(unary) => rename_calls(&mut unary.expression, names),
+        TypedExpressionKind::Binary(binary) => {
+            rename_calls(&mut binary.left, names);
+            rename_calls(&mut binary.right, names);
+        }
+        TypedExpressionKind::If(typed_if) => {
+            rename_calls(&mut typed_if.condition, names);
+            rename_calls(&mut typed_if.then_branch, names);
+            if let Some(else_branch) = &mut typed_if.else_branch {
+                rename_calls(else_branch, names);
+            }
+        }
+        TypedExpressionKind::Let(typed_let) => rename_calls(&mut typed_let.expression, names),
+        TypedExpressionKind::Block(expressions) => {
+            for expression in expressions {
+                rename_calls(expression, names);
+            }
+        }
+        TypedExpressionKind::Application(application) => {
+            if let Some(&name) = names.get(&application.function_name.1) {
+                application.function_name.1 = name;
+            }
+
+            for parameter in &mut application.parameters {
+                rename_calls(parameter, names);
+            }
+        }
+        TypedExpressionKind::While(typed_while) => {
+            rename_calls(&mut typed_while.condition, names);
+            rename_calls(&mut typed_while.expression, names);
+        }
+        TypedExpressionKind::Return(Some(value)) | TypedExpressionKind::Assert(value) => {
+            rename_calls(value, names)
+        }
+        TypedExpressionKind::AssertEq(assert_eq) => {
+            rename_calls(&mut assert_eq.left, names);
+            rename_calls(&mut assert_eq.right, names);
+        }
+    }
+}
@@ -0,0 +1,419 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    error::Span,
+    interner::Interner,
+    parser::{
+        ast::Attribute,
+        expression::BinaryOperation,
+        typed_ast::{TypedFunction, TypedProgram},
+        typed_expression::{TypedBinary, TypedExpression, TypedExpressionKind},
+    },
+    semantic_analyzer::types::Type,
+};
+
+use super::{Lint, LintConfig, LintDiagnostic, LintLevel, Severity};
+
+/// Struct that keeps track of whether a variable was read.
+struct Variable {
+    span: Span,
+    used: bool,
+}
+
+/// Struct that walks through a type checked program and reports the lints.
+pub struct Linter<'a> {
+    interner: &'a Interner<String>,
+    /// Whether the program is built into a library, so that every
+    /// function may be called from outside of it.
+    library: bool,
+    /// The configuration given to the compiler.
+    global_config: &'a LintConfig,
+    /// The configuration of the current function, which includes its attributes.
+    config: LintConfig,
+    /// The variables defined in each scope of the current function.
+    scopes: Vec<HashMap<usize, Variable>>,
+    diagnostics: Vec<LintDiagnostic>,
+}
+
+impl<'a> Linter<'a> {
+    pub fn new(interner: &'a Interner<String>, config: &'a LintConfig, library: bool) -> Self {
+        Self {
+            interner,
+            library,
+            global_config: config,
+            config: config.clone(),
+            scopes: vec![],
+            diagnostics: vec![],
+        }
+    }
+
+    /// Walks through the program and returns the diagnostics of every lint
+    /// that is not allowed.
+    ///
+    /// # Arguments
+    /// * `program` - The type checked program.
+    pub fn lint_program(mut self, program: &TypedProgram) -> Vec<LintDiagnostic> {
+        let used_functions = self.used_functions(program);
+        for function in &program.functions {
+            self.config = self.global_config.clone();
+            self.apply_attributes(&function.attributes);
+            if let Some(ref used_functions) = used_functions {
+                if !used_functions.contains(&function.prototype.name) {
+                    let name = self.interner.get(function.prototype.name);
+                    let message = format!("function `{}` is never used", name);
+                    self.report(Lint::UnusedFunctions, function.prototype.span, message);
+                }
+            }
+
+            self.lint_function(function);
+        }
+
+        self.diagnostics
+    }
+
+    /// Gets the functions that can be reached from `main` through calls,
+    /// including `main` itself. Returns `None` if every function may be used,
+    /// since functions may be called from other files unless this file contains
+    /// the entry point of the program, and from outside of a library.
+    ///
+    /// # Arguments
+    /// * `program` - The type checked program.
+    fn used_functions(&self, program: &TypedProgram) -> Option<HashSet<usize>> {
+        if self.library {
+            return None;
+        }
+
+        let main = program
+            .functions
+            .iter()
+            .map(|function| function.prototype.name)
+            .find(|&name| self.interner.get(name) == "main")?;
+
+        let mut calls = HashMap::new();
+        for function in &program.functions {
+            let mut called_functions = HashSet::new();
+            collect_calls(&function.body, &mut called_functions);
+            calls.insert(function.prototype.name, called_functions);
+        }
+
+        let mut used_functions = HashSet::new();
+        let mut pending = vec![main];
+        while let Some(name) = pending.pop() {
+            if used_functions.insert(name) {
+                if let Some(called_functions) = calls.get(&name) {
+                    pending.extend(called_functions.iter().copied());
+                }
+            }
+        }
+
+        Some(used_functions)
+    }
+
+    /// Applies the `allow`, `warn` and `deny` attributes to the configuration
+    /// of the current function. The name `warnings` refers to every lint.
+    ///
+    /// # Arguments
+    /// * `attributes` - The attributes of the function.
+    fn apply_attributes(&mut self, attributes: &[Attribute]) {
+        for attribute in attributes {
+            let attribute_name = self.interner.get(attribute.name.1);
+            let level = match LintLevel::from_attribute(attribute_name) {
+                Some(level) => level,
+                None => {
+                    let message = format!("unknown attribute `{}`", attribute_name);
+                    self.report(Lint::UnknownLints, attribute.name.0, message);
+                    continue;
+                }
+            };
+
+            for &(span, argument) in &attribute.arguments {
+                let lint_name = self.interner.get(argument);
+                match Lint::from_name(lint_name) {
+                    Some(lint) => self.config.set(lint, level),
+                    // Like on the command line, `warnings` refers to every lint.
+                    None if lint_name == "warnings" => self.config.set_all(level),
+                    None => {
+                        let message = format!("unknown lint `{}`", lint_name);
+                        self.report(Lint::UnknownLints, span, message);
+                    }
+                }
+            }
+        }
+    }
+
+    fn lint_function(&mut self, function: &TypedFunction) {
+        self.scopes.push(HashMap::new());
+        for parameter in &function.prototype.parameters {
+            self.define(parameter.name, parameter.span);
+        }
+
+        let is_value_used = function.prototype.return_type != Type::Void;
+        self.lint_expression(&function.body, is_value_used);
+        self.remove_scope();
+    }
+
+    /// Walks through the expression and reports the lints found.
+    ///
+    /// # Arguments
+    /// * `expression` - The expression to walk through.
+    /// * `is_value_used` - Whether the value of the expression is used.
+    fn lint_expression(&mut self, expression: &TypedExpression, is_value_used: bool) {
+        match &expression.1 {
+            TypedExpressionKind::Int(_)
+            | TypedExpressionKind::Float(_)
+            | TypedExpressionKind::Boolean(_)
+            | TypedExpressionKind::Char(_)
+            | TypedExpressionKind::Panic(_)
+            | TypedExpressionKind::Return(None)
+            | TypedExpressionKind::Error => {}
+            TypedExpressionKind::Identifier(identifier) => self.mark_used(identifier.id),
+            TypedExpressionKind::Unary(unary) => self.lint_expression(&unary.expression, true),
+            TypedExpressionKind::Binary(binary) => {
+                if let Some(result) = evaluate_comparison(binary) {
+                    let message = format!("this comparison is always {}", result);
+                    self.report(Lint::ConstantComparisons, expression.0, message);
+                }
+
+                self.lint_expression(&binary.left, true);
+                self.lint_expression(&binary.right, true);
+            }
+            TypedExpressionKind::If(typed_if) => {
+                if typed_if.else_branch.is_none() && is_value_used {
+                    self.report(
+                        Lint::IfWithoutElse,
+                        expression.0,
+                        "`if` without an `else` is used as a value".to_string(),
+                    );
+                }
+
+                self.lint_expression(&typed_if.condition, true);
+                self.lint_expression(&typed_if.then_branch, is_value_used);
+                if let Some(else_branch) = &typed_if.else_branch {
+                    self.lint_expression(else_branch, is_value_used);
+                }
+            }
+            TypedExpressionKind::Let(typed_let) => {
+                self.lint_expression(&typed_let.expression, true);
+                let (name_span, name) = (typed_let.name.0, typed_let.name.1.id);
+                let is_defined = self.scopes.iter().any(|scope| scope.contains_key(&name));
+                // A `let` without a type annotation assigns to a variable
+                // that is already defined instead of declaring a new one.
+                if typed_let.given_type.is_some() && is_defined {
+                    let message = format!(
+                        "`{}` shadows a variable that is already defined",
+                        self.interner.get(name)
+                    );
+                    self.report(Lint::Shadowing, name_span, message);
+                    self.define(name, name_span);
+                } else if !is_defined {
+                    self.define(name, name_span);
+                }
+            }
+            TypedExpressionKind::Block(expressions) => {
+                self.scopes.push(HashMap::new());
+                let mut reported_unreachable = false;
+                for (index, block_expression) in expressions.iter().enumerate() {
+                    if index > 0
+                        && !reported_unreachable
+                        && expressions[index - 1].1.get_type() == Type::Never
+                    {
+                        self.report(
+                            Lint::UnreachableCode,
+                            block_expression.0,
+                            "unreachable expression".to_string(),
+                        );
+                        reported_unreachable = true;
+                    }
+
+                    let is_last = index + 1 == expressions.len();
+                    self.lint_expression(block_expression, is_value_used && is_last);
+                }
+
+                self.remove_scope();
+            }
+            TypedExpressionKind::Application(application) => {
+                for parameter in &application.parameters {
+                    self.lint_expression(parameter, true);
+                }
+            }
+            TypedExpressionKind::While(typed_while) => {
+                self.lint_expression(&typed_while.condition, true);
+                self.lint_expression(&typed_while.expression, false);
+            }
+            TypedExpressionKind::Return(Some(value)) => self.lint_expression(value, true),
+            TypedExpressionKind::Assert(condition) => self.lint_expression(condition, true),
+            TypedExpressionKind::AssertEq(assert_eq) => {
+                self.lint_expression(&assert_eq.left, true);
+                self.lint_expression(&assert_eq.right, true);
+            }
+        }
+    }
+
+    /// Defines a variable in the current scope.
+    ///
+    /// # Arguments
+    /// * `name` - The id of the name of the variable.
+    /// * `span` - The `Span` of the name of the variable.
+    fn define(&mut self, name: usize, span: Span) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name, Variable { span, used: false });
+        }
+    }
+
+    /// Marks the innermost variable with the given name as used.
+    ///
+    /// # Arguments
+    /// * `name` - The id of the name of the variable.
+    fn mark_used(&mut self, name: usize) {
+        if let Some(variable) = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(&name))
+        {
+            variable.used = true;
+        }
+    }
+
+    /// Removes the current scope and reports the variables in it that were never used.
+    /// Variables whose names start with an underscore are not reported.
+    fn remove_scope(&mut self) {
+        if let Some(scope) = self.scopes.pop() {
+            let mut unused_variables = scope
+                .into_iter()
+                .filter(|(name, variable)| {
+                    !variable.used && !self.interner.get(*name).starts_with('_')
+                })
+                .collect::<Vec<_>>();
+            unused_variables.sort_by_key(|(_, variable)| variable.span.start);
+
+            for (name, variable) in unused_variables {
+                let message = format!("unused variable `{}`", self.interner.get(name));
+                self.report(Lint::UnusedVariables, variable.span, message);
+            }
+        }
+    }
+
+    /// Reports the lint according to its level in the current configuration.
+    ///
+    /// # Arguments
+    /// * `lint` - The lint being reported.
+    /// * `span` - The `Span` of the code that triggered the lint.
+    /// * `message` - The message describing the problem.
+    fn report(&mut self, lint: Lint, span: Span, message: String) {
+        let severity = match self.config.level(lint) {
+            LintLevel::Allow => return,
+            LintLevel::Warn => Severity::Warning,
+            LintLevel::Deny => Severity::Error,
+        };
+
+        self.diagnostics.push(LintDiagnostic {
+            lint,
+            severity,
+            span,
+            message,
+        });
+    }
+}
+
+/// Collects the names of every function called in the expression.
+///
+/// # Arguments
+/// * `expression` - The expression to walk through.
+/// * `called_functions` - The ids of the names of the functions called so far.
+pub(crate) fn collect_calls(expression: &TypedExpression, called_functions: &mut HashSet<usize>) {
+    match &expression.1 {
+        TypedExpressionKind::Application(application) => {
+            called_functions.insert(application.function_name.1);
+            for parameter in &application.parameters {
+                collect_calls(parameter, called_functions);
+            }
+        }
+        TypedExpressionKind::Unary(unary) => collect_calls(&unary.expression, called_functions),
+        TypedExpressionKind::Binary(binary) => {
+            collect_calls(&binary.left, called_functions);
+            collect_calls(&binary.right, called_functions);
+        }
+        TypedExpressionKind::If(typed_if) => {
+            collect_calls(&typed_if.condition, called_functions);
+            collect_calls(&typed_if.then_branch, called_functions);
+            if let Some(else_branch) = &typed_if.else_branch {
+                collect_calls(else_branch, called_functions);
+            }
+        }
+        TypedExpressionKind::Let(typed_let) => {
+            collect_calls(&typed_let.expression, called_functions)
+        }
+        TypedExpressionKind::Block(expressions) => {
+            for expression in expressions {
+                collect_calls(expression, called_functions);
+            }
+        }
+        TypedExpressionKind::While(typed_while) => {
+            collect_calls(&typed_while.condition, called_functions);
+            collect_calls(&typed_while.expression, called_functions);
+        }
+        TypedExpressionKind::Return(Some(value)) => collect_calls(value, called_functions),
+        TypedExpressionKind::Assert(condition) => collect_calls(condition, called_functions),
+        TypedExpressionKind::AssertEq(assert_eq) => {
+            collect_calls(&assert_eq.left, called_functions);
+            collect_calls(&assert_eq.right, called_functions);
+        }
+        _ => {}
+    }
+}
+
+/// Evaluates a comparison whose result is known at compile time. This is the
+/// case when both sides are literals, or when both sides are the same variable.
+/// Variables that are `Float`s are excluded, since `NaN` is not equal to itself.
+/// Returns `None` if the result is not known.
+///
+/// # Arguments
+/// * `binary` - The binary operation to evaluate.
+fn evaluate_comparison(binary: &TypedBinary) -> Option<bool> {
+    let operation = binary.operation;
+    match (&binary.left.1, &binary.right.1) {
+        (TypedExpressionKind::Identifier(left), TypedExpressionKind::Identifier(right))
+            if left.id == right.id && left.ty != Type::Float =>
+        {
+            match operation {
+                BinaryOperation::Equals
+                | BinaryOperation::LessThanEquals
+                | BinaryOperation::GreaterThanEquals => Some(true),
+                BinaryOperation::LessThan | BinaryOperation::GreaterThan => Some(false),
+                _ => None,
+            }
+        }
+        (TypedExpressionKind::Int(left), TypedExpressionKind::Int(right)) => {
+            compare(operation, left, right)
+        }
+        (TypedExpressionKind::Float(left), TypedExpressionKind::Float(right)) => {
+            compare(operation, left, right)
+        }
+        (TypedExpressionKind::Char(left), TypedExpressionKind::Char(right)) => {
+            compare(operation, left, right)
+        }
+        (TypedExpressionKind::Boolean(left), TypedExpressionKind::Boolean(right)) => {
+            compare(operation, left, right)
+        }
+        _ => None,
+    }
+}
+
+/// Compares two literals with the given operation.
+/// Returns `None` if the operation is not a comparison.
+///
+/// # Arguments
+/// * `operation` - The comparison to perform.
+/// * `left` - The left side of the comparison.
+/// * `right` - The right side of the comparison.
+fn compare<T: PartialOrd>(operation: BinaryOperation, left: T, right: T) -> Option<bool> {
+    match operation {
+        BinaryOperation::Equals => Some(left == right),
+        BinaryOperation::LessThan => Some(left < right),
+        BinaryOperation::GreaterThan => Some(left > right),
+        BinaryOperation::LessThanEquals => Some(left <= right),
+        BinaryOperation::GreaterThanEquals => Some(left >= right),
+        _ => None,
+    }
+}
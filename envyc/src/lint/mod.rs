@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+use crate::error::Span;
+
+pub mod linter;
+
+/// Enum that details the different lints that the compiler checks for.
+/// Unlike errors, lints do not stop the compilation unless they are denied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    /// Variables and parameters that are never read.
+    UnusedVariables,
+    /// Functions that are never called in a file that defines `main`.
+    UnusedFunctions,
+    /// Expressions that follow an expression that never completes, such as a `return`.
+    UnreachableCode,
+    /// A `let` with a type annotation that redeclares a variable that is already defined.
+    Shadowing,
+    /// An `if` without an `else` whose value is used.
+    IfWithoutElse,
+    /// Comparisons whose result is known at compile time.
+    ConstantComparisons,
+    /// Attributes or lint names that are not recognized.
+    UnknownLints,
+}
+
+impl Lint {
+    /// All of the lints known to the compiler.
+    pub const ALL: [Lint; 7] = [
+        Lint::UnusedVariables,
+        Lint::UnusedFunctions,
+        Lint::UnreachableCode,
+        Lint::Shadowing,
+        Lint::IfWithoutElse,
+        Lint::ConstantComparisons,
+        Lint::UnknownLints,
+    ];
+
+    /// Gets the name used to refer to this lint in attributes and on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::UnusedVariables => "unused_variables",
+            Lint::UnusedFunctions => "unused_functions",
+            Lint::UnreachableCode => "unreachable_code",
+            Lint::Shadowing => "shadowing",
+            Lint::IfWithoutElse => "if_without_else",
+            Lint::ConstantComparisons => "constant_comparisons",
+            Lint::UnknownLints => "unknown_lints",
+        }
+    }
+
+    /// Finds the lint with the given name.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the lint.
+    pub fn from_name(name: &str) -> Option<Lint> {
+        Lint::ALL.iter().copied().find(|lint| lint.name() == name)
+    }
+}
+
+/// Enum that details how a lint is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    /// The lint is not reported.
+    Allow,
+    /// The lint is reported as a warning.
+    Warn,
+    /// The lint is reported as an error and the compilation fails.
+    Deny,
+}
+
+impl LintLevel {
+    /// Finds the level that corresponds to the name of an attribute,
+    /// such as `allow` in `#[allow(unused_variables)]`.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the attribute.
+    pub fn from_attribute(name: &str) -> Option<LintLevel> {
+        match name {
+            "allow" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// Enum that details the severity of a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Struct that stores the level of each lint.
+/// Every lint is reported as a warning unless configured otherwise.
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    levels: HashMap<Lint, LintLevel>,
+}
+
+impl LintConfig {
+    /// Sets the level of the given lint.
+    ///
+    /// # Arguments
+    /// * `lint` - The lint to configure.
+    /// * `level` - The new level of the lint.
+    pub fn set(&mut self, lint: Lint, level: LintLevel) {
+        self.levels.insert(lint, level);
+    }
+
+    /// Sets the level of every lint.
+    ///
+    /// # Arguments
+    /// * `level` - The new level of the lints.
+    pub fn set_all(&mut self, level: LintLevel) {
+        for lint in Lint::ALL.iter() {
+            self.set(*lint, level);
+        }
+    }
+
+    /// Gets the level of the given lint.
+    ///
+    /// # Arguments
+    /// * `lint` - The lint to get the level of.
+    pub fn level(&self, lint: Lint) -> LintLevel {
+        self.levels.get(&lint).copied().unwrap_or(LintLevel::Warn)
+    }
+}
+
+/// Struct that represents a diagnostic produced by a lint.
+#[derive(Debug)]
+pub struct LintDiagnostic {
+    pub lint: Lint,
+    pub severity: Severity,
+    pub span: Span,
+    pub message: String,
+}
@@ -0,0 +1,283 @@
+#[cfg(feature = "llvm")]
+use inkwell::{
+    module::Module,
+    passes::{PassManager, PassManagerBuilder},
+};
+
+/// Enum that details how much the generated code is optimized.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OptimizationLevel {
+    /// No optimizations are run at all.
+    O0,
+    O1,
+    O2,
+    O3,
+    /// Optimizes like `O2`, but prefers smaller code.
+    Os,
+    /// Optimizes like `O2`, but prefers the smallest code possible.
+    Oz,
+}
+
+impl OptimizationLevel {
+    /// All of the optimization levels.
+    pub const ALL: [OptimizationLevel; 6] = [
+        OptimizationLevel::O0,
+        OptimizationLevel::O1,
+        OptimizationLevel::O2,
+        OptimizationLevel::O3,
+        OptimizationLevel::Os,
+        OptimizationLevel::Oz,
+    ];
+
+    /// Gets the name used to refer to this level on the command line, such as `2` in `-O2`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            OptimizationLevel::O0 => "0",
+            OptimizationLevel::O1 => "1",
+            OptimizationLevel::O2 => "2",
+            OptimizationLevel::O3 => "3",
+            OptimizationLevel::Os => "s",
+            OptimizationLevel::Oz => "z",
+        }
+    }
+
+    /// Finds the optimization level with the given name.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the level.
+    pub fn from_name(name: &str) -> Option<OptimizationLevel> {
+        OptimizationLevel::ALL
+            .iter()
+            .copied()
+            .find(|level| level.name() == name)
+    }
+
+    /// Gets the level used by LLVM for the passes and for the code generation of the target.
+    #[cfg(feature = "llvm")]
+    pub fn llvm_level(&self) -> inkwell::OptimizationLevel {
+        match self {
+            OptimizationLevel::O0 => inkwell::OptimizationLevel::None,
+            OptimizationLevel::O1 => inkwell::OptimizationLevel::Less,
+            OptimizationLevel::O2 | OptimizationLevel::Os | OptimizationLevel::Oz => {
+                inkwell::OptimizationLevel::Default
+            }
+            OptimizationLevel::O3 => inkwell::OptimizationLevel::Aggressive,
+        }
+    }
+
+    /// Gets how strongly LLVM prefers smaller code, from 0 to 2.
+    #[cfg(feature = "llvm")]
+    fn size_level(&self) -> u32 {
+        match self {
+            OptimizationLevel::Os => 1,
+            OptimizationLevel::Oz => 2,
+            _ => 0,
+        }
+    }
+}
+
+/// Enum that details the passes that can be given to `--passes`.
+/// Each pass is named after the pass of the same name in LLVM's `opt`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pass {
+    PromoteMemoryToRegister,
+    InstructionCombining,
+    Reassociate,
+    Gvn,
+    CfgSimplification,
+    DeadStoreElimination,
+    AggressiveDce,
+    Sccp,
+    EarlyCse,
+    ScalarReplAggregates,
+    Licm,
+    LoopRotate,
+    LoopUnroll,
+    LoopDeletion,
+    IndVarSimplify,
+    JumpThreading,
+    TailCallElimination,
+    FunctionInlining,
+    GlobalDce,
+    GlobalOptimizer,
+    ConstantMerge,
+    DeadArgElimination,
+}
+
+impl Pass {
+    /// All of the passes that can be given to `--passes`.
+    pub const ALL: [Pass; 22] = [
+        Pass::PromoteMemoryToRegister,
+        Pass::InstructionCombining,
+        Pass::Reassociate,
+        Pass::Gvn,
+        Pass::CfgSimplification,
+        Pass::DeadStoreElimination,
+        Pass::AggressiveDce,
+        Pass::Sccp,
+        Pass::EarlyCse,
+        Pass::ScalarReplAggregates,
+        Pass::Licm,
+        Pass::LoopRotate,
+        Pass::LoopUnroll,
+        Pass::LoopDeletion,
+        Pass::IndVarSimplify,
+        Pass::JumpThreading,
+        Pass::TailCallElimination,
+        Pass::FunctionInlining,
+        Pass::GlobalDce,
+        Pass::GlobalOptimizer,
+        Pass::ConstantMerge,
+        Pass::DeadArgElimination,
+    ];
+
+    /// The passes that are run after the standard pipeline of every level other than `O0`.
+    pub const DEFAULT: [Pass; 5] = [
+        Pass::PromoteMemoryToRegister,
+        Pass::InstructionCombining,
+        Pass::Reassociate,
+        Pass::Gvn,
+        Pass::CfgSimplification,
+    ];
+
+    /// Gets the name used to refer to this pass on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Pass::PromoteMemoryToRegister => "mem2reg",
+            Pass::InstructionCombining => "instcombine",
+            Pass::Reassociate => "reassociate",
+            Pass::Gvn => "gvn",
+            Pass::CfgSimplification => "simplifycfg",
+            Pass::DeadStoreElimination => "dse",
+            Pass::AggressiveDce => "adce",
+            Pass::Sccp => "sccp",
+            Pass::EarlyCse => "early-cse",
+            Pass::ScalarReplAggregates => "sroa",
+            Pass::Licm => "licm",
+            Pass::LoopRotate => "loop-rotate",
+            Pass::LoopUnroll => "loop-unroll",
+            Pass::LoopDeletion => "loop-deletion",
+            Pass::IndVarSimplify => "indvars",
+            Pass::JumpThreading => "jump-threading",
+            Pass::TailCallElimination => "tailcallelim",
+            Pass::FunctionInlining => "inline",
+            Pass::GlobalDce => "globaldce",
+            Pass::GlobalOptimizer => "globalopt",
+            Pass::ConstantMerge => "constmerge",
+            Pass::DeadArgElimination => "deadargelim",
+        }
+    }
+
+    /// Finds the pass with the given name.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the pass.
+    pub fn from_name(name: &str) -> Option<Pass> {
+        Pass::ALL.iter().copied().find(|pass| pass.name() == name)
+    }
+
+    /// Adds this pass to the pass manager.
+    ///
+    /// # Arguments
+    /// * `pass_manager` - The pass manager to add the pass to.
+    #[cfg(feature = "llvm")]
+    fn add_to(&self, pass_manager: &PassManager<Module>) {
+        match self {
+            Pass::PromoteMemoryToRegister => pass_manager.add_promote_memory_to_register_pass(),
+            Pass::InstructionCombining => pass_manager.add_instruction_combining_pass(),
+            Pass::Reassociate => pass_manager.add_reassociate_pass(),
+            Pass::Gvn => pass_manager.add_gvn_pass(),
+            Pass::CfgSimplification => pass_manager.add_cfg_simplification_pass(),
+            Pass::DeadStoreElimination => pass_manager.add_dead_store_elimination_pass(),
+            Pass::AggressiveDce => pass_manager.add_aggressive_dce_pass(),
+            Pass::Sccp => pass_manager.add_sccp_pass(),
+            Pass::EarlyCse => pass_manager.add_early_cse_pass(),
+            Pass::ScalarReplAggregates => pass_manager.add_scalar_repl_aggregates_pass(),
+            Pass::Licm => pass_manager.add_licm_pass(),
+            Pass::LoopRotate => pass_manager.add_loop_rotate_pass(),
+            Pass::LoopUnroll => pass_manager.add_loop_unroll_pass(),
+            Pass::LoopDeletion => pass_manager.add_loop_deletion_pass(),
+            Pass::IndVarSimplify => pass_manager.add_ind_var_simplify_pass(),
+            Pass::JumpThreading => pass_manager.add_jump_threading_pass(),
+            Pass::TailCallElimination => pass_manager.add_tail_call_elimination_pass(),
+            Pass::FunctionInlining => pass_manager.add_function_inlining_pass(),
+            Pass::GlobalDce => pass_manager.add_global_dce_pass(),
+            Pass::GlobalOptimizer => pass_manager.add_global_optimizer_pass(),
+            Pass::ConstantMerge => pass_manager.add_constant_merge_pass(),
+            Pass::DeadArgElimination => pass_manager.add_dead_arg_elimination_pass(),
+        }
+    }
+}
+
+/// Optimizes the module. When custom passes are given, exactly those passes
+/// are run in the given order, regardless of the level. Otherwise, `O0` runs
+/// no passes at all and every other level runs LLVM's standard pipeline for
+/// the level, followed by `Pass::DEFAULT`.
+///
+/// When `ir_dumps` is given, the passes are run one at a time and the IR
+/// of the module is added to it after each of them.
+///
+/// # Arguments
+/// * `module` - The module to optimize.
+/// * `level` - The optimization level.
+/// * `passes` - The custom passes to run instead of the pipeline of the level.
+/// * `ir_dumps` - The list that the IR is added to after each pass.
+#[cfg(feature = "llvm")]
+pub fn optimize(
+    module: &Module,
+    level: OptimizationLevel,
+    passes: Option<&[Pass]>,
+    mut ir_dumps: Option<&mut Vec<String>>,
+) {
+    let passes = match passes {
+        Some(passes) => passes,
+        None if level == OptimizationLevel::O0 => return,
+        None => {
+            let pass_manager_builder = PassManagerBuilder::create();
+            pass_manager_builder.set_optimization_level(level.llvm_level());
+            pass_manager_builder.set_size_level(level.size_level());
+            let pass_manager = PassManager::create(());
+            pass_manager_builder.populate_module_pass_manager(&pass_manager);
+            pass_manager.run_on(module);
+            if let Some(ir_dumps) = ir_dumps.as_mut() {
+                let pipeline = format!("the standard -O{} pipeline", level.name());
+                ir_dumps.push(dump_ir(module, &pipeline));
+            }
+
+            &Pass::DEFAULT[..]
+        }
+    };
+
+    match ir_dumps {
+        Some(ir_dumps) => {
+            for pass in passes {
+                let pass_manager = PassManager::create(());
+                pass.add_to(&pass_manager);
+                pass_manager.run_on(module);
+                ir_dumps.push(dump_ir(module, pass.name()));
+            }
+        }
+        None => {
+            let pass_manager = PassManager::create(());
+            for pass in passes {
+                pass.add_to(&pass_manager);
+            }
+
+            pass_manager.run_on(module);
+        }
+    }
+}
+
+/// Formats the IR of the module after a pass, in the style of LLVM's `-print-after-all`.
+///
+/// # Arguments
+/// * `module` - The module to print.
+/// * `pass_name` - The name of the pass that was just run.
+#[cfg(feature = "llvm")]
+fn dump_ir(module: &Module, pass_name: &str) -> String {
+    format!(
+        "; *** IR Dump After {} ***\n{}",
+        pass_name,
+        module.print_to_string().to_string()
+    )
+}
@@ -3,16 +3,13 @@ use crate::{error::Span, semantic_analyzer::types::Type};
 use super::expression::Expression;
 
 #[derive(Debug)]
-pub struct Program<'a> {
-    pub extern_declarations: Vec<ExternDeclaration<'a>>,
-    pub functions: Vec<Function<'a>>,
+pub struct Program {
+    pub extern_declarations: Vec<ExternDeclaration>,
+    pub functions: Vec<Function>,
 }
 
-impl<'a> Program<'a> {
-    pub fn new(
-        extern_declarations: Vec<ExternDeclaration<'a>>,
-        functions: Vec<Function<'a>>,
-    ) -> Self {
+impl Program {
+    pub fn new(extern_declarations: Vec<ExternDeclaration>, functions: Vec<Function>) -> Self {
         Self {
             extern_declarations,
             functions,
@@ -21,42 +18,56 @@ impl<'a> Program<'a> {
 }
 
 #[derive(Debug)]
-pub struct Prototype<'a> {
-    pub span: Span<'a>,
+pub struct Prototype {
+    pub span: Span,
     pub name: usize,
-    pub parameters: Vec<Parameter<'a>>,
-    pub return_type: (Type, Span<'a>),
+    pub parameters: Vec<Parameter>,
+    pub return_type: (Type, Span),
 }
 
 #[derive(Debug)]
-pub struct ExternDeclaration<'a> {
-    pub span: Span<'a>,
+pub struct ExternDeclaration {
+    pub span: Span,
     pub name: usize,
-    pub parameters: Vec<(Type, Span<'a>)>,
-    pub return_type: (Type, Span<'a>),
+    pub parameters: Vec<(Type, Span)>,
+    pub return_type: (Type, Span),
 }
 
 #[derive(Debug)]
-pub struct Function<'a> {
-    pub prototype: Prototype<'a>,
-    pub body: Expression<'a>,
+pub struct Function {
+    pub attributes: Vec<Attribute>,
+    pub prototype: Prototype,
+    pub body: Expression,
 }
 
-impl<'a> Function<'a> {
-    pub fn new(prototype: Prototype<'a>, body: Expression<'a>) -> Self {
-        Self { prototype, body }
+impl Function {
+    pub fn new(attributes: Vec<Attribute>, prototype: Prototype, body: Expression) -> Self {
+        Self {
+            attributes,
+            prototype,
+            body,
+        }
     }
 }
 
+/// Represents an attribute placed before a function, such as `#[allow(unused_variables)]`.
+/// The names are stored in the `Interner` and are interpreted by later stages.
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    pub span: Span,
+    pub name: (Span, usize),
+    pub arguments: Vec<(Span, usize)>,
+}
+
 #[derive(Debug)]
-pub struct Parameter<'a> {
-    pub span: Span<'a>,
+pub struct Parameter {
+    pub span: Span,
     pub name: usize,
     pub ty: Type,
 }
 
-impl<'a> Parameter<'a> {
-    pub fn new(span: Span<'a>, name: usize, ty: Type) -> Self {
+impl Parameter {
+    pub fn new(span: Span, name: usize, ty: Type) -> Self {
         Self { span, name, ty }
     }
 }
@@ -1,16 +1,18 @@
+use std::fmt::Display;
+
 use crate::{error::Span, semantic_analyzer::types::Type};
 
 /// Represents an expression that is generated by the `Parser`.
 /// Each expression consists of a span (the location information of the expression)
 /// and the kind of the expression.
-pub type Expression<'a> = (Span<'a>, ExpressionKind<'a>);
+pub type Expression = (Span, ExpressionKind);
 
 /// Enum that details the different types of expressions that can be produced
 /// by the `Expression`. The `ExpressionKind` should strive to only store types that
 /// are small in nature and any other types (i.e. String) should be stored in the
 /// `Interner`.
 #[derive(Debug)]
-pub enum ExpressionKind<'a> {
+pub enum ExpressionKind {
     Int(i64),
     Float(f64),
     Boolean(bool),
@@ -19,56 +21,70 @@ pub enum ExpressionKind<'a> {
     // stored in the `Interner` to reduce redundency in values. Instead,
     // the id's are stored in the variant.
     Identifier(Identifier),
-    Unary(Unary<'a>),
-    Binary(Binary<'a>),
-    If(If<'a>),
-    Let(Let<'a>),
-    Block(Vec<Expression<'a>>),
-    Application(Application<'a>),
-    While(While<'a>),
-    Return(Option<Box<Expression<'a>>>),
+    Unary(Unary),
+    Binary(Binary),
+    If(If),
+    Let(Let),
+    Block(Vec<Expression>),
+    Application(Application),
+    While(While),
+    Return(Option<Box<Expression>>),
+    // The message of the `Panic` is stored in the `Interner`.
+    Panic(usize),
+    Assert(Box<Expression>),
+    AssertEq(AssertEq),
+    // A placeholder for an expression that could not be parsed.
+    // The error itself is reported by the `Parser`.
+    Error,
 }
 
 #[derive(Debug)]
 pub struct Identifier(pub usize);
 
 #[derive(Debug)]
-pub struct Unary<'a> {
+pub struct Unary {
     pub operation: UnaryOperation,
-    pub expression: Box<Expression<'a>>,
+    pub expression: Box<Expression>,
 }
 
 #[derive(Debug)]
-pub struct Binary<'a> {
+pub struct Binary {
     pub operation: BinaryOperation,
-    pub left: Box<Expression<'a>>,
-    pub right: Box<Expression<'a>>,
+    pub operation_span: Span,
+    pub left: Box<Expression>,
+    pub right: Box<Expression>,
 }
 
 #[derive(Debug)]
-pub struct If<'a> {
-    pub condition: Box<Expression<'a>>,
-    pub then_branch: Box<Expression<'a>>,
-    pub else_branch: Option<Box<Expression<'a>>>,
+pub struct If {
+    pub condition: Box<Expression>,
+    pub then_branch: Box<Expression>,
+    pub else_branch: Option<Box<Expression>>,
 }
 
 #[derive(Debug)]
-pub struct Let<'a> {
-    pub name: (Span<'a>, Identifier),
+pub struct Let {
+    pub name: (Span, Identifier),
     pub given_type: Option<Type>,
-    pub expression: Box<Expression<'a>>,
+    pub expression: Box<Expression>,
+}
+
+#[derive(Debug)]
+pub struct Application {
+    pub function_name: (Span, Identifier),
+    pub parameters: Vec<Expression>,
 }
 
 #[derive(Debug)]
-pub struct Application<'a> {
-    pub function_name: (Span<'a>, Identifier),
-    pub parameters: Vec<Expression<'a>>,
+pub struct While {
+    pub condition: Box<Expression>,
+    pub expression: Box<Expression>,
 }
 
 #[derive(Debug)]
-pub struct While<'a> {
-    pub condition: Box<Expression<'a>>,
-    pub expression: Box<Expression<'a>>,
+pub struct AssertEq {
+    pub left: Box<Expression>,
+    pub right: Box<Expression>,
 }
 
 /// Enum that details the different unary operations
@@ -100,3 +116,31 @@ pub enum BinaryOperation {
     Or,
     And,
 }
+
+impl Display for UnaryOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnaryOperation::Plus => write!(f, "+"),
+            UnaryOperation::Minus => write!(f, "-"),
+            UnaryOperation::Not => write!(f, "not"),
+        }
+    }
+}
+
+impl Display for BinaryOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BinaryOperation::Plus => write!(f, "+"),
+            BinaryOperation::Minus => write!(f, "-"),
+            BinaryOperation::Multiply => write!(f, "*"),
+            BinaryOperation::Divide => write!(f, "/"),
+            BinaryOperation::Equals => write!(f, "="),
+            BinaryOperation::LessThan => write!(f, "<"),
+            BinaryOperation::GreaterThan => write!(f, ">"),
+            BinaryOperation::LessThanEquals => write!(f, "<="),
+            BinaryOperation::GreaterThanEquals => write!(f, ">="),
+            BinaryOperation::Or => write!(f, "or"),
+            BinaryOperation::And => write!(f, "and"),
+        }
+    }
+}
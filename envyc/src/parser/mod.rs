@@ -1,7 +1,7 @@
 use std::{iter::Peekable, mem};
 
 use ast::ExternDeclaration;
-use expression::{BinaryOperation, Expression, UnaryOperation};
+use expression::{BinaryOperation, Expression, ExpressionKind, UnaryOperation};
 use parselets::LetParselet;
 
 use crate::{
@@ -11,12 +11,12 @@ use crate::{
 };
 
 use self::{
-    ast::{Function, Parameter, Program, Prototype},
+    ast::{Attribute, Function, Parameter, Program, Prototype},
     parselets::{
         infix_parselet::InfixParselet, precedence::Precedence, prefix_parselet::PrefixParselet,
-        BinaryOperationParselet, BlockParselet, BooleanParselet, CharParselet, FloatParselet,
-        IdentifierParselet, IfParselet, IntParselet, ParenthesisParselet, PrefixOperationParselet,
-        ReturnParselet, WhileParselet,
+        AssertEqParselet, AssertParselet, BinaryOperationParselet, BlockParselet, BooleanParselet,
+        CharParselet, FloatParselet, IdentifierParselet, IfParselet, IntParselet, PanicParselet,
+        ParenthesisParselet, PrefixOperationParselet, ReturnParselet, WhileParselet,
     },
 };
 
@@ -31,51 +31,193 @@ pub mod typed_expression;
 /// The `Parser` uses a mixture of the Pratt parsing technique and the
 /// recursive descent algorithm. It achieves this through the mini parsers
 /// called parselets.
-pub struct Parser<'a, T: Iterator<Item = Token<'a>>> {
+pub struct Parser<T: Iterator<Item = Token>> {
     tokens: Peekable<T>,
+    /// The errors found so far. The `Parser` recovers from errors
+    /// so that every syntax error can be reported at once.
+    errors: Vec<Error>,
 }
 
-impl<'a, T: Iterator<Item = Token<'a>>> Parser<'a, T> {
+impl<T: Iterator<Item = Token>> Parser<T> {
     pub fn new(tokens: Peekable<T>) -> Self {
-        Self { tokens }
+        Self {
+            tokens,
+            errors: vec![],
+        }
     }
 
     /// Walks through the tokens and constructs a program, or a vector
     /// of functions.
-    pub fn parse(&mut self) -> Result<Program<'a>, Vec<Error<'a>>> {
+    pub fn parse(&mut self) -> Result<Program, Vec<Error>> {
+        let (program, errors) = self.parse_with_recovery();
+        if !errors.is_empty() {
+            Err(errors)
+        } else {
+            Ok(program)
+        }
+    }
+
+    /// Walks through the tokens and constructs a program, recovering from any
+    /// syntax errors along the way. Expressions that could not be parsed are
+    /// replaced by `ExpressionKind::Error`, including the body of a function
+    /// that could not be parsed, and the other definitions that could not be
+    /// parsed are skipped. The program is returned with every error found.
+    pub fn parse_with_recovery(&mut self) -> (Program, Vec<Error>) {
         let mut extern_declarations = vec![];
         let mut functions = vec![];
-        let mut errors = vec![];
         while let Some(&(span, kind)) = self.tokens.peek() {
             match kind {
-                TokenKind::Define => match self.parse_function(span) {
+                TokenKind::Define | TokenKind::Hash => match self.parse_function(span) {
                     Ok(function) => functions.push(function),
-                    Err(error) => errors.push(error),
+                    Err(error) => {
+                        self.report(error);
+                        self.synchronize_definition();
+                    }
                 },
                 TokenKind::Extern => match self.parse_extern_declaration(span) {
                     Ok(extern_declaration) => extern_declarations.push(extern_declaration),
-                    Err(error) => errors.push(error),
+                    Err(error) => {
+                        self.report(error);
+                        self.synchronize_definition();
+                    }
                 },
                 _ => {
-                    errors.push(Error::ExpectedKind {
+                    self.report(Error::ExpectedKind {
                         span,
-                        expected_kinds: vec![TokenKind::Define, TokenKind::Extern],
+                        expected_kinds: vec![TokenKind::Define, TokenKind::Extern, TokenKind::Hash],
                         actual_kind: kind,
                     });
 
-                    return Err(errors);
+                    self.tokens.next();
+                    self.synchronize_definition();
+                }
+            }
+        }
+
+        let errors = mem::take(&mut self.errors);
+        (Program::new(extern_declarations, functions), errors)
+    }
+
+    /// Walks through the tokens and constructs the expressions of a single
+    /// line of input, such as the input of the REPL. The expressions are
+    /// parsed like the expressions of a block, recovering from any syntax
+    /// errors along the way.
+    ///
+    /// # Arguments
+    /// * `span` - The `Span` of the start of the input.
+    pub fn parse_expressions(&mut self, span: Span) -> Result<Vec<Expression>, Vec<Error>> {
+        let mut expressions = vec![];
+        let mut last_span = span;
+        while self.tokens.peek().is_some() {
+            match self.parse_expression(0, last_span) {
+                Ok(expression) => {
+                    last_span = expression.0;
+                    expressions.push(expression);
+                }
+                Err(error) => {
+                    expressions.push(self.recover(error, last_span));
+                    if !self.can_continue_block() {
+                        break;
+                    }
+
+                    // A `}` that does not close any block is skipped.
+                    if let Some((_, TokenKind::RightCurlyBrace)) = self.tokens.peek() {
+                        self.tokens.next();
+                    }
                 }
             }
         }
 
+        let errors = mem::take(&mut self.errors);
         if !errors.is_empty() {
             Err(errors)
         } else {
-            Ok(Program::new(extern_declarations, functions))
+            Ok(expressions)
+        }
+    }
+
+    /// Records an error found while parsing. Errors that were already
+    /// reported are ignored, since they are cascades of the same mistake.
+    /// Only the first unexpected end of input is reported.
+    ///
+    /// # Arguments
+    /// * `error` - The error to record.
+    fn report(&mut self, error: Error) {
+        let is_duplicate = self.errors.iter().any(|reported| {
+            reported == &error
+                || matches!(
+                    (reported, &error),
+                    (
+                        Error::UnexpectedEndOfInput(_),
+                        Error::UnexpectedEndOfInput(_)
+                    )
+                )
+        });
+
+        if !is_duplicate {
+            self.errors.push(error);
+        }
+    }
+
+    /// Skips tokens until the start of the next definition, which is
+    /// either a `define` or an `extern`.
+    fn synchronize_definition(&mut self) {
+        while let Some((_, kind)) = self.tokens.peek() {
+            if matches!(
+                kind,
+                TokenKind::Define | TokenKind::Extern | TokenKind::Hash
+            ) {
+                break;
+            }
+
+            self.tokens.next();
+        }
+    }
+
+    /// Records the error and skips tokens until the end of the expression that
+    /// failed to parse. The expression ends at a `;`, which is consumed, or
+    /// before the `}` that closes the current block. Blocks nested within the
+    /// skipped tokens are skipped entirely. A placeholder expression is
+    /// returned in place of the expression.
+    ///
+    /// # Arguments
+    /// * `error` - The error that occurred.
+    /// * `span` - The `Span` of the last token before the expression.
+    fn recover(&mut self, error: Error, span: Span) -> Expression {
+        self.report(error);
+        let mut depth = 0;
+        while let Some((_, kind)) = self.tokens.peek() {
+            match kind {
+                TokenKind::Define | TokenKind::Extern | TokenKind::Hash => break,
+                TokenKind::RightCurlyBrace if depth == 0 => break,
+                TokenKind::SemiColon if depth == 0 => {
+                    self.tokens.next();
+                    break;
+                }
+                TokenKind::LeftCurlyBrace => depth += 1,
+                TokenKind::RightCurlyBrace => depth -= 1,
+                _ => {}
+            }
+
+            self.tokens.next();
         }
+
+        (span, ExpressionKind::Error)
+    }
+
+    /// Determines whether a block can keep parsing expressions after recovering
+    /// from an error. This is not possible if the input ended or if the next
+    /// token starts a new definition.
+    fn can_continue_block(&mut self) -> bool {
+        !matches!(
+            self.tokens.peek(),
+            None | Some((_, TokenKind::Define))
+                | Some((_, TokenKind::Extern))
+                | Some((_, TokenKind::Hash))
+        )
     }
 
-    fn parse_prototype(&mut self, span: Span<'a>) -> Result<(Span<'a>, Prototype<'a>), Error<'a>> {
+    fn parse_prototype(&mut self, span: Span) -> Result<(Span, Prototype), Error> {
         if let (prototype_name_span, TokenKind::Identifier(id)) =
             self.expect(TokenKind::Identifier(0), span)?
         {
@@ -123,10 +265,7 @@ impl<'a, T: Iterator<Item = Token<'a>>> Parser<'a, T> {
         }
     }
 
-    fn parse_extern_declaration(
-        &mut self,
-        span: Span<'a>,
-    ) -> Result<ExternDeclaration<'a>, Error<'a>> {
+    fn parse_extern_declaration(&mut self, span: Span) -> Result<ExternDeclaration, Error> {
         let (extern_span, _) = self.expect(TokenKind::Extern, span)?;
         if let (prototype_name_span, TokenKind::Identifier(id)) =
             self.expect(TokenKind::Identifier(0), extern_span)?
@@ -174,15 +313,90 @@ impl<'a, T: Iterator<Item = Token<'a>>> Parser<'a, T> {
         }
     }
 
-    fn parse_function(&mut self, span: Span<'a>) -> Result<Function<'a>, Error<'a>> {
-        let (define_span, _) = self.expect(TokenKind::Define, span)?;
+    fn parse_function(&mut self, span: Span) -> Result<Function, Error> {
+        let mut attributes = vec![];
+        let mut last_span = span;
+        while let Some((_, TokenKind::Hash)) = self.tokens.peek() {
+            let attribute = self.parse_attribute(last_span)?;
+            last_span = attribute.span;
+            attributes.push(attribute);
+        }
+
+        let (define_span, _) = self.expect(TokenKind::Define, last_span)?;
         let (right_paren_span, prototype) = self.parse_prototype(define_span)?;
         let (eq_span, _) = self.expect(TokenKind::EqualSign, right_paren_span)?;
-        let body = self.parse_expression(0, eq_span)?;
-        Ok(Function::new(prototype, body))
+        // The prototype is kept when the body can not be parsed, so that
+        // calls to the function are still checked against it.
+        let body = match self.parse_expression(0, eq_span) {
+            Ok(body) => body,
+            Err(error) => {
+                let body = self.recover(error, eq_span);
+                self.synchronize_definition();
+                body
+            }
+        };
+
+        Ok(Function::new(attributes, prototype, body))
+    }
+
+    /// Parses an attribute of the form `#[name(argument, ...)]`.
+    ///
+    /// # Arguments
+    /// * `span` - The `Span` of the previous token.
+    fn parse_attribute(&mut self, span: Span) -> Result<Attribute, Error> {
+        let (hash_span, _) = self.expect(TokenKind::Hash, span)?;
+        let (left_bracket_span, _) = self.expect(TokenKind::LeftSquareBracket, hash_span)?;
+        let name = match self.expect(TokenKind::Identifier(0), left_bracket_span)? {
+            (name_span, TokenKind::Identifier(id)) => (name_span, id),
+            _ => unreachable!(),
+        };
+
+        let (mut last_span, _) = self.expect(TokenKind::LeftParenthesis, name.0)?;
+        let mut arguments = vec![];
+        loop {
+            match self.consume(last_span)? {
+                (argument_span, TokenKind::Identifier(id)) => {
+                    arguments.push((argument_span, id));
+                    last_span = argument_span;
+                }
+                (right_paren_span, TokenKind::RightParenthesis) if arguments.is_empty() => {
+                    last_span = right_paren_span;
+                    break;
+                }
+                (span, actual_kind) => {
+                    return Err(Error::ExpectedKind {
+                        span,
+                        expected_kinds: vec![TokenKind::Identifier(0)],
+                        actual_kind,
+                    })
+                }
+            }
+
+            match self.consume(last_span)? {
+                (comma_span, TokenKind::Comma) => last_span = comma_span,
+                (right_paren_span, TokenKind::RightParenthesis) => {
+                    last_span = right_paren_span;
+                    break;
+                }
+                (span, actual_kind) => {
+                    return Err(Error::ExpectedKind {
+                        span,
+                        expected_kinds: vec![TokenKind::Comma, TokenKind::RightParenthesis],
+                        actual_kind,
+                    })
+                }
+            }
+        }
+
+        let (right_bracket_span, _) = self.expect(TokenKind::RightSquareBracket, last_span)?;
+        Ok(Attribute {
+            span: hash_span.combine(right_bracket_span),
+            name,
+            arguments,
+        })
     }
 
-    fn parse_types_list(&mut self) -> Result<Vec<(Type, Span<'a>)>, Error<'a>> {
+    fn parse_types_list(&mut self) -> Result<Vec<(Type, Span)>, Error> {
         let mut types = vec![];
         while let Some((_, kind)) = self.tokens.peek() {
             if kind == &TokenKind::RightParenthesis {
@@ -222,7 +436,7 @@ impl<'a, T: Iterator<Item = Token<'a>>> Parser<'a, T> {
         Ok(types)
     }
 
-    fn parse_parameters(&mut self) -> Result<Vec<Parameter<'a>>, Error<'a>> {
+    fn parse_parameters(&mut self) -> Result<Vec<Parameter>, Error> {
         let mut parameters = vec![];
         while let Some((_, kind)) = self.tokens.peek() {
             if kind == &TokenKind::RightParenthesis {
@@ -281,11 +495,7 @@ impl<'a, T: Iterator<Item = Token<'a>>> Parser<'a, T> {
     /// # Arguments
     /// * `precendence` - The current precedence to use when evaluating expressions.
     /// * `span` - The `Span` of the current token.
-    fn parse_expression(
-        &mut self,
-        precedence: usize,
-        span: Span<'a>,
-    ) -> Result<Expression<'a>, Error<'a>> {
+    fn parse_expression(&mut self, precedence: usize, span: Span) -> Result<Expression, Error> {
         let token = self.consume(span)?;
         let mut left = self.parse_prefix(token)?;
         while precedence < self.get_precedence() {
@@ -302,7 +512,7 @@ impl<'a, T: Iterator<Item = Token<'a>>> Parser<'a, T> {
     ///
     /// # Arguments
     /// * `token` - The token to parse into a prefix expression.
-    fn parse_prefix(&mut self, token: Token<'a>) -> Result<Expression<'a>, Error<'a>> {
+    fn parse_prefix(&mut self, token: Token) -> Result<Expression, Error> {
         match token.1 {
             TokenKind::IntegerLiteral(_) => IntParselet.parse(self, token),
             TokenKind::FloatLiteral(_) => FloatParselet.parse(self, token),
@@ -325,6 +535,9 @@ impl<'a, T: Iterator<Item = Token<'a>>> Parser<'a, T> {
             TokenKind::While => WhileParselet.parse(self, token),
             TokenKind::LeftParenthesis => ParenthesisParselet.parse(self, token),
             TokenKind::Return => ReturnParselet.parse(self, token),
+            TokenKind::Panic => PanicParselet.parse(self, token),
+            TokenKind::Assert => AssertParselet.parse(self, token),
+            TokenKind::AssertEq => AssertEqParselet.parse(self, token),
             _ => Err(Error::ExpectedPrefixExpression {
                 span: token.0,
                 found_kind: token.1,
@@ -339,11 +552,7 @@ impl<'a, T: Iterator<Item = Token<'a>>> Parser<'a, T> {
     /// # Arguments
     /// * `left` - The first part of the infix expression that was already parsed.
     /// * `token` - The token to parse into a prefix expression.
-    fn parse_infix(
-        &mut self,
-        left: Expression<'a>,
-        token: Token<'a>,
-    ) -> Result<Expression<'a>, Error<'a>> {
+    fn parse_infix(&mut self, left: Expression, token: Token) -> Result<Expression, Error> {
         match token.1 {
             TokenKind::Plus => {
                 BinaryOperationParselet::new(Precedence::Addition, BinaryOperation::Plus, false)
@@ -427,7 +636,7 @@ impl<'a, T: Iterator<Item = Token<'a>>> Parser<'a, T> {
 
     /// Returns an immutable reference to the next token
     /// without consuming it.
-    fn peek(&mut self) -> Option<&Token<'a>> {
+    fn peek(&mut self) -> Option<&Token> {
         self.tokens.peek()
     }
 
@@ -437,7 +646,7 @@ impl<'a, T: Iterator<Item = Token<'a>>> Parser<'a, T> {
     ///
     /// # Arguments
     /// * `span` - The `Span` of the previous token.
-    fn consume(&mut self, span: Span<'a>) -> Result<Token<'a>, Error<'a>> {
+    fn consume(&mut self, span: Span) -> Result<Token, Error> {
         match self.tokens.next() {
             Some(token) => Ok(token),
             None => Err(Error::UnexpectedEndOfInput(span)),
@@ -452,7 +661,7 @@ impl<'a, T: Iterator<Item = Token<'a>>> Parser<'a, T> {
     /// # Arguments
     /// * `expected_kind` - The kind expected of the next token.
     /// * `span` - The `Span` of the previous token.
-    fn expect(&mut self, expected_kind: TokenKind, span: Span<'a>) -> Result<Token<'a>, Error<'a>> {
+    fn expect(&mut self, expected_kind: TokenKind, span: Span) -> Result<Token, Error> {
         let token = self.consume(span)?;
 
         if mem::discriminant(&token.1) == mem::discriminant(&expected_kind) {
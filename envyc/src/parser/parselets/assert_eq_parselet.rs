@@ -0,0 +1,33 @@
+use crate::{
+    error::Error,
+    lexer::token::{Token, TokenKind},
+    parser::{
+        expression::{AssertEq, Expression, ExpressionKind},
+        Parser,
+    },
+};
+
+use super::prefix_parselet::PrefixParselet;
+
+pub struct AssertEqParselet;
+impl PrefixParselet for AssertEqParselet {
+    fn parse(
+        &self,
+        parser: &mut Parser<impl Iterator<Item = Token>>,
+        token: Token,
+    ) -> Result<Expression, Error> {
+        let (left_parenthesis_span, _) = parser.expect(TokenKind::LeftParenthesis, token.0)?;
+        let left = parser.parse_expression(0, left_parenthesis_span)?;
+        let (comma_span, _) = parser.expect(TokenKind::Comma, left.0)?;
+        let right = parser.parse_expression(0, comma_span)?;
+        let (right_parenthesis_span, _) = parser.expect(TokenKind::RightParenthesis, right.0)?;
+
+        Ok((
+            token.0.combine(right_parenthesis_span),
+            ExpressionKind::AssertEq(AssertEq {
+                left: Box::new(left),
+                right: Box::new(right),
+            }),
+        ))
+    }
+}
@@ -0,0 +1,29 @@
+use crate::{
+    error::Error,
+    lexer::token::{Token, TokenKind},
+    parser::{
+        expression::{Expression, ExpressionKind},
+        Parser,
+    },
+};
+
+use super::prefix_parselet::PrefixParselet;
+
+pub struct AssertParselet;
+impl PrefixParselet for AssertParselet {
+    fn parse(
+        &self,
+        parser: &mut Parser<impl Iterator<Item = Token>>,
+        token: Token,
+    ) -> Result<Expression, Error> {
+        let (left_parenthesis_span, _) = parser.expect(TokenKind::LeftParenthesis, token.0)?;
+        let condition = parser.parse_expression(0, left_parenthesis_span)?;
+        let (right_parenthesis_span, _) =
+            parser.expect(TokenKind::RightParenthesis, condition.0)?;
+
+        Ok((
+            token.0.combine(right_parenthesis_span),
+            ExpressionKind::Assert(Box::new(condition)),
+        ))
+    }
+}
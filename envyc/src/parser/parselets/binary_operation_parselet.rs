@@ -29,13 +29,13 @@ impl BinaryOperationParselet {
     }
 }
 
-impl<'a> InfixParselet<'a> for BinaryOperationParselet {
+impl InfixParselet for BinaryOperationParselet {
     fn parse(
         &self,
-        parser: &mut Parser<'a, impl Iterator<Item = Token<'a>>>,
-        left: Expression<'a>,
-        token: Token<'a>,
-    ) -> Result<Expression<'a>, Error<'a>> {
+        parser: &mut Parser<impl Iterator<Item = Token>>,
+        left: Expression,
+        token: Token,
+    ) -> Result<Expression, Error> {
         let right = parser.parse_expression(
             self.precedence - if self.is_right_associative { 1 } else { 0 },
             token.0,
@@ -44,6 +44,7 @@ impl<'a> InfixParselet<'a> for BinaryOperationParselet {
         let binary_operation_span = left.0.combine(right.0);
         let kind = ExpressionKind::Binary(Binary {
             operation: self.operation,
+            operation_span: token.0,
             left: Box::new(left),
             right: Box::new(right),
         });
@@ -10,12 +10,12 @@ use crate::{
 use super::prefix_parselet::PrefixParselet;
 
 pub struct BlockParselet;
-impl<'a> PrefixParselet<'a> for BlockParselet {
+impl PrefixParselet for BlockParselet {
     fn parse(
         &self,
-        parser: &mut Parser<'a, impl Iterator<Item = Token<'a>>>,
-        token: Token<'a>,
-    ) -> Result<Expression<'a>, Error<'a>> {
+        parser: &mut Parser<impl Iterator<Item = Token>>,
+        token: Token,
+    ) -> Result<Expression, Error> {
         let mut expressions = vec![];
         let mut last_span = token.0;
         loop {
@@ -24,9 +24,18 @@ impl<'a> PrefixParselet<'a> for BlockParselet {
                 break;
             }
 
-            let expression = parser.parse_expression(0, last_span)?;
-            last_span = expression.0;
-            expressions.push(expression);
+            match parser.parse_expression(0, last_span) {
+                Ok(expression) => {
+                    last_span = expression.0;
+                    expressions.push(expression);
+                }
+                Err(error) => {
+                    expressions.push(parser.recover(error, last_span));
+                    if !parser.can_continue_block() {
+                        break;
+                    }
+                }
+            }
         }
 
         Ok((
@@ -20,12 +20,12 @@ macro_rules! get {
 }
 
 pub struct CharParselet;
-impl<'a> PrefixParselet<'a> for CharParselet {
+impl PrefixParselet for CharParselet {
     fn parse(
         &self,
-        _: &mut Parser<'a, impl Iterator<Item = Token<'a>>>,
-        token: Token<'a>,
-    ) -> Result<Expression<'a>, Error<'a>> {
+        _: &mut Parser<impl Iterator<Item = Token>>,
+        token: Token,
+    ) -> Result<Expression, Error> {
         let value = get!(token, TokenKind::CharLiteral(value), value);
         Ok((token.0, ExpressionKind::Char(value)))
     }
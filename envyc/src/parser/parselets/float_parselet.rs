@@ -20,12 +20,12 @@ macro_rules! get {
 }
 
 pub struct FloatParselet;
-impl<'a> PrefixParselet<'a> for FloatParselet {
+impl PrefixParselet for FloatParselet {
     fn parse(
         &self,
-        _: &mut Parser<'a, impl Iterator<Item = Token<'a>>>,
-        token: Token<'a>,
-    ) -> Result<Expression<'a>, Error<'a>> {
+        _: &mut Parser<impl Iterator<Item = Token>>,
+        token: Token,
+    ) -> Result<Expression, Error> {
         let value = get!(token, TokenKind::FloatLiteral(value), value);
         Ok((token.0, ExpressionKind::Float(value)))
     }
@@ -20,12 +20,12 @@ macro_rules! get {
 }
 
 pub struct IdentifierParselet;
-impl<'a> PrefixParselet<'a> for IdentifierParselet {
+impl PrefixParselet for IdentifierParselet {
     fn parse(
         &self,
-        parser: &mut Parser<'a, impl Iterator<Item = Token<'a>>>,
-        token: Token<'a>,
-    ) -> Result<Expression<'a>, Error<'a>> {
+        parser: &mut Parser<impl Iterator<Item = Token>>,
+        token: Token,
+    ) -> Result<Expression, Error> {
         let id = get!(token, TokenKind::Identifier(id), id);
         if let Some((_, TokenKind::LeftParenthesis)) = parser.peek() {
             let (left_parenthesis_span, _) = parser.consume(token.0)?;
@@ -10,12 +10,12 @@ use crate::{
 use super::prefix_parselet::PrefixParselet;
 
 pub struct IfParselet;
-impl<'a> PrefixParselet<'a> for IfParselet {
+impl PrefixParselet for IfParselet {
     fn parse(
         &self,
-        parser: &mut Parser<'a, impl Iterator<Item = Token<'a>>>,
-        token: Token<'a>,
-    ) -> Result<Expression<'a>, Error<'a>> {
+        parser: &mut Parser<impl Iterator<Item = Token>>,
+        token: Token,
+    ) -> Result<Expression, Error> {
         let condition = parser.parse_expression(0, token.0)?;
         let (then_span, _) = parser.expect(TokenKind::Then, condition.0)?;
         let then_branch = parser.parse_expression(0, then_span)?;
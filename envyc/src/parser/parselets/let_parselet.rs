@@ -21,12 +21,12 @@ macro_rules! get {
 }
 
 pub struct LetParselet;
-impl<'a> PrefixParselet<'a> for LetParselet {
+impl PrefixParselet for LetParselet {
     fn parse(
         &self,
-        parser: &mut Parser<'a, impl Iterator<Item = Token<'a>>>,
-        token: Token<'a>,
-    ) -> Result<Expression<'a>, Error<'a>> {
+        parser: &mut Parser<impl Iterator<Item = Token>>,
+        token: Token,
+    ) -> Result<Expression, Error> {
         let identifier = parser.expect(TokenKind::Identifier(0), token.0)?;
         let id = get!(identifier, TokenKind::Identifier(id), id);
         let (given_type, type_span) = {
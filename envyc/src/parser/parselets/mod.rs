@@ -1,3 +1,5 @@
+pub mod assert_eq_parselet;
+pub mod assert_parselet;
 pub mod binary_operation_parselet;
 pub mod block_parselet;
 pub mod boolean_parselet;
@@ -8,6 +10,7 @@ pub mod if_parselet;
 pub mod infix_parselet;
 pub mod int_parselet;
 pub mod let_parselet;
+pub mod panic_parselet;
 pub mod parenthesis_parselet;
 pub mod precedence;
 pub mod prefix_operation_parselet;
@@ -15,6 +18,8 @@ pub mod prefix_parselet;
 pub mod return_parselet;
 pub mod while_parselet;
 
+pub use assert_eq_parselet::AssertEqParselet;
+pub use assert_parselet::AssertParselet;
 pub use binary_operation_parselet::BinaryOperationParselet;
 pub use block_parselet::BlockParselet;
 pub use boolean_parselet::BooleanParselet;
@@ -24,6 +29,7 @@ pub use identifier_parselet::IdentifierParselet;
 pub use if_parselet::IfParselet;
 pub use int_parselet::IntParselet;
 pub use let_parselet::LetParselet;
+pub use panic_parselet::PanicParselet;
 pub use parenthesis_parselet::ParenthesisParselet;
 pub use prefix_operation_parselet::PrefixOperationParselet;
 pub use return_parselet::ReturnParselet;
@@ -0,0 +1,36 @@
+use crate::{
+    error::Error,
+    lexer::token::{Token, TokenKind},
+    parser::{
+        expression::{Expression, ExpressionKind},
+        Parser,
+    },
+};
+
+use super::prefix_parselet::PrefixParselet;
+
+pub struct PanicParselet;
+impl PrefixParselet for PanicParselet {
+    fn parse(
+        &self,
+        parser: &mut Parser<impl Iterator<Item = Token>>,
+        token: Token,
+    ) -> Result<Expression, Error> {
+        let (left_parenthesis_span, _) = parser.expect(TokenKind::LeftParenthesis, token.0)?;
+        let (message_span, message) =
+            parser.expect(TokenKind::StringLiteral(0), left_parenthesis_span)?;
+        let message = if let TokenKind::StringLiteral(id) = message {
+            id
+        } else {
+            unreachable!()
+        };
+
+        let (right_parenthesis_span, _) =
+            parser.expect(TokenKind::RightParenthesis, message_span)?;
+
+        Ok((
+            token.0.combine(right_parenthesis_span),
+            ExpressionKind::Panic(message),
+        ))
+    }
+}
@@ -7,12 +7,12 @@ use crate::{
 use super::prefix_parselet::PrefixParselet;
 
 pub struct ParenthesisParselet;
-impl<'a> PrefixParselet<'a> for ParenthesisParselet {
+impl PrefixParselet for ParenthesisParselet {
     fn parse(
         &self,
-        parser: &mut Parser<'a, impl Iterator<Item = Token<'a>>>,
-        token: Token<'a>,
-    ) -> Result<Expression<'a>, Error<'a>> {
+        parser: &mut Parser<impl Iterator<Item = Token>>,
+        token: Token,
+    ) -> Result<Expression, Error> {
         let expression = parser.parse_expression(0, token.0)?;
         let (right_parenthesis_span, _) =
             parser.expect(TokenKind::RightParenthesis, expression.0)?;
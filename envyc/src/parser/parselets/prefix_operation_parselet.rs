@@ -23,12 +23,12 @@ impl PrefixOperationParselet {
     }
 }
 
-impl<'a> PrefixParselet<'a> for PrefixOperationParselet {
+impl PrefixParselet for PrefixOperationParselet {
     fn parse(
         &self,
-        parser: &mut Parser<'a, impl Iterator<Item = Token<'a>>>,
-        token: Token<'a>,
-    ) -> Result<Expression<'a>, Error<'a>> {
+        parser: &mut Parser<impl Iterator<Item = Token>>,
+        token: Token,
+    ) -> Result<Expression, Error> {
         let operand = parser.parse_expression(self.precedence, token.0)?;
         let span = token.0.combine(operand.0);
         let kind = ExpressionKind::Unary(Unary {
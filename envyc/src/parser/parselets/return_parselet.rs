@@ -10,12 +10,12 @@ use crate::{
 use super::prefix_parselet::PrefixParselet;
 
 pub struct ReturnParselet;
-impl<'a> PrefixParselet<'a> for ReturnParselet {
+impl PrefixParselet for ReturnParselet {
     fn parse(
         &self,
-        parser: &mut Parser<'a, impl Iterator<Item = Token<'a>>>,
-        token: Token<'a>,
-    ) -> Result<Expression<'a>, Error<'a>> {
+        parser: &mut Parser<impl Iterator<Item = Token>>,
+        token: Token,
+    ) -> Result<Expression, Error> {
         let mut expression = None;
         match parser.peek() {
             Some((_, TokenKind::SemiColon)) => {
@@ -10,12 +10,12 @@ use crate::{
 use super::prefix_parselet::PrefixParselet;
 
 pub struct WhileParselet;
-impl<'a> PrefixParselet<'a> for WhileParselet {
+impl PrefixParselet for WhileParselet {
     fn parse(
         &self,
-        parser: &mut Parser<'a, impl Iterator<Item = Token<'a>>>,
-        token: Token<'a>,
-    ) -> Result<Expression<'a>, Error<'a>> {
+        parser: &mut Parser<impl Iterator<Item = Token>>,
+        token: Token,
+    ) -> Result<Expression, Error> {
         let condition = parser.parse_expression(0, token.0)?;
         let expression = parser.parse_expression(0, condition.0)?;
 
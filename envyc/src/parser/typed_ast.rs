@@ -1,17 +1,17 @@
-use crate::{error::Span, semantic_analyzer::types::Type};
+use crate::{error::Span, interner::Interner, semantic_analyzer::types::Type};
 
-use super::typed_expression::TypedExpression;
+use super::{ast::Attribute, typed_expression::TypedExpression};
 
 #[derive(Debug)]
-pub struct TypedProgram<'a> {
-    pub extern_declarations: Vec<TypedExternDeclaration<'a>>,
-    pub functions: Vec<TypedFunction<'a>>,
+pub struct TypedProgram {
+    pub extern_declarations: Vec<TypedExternDeclaration>,
+    pub functions: Vec<TypedFunction>,
 }
 
-impl<'a> TypedProgram<'a> {
+impl TypedProgram {
     pub fn new(
-        extern_declarations: Vec<TypedExternDeclaration<'a>>,
-        functions: Vec<TypedFunction<'a>>,
+        extern_declarations: Vec<TypedExternDeclaration>,
+        functions: Vec<TypedFunction>,
     ) -> Self {
         Self {
             extern_declarations,
@@ -21,18 +21,18 @@ impl<'a> TypedProgram<'a> {
 }
 
 #[derive(Debug)]
-pub struct TypedPrototype<'a> {
-    pub span: Span<'a>,
+pub struct TypedPrototype {
+    pub span: Span,
     pub name: usize,
-    pub parameters: Vec<TypedParameter<'a>>,
+    pub parameters: Vec<TypedParameter>,
     pub return_type: Type,
 }
 
-impl<'a> TypedPrototype<'a> {
+impl TypedPrototype {
     pub fn new(
-        span: Span<'a>,
+        span: Span,
         name: usize,
-        parameters: Vec<TypedParameter<'a>>,
+        parameters: Vec<TypedParameter>,
         return_type: Type,
     ) -> Self {
         Self {
@@ -42,37 +42,64 @@ impl<'a> TypedPrototype<'a> {
             return_type,
         }
     }
+
+    /// Gets the signature of the function, such as `add(Int, Int) :: Int`.
+    ///
+    /// # Arguments
+    /// * `interner` - The `Interner` of the name of the function.
+    pub fn signature(&self, interner: &Interner<String>) -> String {
+        let parameter_types = self
+            .parameters
+            .iter()
+            .map(|parameter| parameter.ty.to_string())
+            .collect::<Vec<_>>();
+        format!(
+            "{}({}) :: {}",
+            interner.get(self.name),
+            parameter_types.join(", "),
+            self.return_type
+        )
+    }
 }
 
 #[derive(Debug)]
-pub struct TypedExternDeclaration<'a> {
-    pub span: Span<'a>,
+pub struct TypedExternDeclaration {
+    pub span: Span,
     pub name: usize,
-    pub parameters: Vec<(Type, Span<'a>)>,
-    pub return_type: (Type, Span<'a>),
+    pub parameters: Vec<(Type, Span)>,
+    pub return_type: (Type, Span),
 }
 
 #[derive(Debug)]
-pub struct TypedFunction<'a> {
-    pub prototype: TypedPrototype<'a>,
-    pub body: TypedExpression<'a>,
+pub struct TypedFunction {
+    pub attributes: Vec<Attribute>,
+    pub prototype: TypedPrototype,
+    pub body: TypedExpression,
 }
 
-impl<'a> TypedFunction<'a> {
-    pub fn new(prototype: TypedPrototype<'a>, body: TypedExpression<'a>) -> Self {
-        Self { prototype, body }
+impl TypedFunction {
+    pub fn new(
+        attributes: Vec<Attribute>,
+        prototype: TypedPrototype,
+        body: TypedExpression,
+    ) -> Self {
+        Self {
+            attributes,
+            prototype,
+            body,
+        }
     }
 }
 
 #[derive(Debug)]
-pub struct TypedParameter<'a> {
-    pub span: Span<'a>,
+pub struct TypedParameter {
+    pub span: Span,
     pub ty: Type,
     pub name: usize,
 }
 
-impl<'a> TypedParameter<'a> {
-    pub fn new(span: Span<'a>, ty: Type, name: usize) -> Self {
+impl TypedParameter {
+    pub fn new(span: Span, ty: Type, name: usize) -> Self {
         Self { span, ty, name }
     }
 }
@@ -5,14 +5,14 @@ use super::expression::{BinaryOperation, UnaryOperation};
 /// Represents an expression that is generated by the `Parser`.
 /// Each expression consists of a span (the location information of the expression)
 /// and the kind of the expression.
-pub type TypedExpression<'a> = (Span<'a>, TypedExpressionKind<'a>);
+pub type TypedExpression = (Span, TypedExpressionKind);
 
 /// Enum that details the different types of expressions that can be produced
 /// by the `Expression`. The `ExpressionKind` should strive to only store types that
 /// are small in nature and any other types (i.e. String) should be stored in the
 /// `Interner`.
 #[derive(Debug)]
-pub enum TypedExpressionKind<'a> {
+pub enum TypedExpressionKind {
     Int(i64),
     Float(f64),
     Boolean(bool),
@@ -21,17 +21,22 @@ pub enum TypedExpressionKind<'a> {
     // stored in the `Interner` to reduce redundency in values. Instead,
     // the id's are stored in the variant.
     Identifier(TypedIdentifier),
-    Unary(TypedUnary<'a>),
-    Binary(TypedBinary<'a>),
-    If(TypedIf<'a>),
-    Let(TypedLet<'a>),
-    Block(Vec<TypedExpression<'a>>),
-    Application(TypedApplication<'a>),
-    While(TypedWhile<'a>),
-    Return(Option<Box<TypedExpression<'a>>>),
+    Unary(TypedUnary),
+    Binary(TypedBinary),
+    If(TypedIf),
+    Let(TypedLet),
+    Block(Vec<TypedExpression>),
+    Application(TypedApplication),
+    While(TypedWhile),
+    Return(Option<Box<TypedExpression>>),
+    // The message of the `Panic` is stored in the `Interner`.
+    Panic(usize),
+    Assert(Box<TypedExpression>),
+    AssertEq(TypedAssertEq),
+    Error,
 }
 
-impl<'a> TypedExpressionKind<'a> {
+impl TypedExpressionKind {
     pub fn get_type(&self) -> Type {
         match self {
             TypedExpressionKind::Int(_) => Type::Int,
@@ -49,6 +54,10 @@ impl<'a> TypedExpressionKind<'a> {
             TypedExpressionKind::Application(ref inner) => inner.ty,
             TypedExpressionKind::While(_) => Type::Void,
             TypedExpressionKind::Return(_) => Type::Never,
+            TypedExpressionKind::Panic(_) => Type::Never,
+            TypedExpressionKind::Assert(_) => Type::Void,
+            TypedExpressionKind::AssertEq(_) => Type::Void,
+            TypedExpressionKind::Error => Type::Error,
         }
     }
 }
@@ -60,45 +69,52 @@ pub struct TypedIdentifier {
 }
 
 #[derive(Debug)]
-pub struct TypedUnary<'a> {
+pub struct TypedUnary {
     pub operation: UnaryOperation,
-    pub expression: Box<TypedExpression<'a>>,
+    pub expression: Box<TypedExpression>,
     pub ty: Type,
 }
 
 #[derive(Debug)]
-pub struct TypedBinary<'a> {
+pub struct TypedBinary {
     pub operation: BinaryOperation,
-    pub left: Box<TypedExpression<'a>>,
-    pub right: Box<TypedExpression<'a>>,
+    pub operation_span: Span,
+    pub left: Box<TypedExpression>,
+    pub right: Box<TypedExpression>,
     pub ty: Type,
 }
 
 #[derive(Debug)]
-pub struct TypedIf<'a> {
-    pub condition: Box<TypedExpression<'a>>,
-    pub then_branch: Box<TypedExpression<'a>>,
-    pub else_branch: Option<Box<TypedExpression<'a>>>,
+pub struct TypedIf {
+    pub condition: Box<TypedExpression>,
+    pub then_branch: Box<TypedExpression>,
+    pub else_branch: Option<Box<TypedExpression>>,
     pub ty: Type,
 }
 
 #[derive(Debug)]
-pub struct TypedLet<'a> {
-    pub name: (Span<'a>, TypedIdentifier),
+pub struct TypedLet {
+    pub name: (Span, TypedIdentifier),
     pub given_type: Option<Type>,
-    pub expression: Box<TypedExpression<'a>>,
+    pub expression: Box<TypedExpression>,
     pub ty: Type,
 }
 
 #[derive(Debug)]
-pub struct TypedApplication<'a> {
-    pub function_name: (Span<'a>, usize),
-    pub parameters: Vec<TypedExpression<'a>>,
+pub struct TypedApplication {
+    pub function_name: (Span, usize),
+    pub parameters: Vec<TypedExpression>,
     pub ty: Type,
 }
 
 #[derive(Debug)]
-pub struct TypedWhile<'a> {
-    pub condition: Box<TypedExpression<'a>>,
-    pub expression: Box<TypedExpression<'a>>,
+pub struct TypedWhile {
+    pub condition: Box<TypedExpression>,
+    pub expression: Box<TypedExpression>,
+}
+
+#[derive(Debug)]
+pub struct TypedAssertEq {
+    pub left: Box<TypedExpression>,
+    pub right: Box<TypedExpression>,
 }
@@ -0,0 +1,426 @@
+use std::fmt::{Display, Write};
+
+use crate::{
+    error::{SourceMap, Span},
+    interner::Interner,
+    lexer::token::{Token, TokenKind},
+    parser::{
+        ast::{Attribute, Program},
+        expression::{Expression, ExpressionKind},
+        typed_ast::TypedProgram,
+        typed_expression::{TypedExpression, TypedExpressionKind},
+    },
+    semantic_analyzer::types::Type,
+};
+
+/// Prints every token other than whitespace on its own line, along with
+/// its location and the text it was lexed from.
+///
+/// # Arguments
+/// * `tokens` - The tokens to print.
+/// * `source_map` - The `SourceMap` containing the file of the tokens.
+pub fn print_tokens(tokens: &[Token], source_map: &SourceMap) -> String {
+    let mut output = String::new();
+    for (span, kind) in tokens {
+        if matches!(kind, TokenKind::Whitespace(_)) {
+            continue;
+        }
+
+        let (line, column) = source_map.line_column(span.file_id, span.start);
+        let source = source_map.source(span.file_id);
+        let text = source.get(span.range()).unwrap_or_default();
+        let _ = writeln!(output, "{}:{} {} `{}`", line, column, kind, text);
+    }
+
+    output
+}
+
+/// Prints the parsed program as a tree, with one node on each line.
+///
+/// # Arguments
+/// * `program` - The program to print.
+/// * `interner` - The `Interner` of the names in the program.
+/// * `source_map` - The `SourceMap` containing the file of the program.
+pub fn print_program(
+    program: &Program,
+    interner: &Interner<String>,
+    source_map: &SourceMap,
+) -> String {
+    let mut printer = Printer::new(interner, source_map);
+    for extern_declaration in &program.extern_declarations {
+        printer.extern_declaration(
+            extern_declaration.span,
+            extern_declaration.name,
+            &extern_declaration.parameters,
+            extern_declaration.return_type.0,
+        );
+    }
+
+    for function in &program.functions {
+        printer.attributes(&function.attributes);
+        let parameters = function
+            .prototype
+            .parameters
+            .iter()
+            .map(|parameter| format!("{}: {}", interner.get(parameter.name), parameter.ty))
+            .collect::<Vec<_>>();
+        printer.node(
+            function.prototype.span,
+            format!(
+                "Function {}({}) :: {}",
+                interner.get(function.prototype.name),
+                parameters.join(", "),
+                function.prototype.return_type.0
+            ),
+        );
+        printer.indented(|printer| printer.expression(&function.body));
+    }
+
+    printer.output
+}
+
+/// Prints the type checked program as a tree, with one node on each line.
+/// Every expression is followed by its type.
+///
+/// # Arguments
+/// * `program` - The program to print.
+/// * `interner` - The `Interner` of the names in the program.
+/// * `source_map` - The `SourceMap` containing the file of the program.
+pub fn print_typed_program(
+    program: &TypedProgram,
+    interner: &Interner<String>,
+    source_map: &SourceMap,
+) -> String {
+    let mut printer = Printer::new(interner, source_map);
+    for extern_declaration in &program.extern_declarations {
+        printer.extern_declaration(
+            extern_declaration.span,
+            extern_declaration.name,
+            &extern_declaration.parameters,
+            extern_declaration.return_type.0,
+        );
+    }
+
+    for function in &program.functions {
+        printer.attributes(&function.attributes);
+        let parameters = function
+            .prototype
+            .parameters
+            .iter()
+            .map(|parameter| format!("{}: {}", interner.get(parameter.name), parameter.ty))
+            .collect::<Vec<_>>();
+        printer.node(
+            function.prototype.span,
+            format!(
+                "Function {}({}) :: {}",
+                interner.get(function.prototype.name),
+                parameters.join(", "),
+                function.prototype.return_type
+            ),
+        );
+        printer.indented(|printer| printer.typed_expression(&function.body));
+    }
+
+    printer.output
+}
+
+/// Struct that prints the nodes of a program, indenting the children of each node.
+struct Printer<'a> {
+    interner: &'a Interner<String>,
+    source_map: &'a SourceMap,
+    output: String,
+    depth: usize,
+}
+
+impl<'a> Printer<'a> {
+    fn new(interner: &'a Interner<String>, source_map: &'a SourceMap) -> Self {
+        Self {
+            interner,
+            source_map,
+            output: String::new(),
+            depth: 0,
+        }
+    }
+
+    /// Prints a node on its own line, followed by the location where it starts.
+    ///
+    /// # Arguments
+    /// * `span` - The `Span` of the node.
+    /// * `description` - The description of the node.
+    fn node(&mut self, span: Span, description: impl Display) {
+        let (line, column) = self.source_map.line_column(span.file_id, span.start);
+        let _ = writeln!(
+            self.output,
+            "{:indent$}{} @ {}:{}",
+            "",
+            description,
+            line,
+            column,
+            indent = self.depth * 2
+        );
+    }
+
+    /// Runs the function with the depth increased by one, so that
+    /// the nodes it prints are children of the last node.
+    ///
+    /// # Arguments
+    /// * `function` - The function that prints the children.
+    fn indented(&mut self, function: impl FnOnce(&mut Self)) {
+        self.depth += 1;
+        function(self);
+        self.depth -= 1;
+    }
+
+    /// Prints the signature of an extern declaration.
+    ///
+    /// # Arguments
+    /// * `span` - The `Span` of the declaration.
+    /// * `name` - The id of the name of the declared function.
+    /// * `parameters` - The types of the parameters.
+    /// * `return_type` - The return type.
+    fn extern_declaration(
+        &mut self,
+        span: Span,
+        name: usize,
+        parameters: &[(Type, Span)],
+        return_type: Type,
+    ) {
+        let parameters = parameters
+            .iter()
+            .map(|(ty, _)| ty.to_string())
+            .collect::<Vec<_>>();
+        let description = format!(
+            "Extern {}({}) :: {}",
+            self.interner.get(name),
+            parameters.join(", "),
+            return_type
+        );
+        self.node(span, description);
+    }
+
+    /// Prints the attributes of a function, such as `#[allow(unused_variables)]`.
+    ///
+    /// # Arguments
+    /// * `attributes` - The attributes to print.
+    fn attributes(&mut self, attributes: &[Attribute]) {
+        for attribute in attributes {
+            let arguments = attribute
+                .arguments
+                .iter()
+                .map(|(_, argument)| self.interner.get(*argument).as_str())
+                .collect::<Vec<_>>();
+            let description = format!(
+                "Attribute {}({})",
+                self.interner.get(attribute.name.1),
+                arguments.join(", ")
+            );
+            self.node(attribute.span, description);
+        }
+    }
+
+    /// Prints a parsed expression and its subexpressions.
+    ///
+    /// # Arguments
+    /// * `expression` - The expression to print.
+    fn expression(&mut self, (span, kind): &Expression) {
+        let span = *span;
+        match kind {
+            ExpressionKind::Int(value) => self.node(span, format!("Int {}", value)),
+            ExpressionKind::Float(value) => self.node(span, format!("Float {}", value)),
+            ExpressionKind::Boolean(value) => self.node(span, format!("Boolean {}", value)),
+            ExpressionKind::Char(value) => self.node(span, format!("Char {:?}", value)),
+            ExpressionKind::Identifier(identifier) => {
+                let name = self.interner.get(identifier.0);
+                self.node(span, format!("Identifier {}", name))
+            }
+            ExpressionKind::Unary(unary) => {
+                self.node(span, format!("Unary {}", unary.operation));
+                self.indented(|printer| printer.expression(&unary.expression));
+            }
+            ExpressionKind::Binary(binary) => {
+                self.node(span, format!("Binary {}", binary.operation));
+                self.indented(|printer| {
+                    printer.expression(&binary.left);
+                    printer.expression(&binary.right);
+                });
+            }
+            ExpressionKind::If(if_expression) => {
+                self.node(span, "If");
+                self.indented(|printer| {
+                    printer.expression(&if_expression.condition);
+                    printer.expression(&if_expression.then_branch);
+                    if let Some(else_branch) = &if_expression.else_branch {
+                        printer.expression(else_branch);
+                    }
+                });
+            }
+            ExpressionKind::Let(let_expression) => {
+                let name = self.interner.get((let_expression.name.1).0);
+                let description = match let_expression.given_type {
+                    Some(given_type) => format!("Let {}: {}", name, given_type),
+                    None => format!("Let {}", name),
+                };
+                self.node(span, description);
+                self.indented(|printer| printer.expression(&let_expression.expression));
+            }
+            ExpressionKind::Block(expressions) => {
+                self.node(span, "Block");
+                self.indented(|printer| {
+                    for expression in expressions {
+                        printer.expression(expression);
+                    }
+                });
+            }
+            ExpressionKind::Application(application) => {
+                let name = self.interner.get((application.function_name.1).0);
+                self.node(span, format!("Application {}", name));
+                self.indented(|printer| {
+                    for parameter in &application.parameters {
+                        printer.expression(parameter);
+                    }
+                });
+            }
+            ExpressionKind::While(while_expression) => {
+                self.node(span, "While");
+                self.indented(|printer| {
+                    printer.expression(&while_expression.condition);
+                    printer.expression(&while_expression.expression);
+                });
+            }
+            ExpressionKind::Return(expression) => {
+                self.node(span, "Return");
+                if let Some(expression) = expression {
+                    self.indented(|printer| printer.expression(expression));
+                }
+            }
+            ExpressionKind::Panic(message) => {
+                let message = self.interner.get(*message);
+                self.node(span, format!("Panic {:?}", message))
+            }
+            ExpressionKind::Assert(expression) => {
+                self.node(span, "Assert");
+                self.indented(|printer| printer.expression(expression));
+            }
+            ExpressionKind::AssertEq(assert_eq) => {
+                self.node(span, "AssertEq");
+                self.indented(|printer| {
+                    printer.expression(&assert_eq.left);
+                    printer.expression(&assert_eq.right);
+                });
+            }
+            ExpressionKind::Error => self.node(span, "Error"),
+        }
+    }
+
+    /// Prints a type checked expression and its subexpressions.
+    /// Each expression is followed by its type.
+    ///
+    /// # Arguments
+    /// * `expression` - The expression to print.
+    fn typed_expression(&mut self, (span, kind): &TypedExpression) {
+        let span = *span;
+        let ty = kind.get_type();
+        match kind {
+            TypedExpressionKind::Int(value) => self.typed_node(span, format!("Int {}", value), ty),
+            TypedExpressionKind::Float(value) => {
+                self.typed_node(span, format!("Float {}", value), ty)
+            }
+            TypedExpressionKind::Boolean(value) => {
+                self.typed_node(span, format!("Boolean {}", value), ty)
+            }
+            TypedExpressionKind::Char(value) => {
+                self.typed_node(span, format!("Char {:?}", value), ty)
+            }
+            TypedExpressionKind::Identifier(identifier) => {
+                let name = self.interner.get(identifier.id);
+                self.typed_node(span, format!("Identifier {}", name), ty)
+            }
+            TypedExpressionKind::Unary(unary) => {
+                self.typed_node(span, format!("Unary {}", unary.operation), ty);
+                self.indented(|printer| printer.typed_expression(&unary.expression));
+            }
+            TypedExpressionKind::Binary(binary) => {
+                self.typed_node(span, format!("Binary {}", binary.operation), ty);
+                self.indented(|printer| {
+                    printer.typed_expression(&binary.left);
+                    printer.typed_expression(&binary.right);
+                });
+            }
+            TypedExpressionKind::If(if_expression) => {
+                self.typed_node(span, "If", ty);
+                self.indented(|printer| {
+                    printer.typed_expression(&if_expression.condition);
+                    printer.typed_expression(&if_expression.then_branch);
+                    if let Some(else_branch) = &if_expression.else_branch {
+                        printer.typed_expression(else_branch);
+                    }
+                });
+            }
+            TypedExpressionKind::Let(let_expression) => {
+                let name = self.interner.get(let_expression.name.1.id);
+                let description = match let_expression.given_type {
+                    Some(given_type) => format!("Let {}: {}", name, given_type),
+                    None => format!("Let {}", name),
+                };
+                self.typed_node(span, description, ty);
+                self.indented(|printer| printer.typed_expression(&let_expression.expression));
+            }
+            TypedExpressionKind::Block(expressions) => {
+                self.typed_node(span, "Block", ty);
+                self.indented(|printer| {
+                    for expression in expressions {
+                        printer.typed_expression(expression);
+                    }
+                });
+            }
+            TypedExpressionKind::Application(application) => {
+                let name = self.interner.get(application.function_name.1);
+                self.typed_node(span, format!("Application {}", name), ty);
+                self.indented(|printer| {
+                    for parameter in &application.parameters {
+                        printer.typed_expression(parameter);
+                    }
+                });
+            }
+            TypedExpressionKind::While(while_expression) => {
+                self.typed_node(span, "While", ty);
+                self.indented(|printer| {
+                    printer.typed_expression(&while_expression.condition);
+                    printer.typed_expression(&while_expression.expression);
+                });
+            }
+            TypedExpressionKind::Return(expression) => {
+                self.typed_node(span, "Return", ty);
+                if let Some(expression) = expression {
+                    self.indented(|printer| printer.typed_expression(expression));
+                }
+            }
+            TypedExpressionKind::Panic(message) => {
+                let message = self.interner.get(*message);
+                self.typed_node(span, format!("Panic {:?}", message), ty)
+            }
+            TypedExpressionKind::Assert(expression) => {
+                self.typed_node(span, "Assert", ty);
+                self.indented(|printer| printer.typed_expression(expression));
+            }
+            TypedExpressionKind::AssertEq(assert_eq) => {
+                self.typed_node(span, "AssertEq", ty);
+                self.indented(|printer| {
+                    printer.typed_expression(&assert_eq.left);
+                    printer.typed_expression(&assert_eq.right);
+                });
+            }
+            TypedExpressionKind::Error => self.typed_node(span, "Error", ty),
+        }
+    }
+
+    /// Prints a node of a type checked expression, followed by its type.
+    ///
+    /// # Arguments
+    /// * `span` - The `Span` of the node.
+    /// * `description` - The description of the node.
+    /// * `ty` - The `Type` of the expression.
+    fn typed_node(&mut self, span: Span, description: impl Display, ty: Type) {
+        self.node(span, format!("{} :: {}", description, ty));
+    }
+}
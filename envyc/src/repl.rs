@@ -0,0 +1,624 @@
+use std::{collections::HashSet, mem};
+#[cfg(unix)]
+use std::{
+    fs::File,
+    io::{self, Read, Write},
+    os::unix::io::FromRawFd,
+    ptr,
+};
+
+use inkwell::context::Context;
+
+use crate::{
+    builtins::define_builtins,
+    codegen::code_generator::symbol_name,
+    environment::Environment,
+    error::{Error, FileId, SourceMap, Span},
+    filter_tokens,
+    function_table::FunctionTable,
+    generate_module, initialize_targets,
+    interner::Interner,
+    lex,
+    lexer::token::{Token, TokenKind},
+    lint::linter::collect_calls,
+    optimization::optimize,
+    parser::{
+        ast::{Function, Prototype},
+        expression::ExpressionKind,
+        typed_ast::{TypedFunction, TypedProgram},
+        Parser,
+    },
+    semantic_analyzer::{type_check::TypeCheck, types::Type},
+    session::Options,
+    EmitKind,
+};
+
+/// The name of the function that each expression entered in the REPL is
+/// compiled into. An identifier in Envy can not contain a `.`, so the name
+/// never conflicts with a function defined by the user.
+const EXPRESSION_FUNCTION: &str = "repl.expression";
+
+/// Enum that details the result of evaluating a single input of the REPL.
+#[derive(Debug)]
+pub enum Evaluation {
+    /// The input defined functions or extern declarations.
+    /// The signature of each definition is given, such as `add(Int, Int) :: Int`.
+    Definitions(Vec<String>),
+    /// The input was an expression. The value is `None` when the type of the
+    /// expression is `Void`, since there is no value to show.
+    Value { value: Option<String>, ty: Type },
+    /// The input was an expression that stopped the program before it finished,
+    /// by panicking or by calling `exit`, with the given exit code. A program
+    /// killed by a signal stops with 128 plus the number of the signal.
+    Exit(i32),
+}
+
+/// Struct that evaluates the inputs of an interactive session one at a time.
+/// The definitions entered so far are kept, along with the environment and
+/// the function table they were type checked with, so that later inputs
+/// can use them. Each expression is compiled along with every definition and
+/// run in memory with LLVM's JIT, so no linker is needed.
+pub struct Repl {
+    source_map: SourceMap,
+    interner: Interner<String>,
+    env: Environment<Type>,
+    function_table: FunctionTable,
+    /// The functions and extern declarations defined so far.
+    definitions: TypedProgram,
+    options: Options,
+    /// The number of inputs entered so far, used to name the input in errors.
+    inputs: usize,
+}
+
+impl Repl {
+    /// Creates a `Repl` without any definitions.
+    ///
+    /// # Arguments
+    /// * `options` - The options the inputs are compiled with.
+    pub fn new(options: Options) -> Self {
+        let mut interner = Interner::default();
+        let mut env = Environment::default();
+        let mut function_table = FunctionTable::default();
+        define_builtins(&mut env, &mut function_table, &mut interner);
+        Self {
+            source_map: SourceMap::default(),
+            interner,
+            env,
+            function_table,
+            definitions: TypedProgram::new(vec![], vec![]),
+            options,
+            inputs: 0,
+        }
+    }
+
+    /// Gets the `SourceMap` of the inputs, used to report the errors of an input.
+    pub fn source_map(&self) -> &SourceMap {
+        &self.source_map
+    }
+
+    /// Determines whether the input is complete, which is when every `{` has
+    /// been closed. Input that can not be lexed is complete, so that its
+    /// errors are reported instead of waiting for more input.
+    ///
+    /// # Arguments
+    /// * `input` - The input entered so far.
+    pub fn is_complete(input: &str) -> bool {
+        let file_id = SourceMap::default().add("", "");
+        let tokens = match lex(file_id, input.as_bytes(), &mut Interner::default()) {
+            Ok(tokens) => tokens,
+            Err(_) => return true,
+        };
+
+        let depth = tokens.iter().fold(0, |depth, token| match token.1 {
+            TokenKind::LeftCurlyBrace => depth + 1,
+            TokenKind::RightCurlyBrace => depth - 1,
+            _ => depth,
+        });
+
+        depth <= 0
+    }
+
+    /// Evaluates a single input. Inputs that start with `define`, `extern`
+    /// or an attribute are added to the definitions, and any other input is
+    /// evaluated as the expressions of a block. The definitions are left
+    /// unchanged when the input has errors.
+    ///
+    /// # Arguments
+    /// * `input` - The input to evaluate.
+    pub fn evaluate(&mut self, input: &str) -> Result<Evaluation, Vec<Error>> {
+        let file_id = self.add_input(input);
+        let tokens = self.lex(file_id)?;
+        let first_kind = tokens
+            .iter()
+            .map(|token| token.1)
+            .find(|kind| !matches!(kind, TokenKind::Whitespace(_)));
+        let is_definition = matches!(
+            first_kind,
+            Some(TokenKind::Define) | Some(TokenKind::Extern) | Some(TokenKind::Hash)
+        );
+
+        if is_definition {
+            self.define(tokens).map(Evaluation::Definitions)
+        } else {
+            let function = self.check_expression(file_id, tokens)?;
+            self.run(file_id, function)
+        }
+    }
+
+    /// Adds the definitions of a file, such as one loaded with `:load`, and
+    /// returns their signatures. The definitions are left unchanged when the
+    /// file has errors.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the file.
+    /// * `source` - The source of the file.
+    pub fn load(
+        &mut self,
+        name: impl Into<String>,
+        source: impl Into<String>,
+    ) -> Result<Vec<String>, Vec<Error>> {
+        let file_id = self.source_map.add(name, source);
+        let tokens = self.lex(file_id)?;
+        self.define(tokens)
+    }
+
+    /// Type checks the input as an expression and returns its type
+    /// without running it.
+    ///
+    /// # Arguments
+    /// * `input` - The expression.
+    pub fn type_of(&mut self, input: &str) -> Result<Type, Vec<Error>> {
+        let file_id = self.add_input(input);
+        let tokens = self.lex(file_id)?;
+        let function = self.check_expression(file_id, tokens)?;
+        Ok(function.body.1.get_type())
+    }
+
+    /// Gets the optimized LLVM IR of a function that was defined, or `None`
+    /// if no function with the name has been defined.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the function.
+    pub fn ir(&mut self, name: &str) -> Result<Option<String>, Vec<Error>> {
+        let file_id = match self
+            .definitions
+            .functions
+            .iter()
+            .find(|function| self.interner.get(function.prototype.name) == name)
+        {
+            Some(function) => function.prototype.span.file_id,
+            None => return Ok(None),
+        };
+
+        let context = Context::create();
+        let config = self.options.config(&[EmitKind::LlvmIr]);
+        let module = generate_module(
+            &context,
+            &self.definitions,
+            file_id,
+            &mut self.interner,
+            &self.source_map,
+            &config,
+            None,
+        )?;
+        optimize(&module, config.optimization_level, config.passes, None);
+        Ok(module
+            .get_function(&symbol_name(name))
+            .map(|function| function.print_to_string().to_string()))
+    }
+
+    /// Adds an input to the `SourceMap`, naming it after its position in the session.
+    ///
+    /// # Arguments
+    /// * `input` - The input.
+    fn add_input(&mut self, input: &str) -> FileId {
+        self.inputs += 1;
+        self.source_map
+            .add(format!("<repl:{}>", self.inputs), input.to_string())
+    }
+
+    /// Lexes the source of a file.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the file.
+    fn lex(&mut self, file_id: FileId) -> Result<Vec<Token>, Vec<Error>> {
+        lex(
+            file_id,
+            self.source_map.source(file_id).as_bytes(),
+            &mut self.interner,
+        )
+    }
+
+    /// Parses and type checks definitions, and adds them to the definitions of
+    /// the session. A function or an extern declaration replaces any previous
+    /// definition with the same name, but its signature can only change if no
+    /// other definition calls it, since the callers were checked against the
+    /// previous signature. The environment and the function table are restored
+    /// when the definitions have errors.
+    ///
+    /// # Arguments
+    /// * `tokens` - The tokens of the definitions.
+    fn define(&mut self, tokens: Vec<Token>) -> Result<Vec<String>, Vec<Error>> {
+        let program = Parser::new(filter_tokens(tokens)).parse()?;
+        let env = self.env.clone();
+        let function_table = self.function_table.clone();
+        let program = match program
+            .check(&mut self.env, &mut self.function_table, &self.interner)
+            .and_then(|program| {
+                self.check_signature_changes(&program)?;
+                Ok(program)
+            }) {
+            Ok(program) => program,
+            Err(errors) => {
+                self.env = env;
+                self.function_table = function_table;
+                return Err(errors);
+            }
+        };
+
+        let mut signatures = vec![];
+        for extern_declaration in program.extern_declarations {
+            let parameter_types = extern_declaration
+                .parameters
+                .iter()
+                .map(|parameter| parameter.0.to_string())
+                .collect::<Vec<_>>();
+            signatures.push(format!(
+                "extern {}({}) :: {}",
+                self.interner.get(extern_declaration.name),
+                parameter_types.join(", "),
+                extern_declaration.return_type.0
+            ));
+            self.remove_definition(extern_declaration.name);
+            self.definitions
+                .extern_declarations
+                .push(extern_declaration);
+        }
+
+        for function in program.functions {
+            signatures.push(function.prototype.signature(&self.interner));
+            self.remove_definition(function.prototype.name);
+            self.definitions.functions.push(function);
+        }
+
+        Ok(signatures)
+    }
+
+    /// Checks that the definitions do not change the signature of a function
+    /// or an extern declaration that is called by one of the definitions of
+    /// the session, unless the caller is defined again along with it.
+    ///
+    /// # Arguments
+    /// * `program` - The type checked definitions.
+    fn check_signature_changes(&self, program: &TypedProgram) -> Result<(), Vec<Error>> {
+        let new_definitions = program
+            .functions
+            .iter()
+            .map(|function| (function.prototype.name, function.prototype.span))
+            .chain(
+                program
+                    .extern_declarations
+                    .iter()
+                    .map(|extern_declaration| (extern_declaration.name, extern_declaration.span)),
+            )
+            .collect::<Vec<_>>();
+        let mut errors = vec![];
+        for &(name, span) in &new_definitions {
+            let previous_signature = match signature(&self.definitions, name) {
+                Some(previous_signature)
+                    if Some(&previous_signature) != signature(program, name).as_ref() =>
+                {
+                    previous_signature
+                }
+                _ => continue,
+            };
+
+            let caller = self.definitions.functions.iter().find(|function| {
+                let mut called_functions = HashSet::new();
+                collect_calls(&function.body, &mut called_functions);
+                called_functions.contains(&name)
+                    && new_definitions
+                        .iter()
+                        .all(|&(new_name, _)| new_name != function.prototype.name)
+            });
+
+            if let Some(caller) = caller {
+                errors.push(Error::ChangedSignature {
+                    span,
+                    name: self.interner.get(name).clone(),
+                    previous_signature: format_signature(name, &previous_signature, &self.interner),
+                    caller_span: caller.prototype.span,
+                    caller: self.interner.get(caller.prototype.name).clone(),
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Removes the function or the extern declaration with the given name, if there is one.
+    ///
+    /// # Arguments
+    /// * `name` - The id of the name of the definition.
+    fn remove_definition(&mut self, name: usize) {
+        self.definitions
+            .functions
+            .retain(|function| function.prototype.name != name);
+        self.definitions
+            .extern_declarations
+            .retain(|extern_declaration| extern_declaration.name != name);
+    }
+
+    /// Parses and type checks the expressions of an input as the body of a
+    /// function that takes no parameters. The function returns the value of
+    /// the last expression, or nothing if the expressions never finish.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the input.
+    /// * `tokens` - The tokens of the input.
+    fn check_expression(
+        &mut self,
+        file_id: FileId,
+        tokens: Vec<Token>,
+    ) -> Result<TypedFunction, Vec<Error>> {
+        let span = Span::new(file_id, 0, self.source_map.source(file_id).len());
+        let expressions =
+            Parser::new(filter_tokens(tokens)).parse_expressions(Span::new(file_id, 0, 0))?;
+        let name = self.interner.insert(EXPRESSION_FUNCTION.to_string());
+        // The return type is not known until the body is checked, and
+        // `Type::Error` unifies with the type of any body.
+        let function = Function::new(
+            vec![],
+            Prototype {
+                span,
+                name,
+                parameters: vec![],
+                return_type: (Type::Error, span),
+            },
+            (span, ExpressionKind::Block(expressions)),
+        );
+
+        self.env.new_scope();
+        self.env.define(name, Type::Error);
+        let function = function.check(&mut self.env, &mut self.function_table, &self.interner);
+        self.env.remove_top_scope();
+
+        let mut function = function?;
+        function.prototype.return_type = match function.body.1.get_type() {
+            Type::Never | Type::Error => Type::Void,
+            ty => ty,
+        };
+
+        Ok(function)
+    }
+
+    /// Compiles the function of an expression along with every definition
+    /// and runs it with LLVM's JIT, returning its value as it is shown
+    /// to the user. A panic or a call to `exit` ends the process that runs
+    /// the generated code, so on Unix the function is run in a child process
+    /// and the session continues when it stops.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the input.
+    /// * `function` - The function of the expression.
+    fn run(&mut self, file_id: FileId, function: TypedFunction) -> Result<Evaluation, Vec<Error>> {
+        let ty = function.prototype.return_type;
+        self.definitions.functions.push(function);
+        let context = Context::create();
+        let config = self.options.config(&[EmitKind::Object]);
+        let module = generate_module(
+            &context,
+            &self.definitions,
+            file_id,
+            &mut self.interner,
+            &self.source_map,
+            &config,
+            None,
+        );
+        self.definitions.functions.pop();
+
+        let module = module?;
+        initialize_targets();
+        optimize(&module, config.optimization_level, config.passes, None);
+        let execution_engine = module
+            .create_jit_execution_engine(config.optimization_level.llvm_level())
+            .map_err(|message| {
+                vec![Error::JitFailure {
+                    message: message.to_string(),
+                }]
+            })?;
+
+        let address = execution_engine
+            .get_function_address(EXPRESSION_FUNCTION)
+            .map_err(|error| {
+                vec![Error::JitFailure {
+                    message: error.to_string(),
+                }]
+            })?;
+
+        // The function was generated with the return type that it is called with,
+        // and it takes no parameters, so it is safe to call.
+        run_expression(|| unsafe { call_expression(address, ty) }, ty)
+    }
+}
+
+/// Runs the function of an expression in a child process and waits for it.
+/// The value is sent back to the session through a pipe. When the child
+/// stops without sending it, such as when it panics, its exit code is returned.
+///
+/// # Arguments
+/// * `call` - Calls the function and returns its value as it is shown to the user.
+/// * `ty` - The type of the expression.
+#[cfg(unix)]
+fn run_expression(
+    call: impl FnOnce() -> Option<String>,
+    ty: Type,
+) -> Result<Evaluation, Vec<Error>> {
+    let mut descriptors = [0; 2];
+    if unsafe { libc::pipe(descriptors.as_mut_ptr()) } != 0 {
+        return Err(vec![Error::JitFailure {
+            message: io::Error::last_os_error().to_string(),
+        }]);
+    }
+
+    // The pipe is only used through these files, which close it when they are dropped.
+    let (mut reader, mut writer) = unsafe {
+        (
+            File::from_raw_fd(descriptors[0]),
+            File::from_raw_fd(descriptors[1]),
+        )
+    };
+
+    // Anything left in the buffer would otherwise be printed by both processes.
+    let _ = io::stdout().flush();
+    match unsafe { libc::fork() } {
+        -1 => Err(vec![Error::JitFailure {
+            message: io::Error::last_os_error().to_string(),
+        }]),
+        0 => {
+            drop(reader);
+            let message = match call() {
+                Some(value) => format!("v{}", value),
+                None => String::from("n"),
+            };
+
+            // The output of the C functions is buffered separately from Rust's.
+            unsafe { libc::fflush(ptr::null_mut()) };
+            let _ = io::stdout().flush();
+            let _ = writer.write_all(message.as_bytes());
+            unsafe { libc::_exit(0) }
+        }
+        child => {
+            drop(writer);
+            let mut message = String::new();
+            let _ = reader.read_to_string(&mut message);
+            let mut status = 0;
+            if unsafe { libc::waitpid(child, &mut status, 0) } == -1 {
+                return Err(vec![Error::JitFailure {
+                    message: io::Error::last_os_error().to_string(),
+                }]);
+            }
+
+            if let Some(value) = message.strip_prefix('v') {
+                Ok(Evaluation::Value {
+                    value: Some(value.to_string()),
+                    ty,
+                })
+            } else if message == "n" {
+                Ok(Evaluation::Value { value: None, ty })
+            } else if libc::WIFSIGNALED(status) {
+                Ok(Evaluation::Exit(128 + libc::WTERMSIG(status)))
+            } else {
+                Ok(Evaluation::Exit(libc::WEXITSTATUS(status)))
+            }
+        }
+    }
+}
+
+/// Runs the function of an expression in the process of the session. A panic
+/// or a call to `exit` ends the session, since processes can not be forked.
+///
+/// # Arguments
+/// * `call` - Calls the function and returns its value as it is shown to the user.
+/// * `ty` - The type of the expression.
+#[cfg(not(unix))]
+fn run_expression(
+    call: impl FnOnce() -> Option<String>,
+    ty: Type,
+) -> Result<Evaluation, Vec<Error>> {
+    Ok(Evaluation::Value { value: call(), ty })
+}
+
+/// Calls the function of an expression that was compiled by the JIT and
+/// returns its value as it is shown to the user. The caller must ensure
+/// that the function takes no parameters and returns a value of the type.
+///
+/// # Arguments
+/// * `address` - The address of the function.
+/// * `ty` - The type of the expression, which is the return type of the function.
+unsafe fn call_expression(address: usize, ty: Type) -> Option<String> {
+    match ty {
+        Type::Int => Some(call::<i64>(address).to_string()),
+        Type::Float => Some(format!("{:?}", call::<f64>(address))),
+        Type::Boolean => Some((call::<u8>(address) & 1 == 1).to_string()),
+        Type::Char => Some(format!("{:?}", call::<u8>(address) as char)),
+        Type::Void | Type::Never | Type::Error => {
+            call::<()>(address);
+            None
+        }
+    }
+}
+
+/// Calls a function that takes no parameters at the given address.
+/// The caller must ensure that the function returns a value of type `T`.
+///
+/// # Arguments
+/// * `address` - The address of the function.
+unsafe fn call<T>(address: usize) -> T {
+    let function = mem::transmute::<usize, unsafe extern "C" fn() -> T>(address);
+    function()
+}
+
+/// Gets the types of the parameters and the return type of the function or
+/// the extern declaration with the given name, if the program defines it.
+///
+/// # Arguments
+/// * `program` - The program.
+/// * `name` - The id of the name of the definition.
+fn signature(program: &TypedProgram, name: usize) -> Option<(Vec<Type>, Type)> {
+    let function = program
+        .functions
+        .iter()
+        .find(|function| function.prototype.name == name)
+        .map(|function| {
+            let parameter_types = function
+                .prototype
+                .parameters
+                .iter()
+                .map(|parameter| parameter.ty)
+                .collect();
+            (parameter_types, function.prototype.return_type)
+        });
+
+    function.or_else(|| {
+        program
+            .extern_declarations
+            .iter()
+            .find(|extern_declaration| extern_declaration.name == name)
+            .map(|extern_declaration| {
+                let parameter_types = extern_declaration
+                    .parameters
+                    .iter()
+                    .map(|parameter| parameter.0)
+                    .collect();
+                (parameter_types, extern_declaration.return_type.0)
+            })
+    })
+}
+
+/// Formats a signature like `TypedPrototype::signature`, such as `add(Int, Int) :: Int`.
+///
+/// # Arguments
+/// * `name` - The id of the name of the definition.
+/// * `signature` - The types of the parameters and the return type.
+/// * `interner` - The `Interner` of the name.
+fn format_signature(
+    name: usize,
+    (parameter_types, return_type): &(Vec<Type>, Type),
+    interner: &Interner<String>,
+) -> String {
+    let parameter_types = parameter_types
+        .iter()
+        .map(Type::to_string)
+        .collect::<Vec<_>>();
+    format!(
+        "{}({}) :: {}",
+        interner.get(name),
+        parameter_types.join(", "),
+        return_type
+    )
+}
@@ -1,2 +1,3 @@
+pub mod suggestions;
 pub mod type_check;
 pub mod types;
@@ -0,0 +1,127 @@
+use crate::{
+    environment::Environment, error::Suggestion, function_table::FunctionTable, interner::Interner,
+};
+
+use super::types::Type;
+
+/// Finds the variable in scope whose name is the closest to the given name.
+/// Functions are not considered, since they can not be used as variables.
+///
+/// # Arguments
+/// * `name` - The id of the name that could not be found.
+/// * `env` - The `Environment` of the names in scope.
+/// * `function_table` - The `FunctionTable` of the defined functions.
+/// * `interner` - The `Interner` used to get the names from their ids.
+pub fn suggest_variable(
+    name: usize,
+    env: &Environment<Type>,
+    function_table: &FunctionTable,
+    interner: &Interner<String>,
+) -> Option<Suggestion> {
+    let candidates = env
+        .names()
+        .filter(|&id| function_table.get_parameter_types(id).is_none());
+
+    find_closest_name(name, candidates, interner).map(|id| Suggestion {
+        name: interner.get(id).clone(),
+        signature: None,
+    })
+}
+
+/// Finds the defined function whose name is the closest to the given name,
+/// along with the signature of that function.
+///
+/// # Arguments
+/// * `name` - The id of the name that could not be found.
+/// * `env` - The `Environment` that stores the return types of the functions.
+/// * `function_table` - The `FunctionTable` of the defined functions.
+/// * `interner` - The `Interner` used to get the names from their ids.
+pub fn suggest_function(
+    name: usize,
+    env: &Environment<Type>,
+    function_table: &FunctionTable,
+    interner: &Interner<String>,
+) -> Option<Suggestion> {
+    let id = find_closest_name(name, function_table.function_names(), interner)?;
+    let parameter_types = function_table
+        .get_parameter_types(id)?
+        .iter()
+        .map(|ty| ty.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let return_type = env.get(id).unwrap_or(Type::Void);
+    Some(Suggestion {
+        name: interner.get(id).clone(),
+        signature: Some(format!(
+            "{}({}) :: {}",
+            interner.get(id),
+            parameter_types,
+            return_type
+        )),
+    })
+}
+
+/// Finds the candidate with the smallest edit distance to the given name.
+/// Candidates that are too far away from the name to be a likely typo are
+/// ignored. Ties are broken alphabetically so that the result is stable.
+///
+/// # Arguments
+/// * `name` - The id of the name that could not be found.
+/// * `candidates` - The ids of the names to compare against.
+/// * `interner` - The `Interner` used to get the names from their ids.
+fn find_closest_name(
+    name: usize,
+    candidates: impl Iterator<Item = usize>,
+    interner: &Interner<String>,
+) -> Option<usize> {
+    let target = interner.get(name);
+    let maximum_distance = std::cmp::max(target.chars().count() / 3, 1);
+    candidates
+        .filter(|&id| id != name)
+        .map(|id| {
+            (
+                edit_distance(target, interner.get(id)),
+                interner.get(id),
+                id,
+            )
+        })
+        .filter(|(distance, _, _)| *distance <= maximum_distance)
+        .min()
+        .map(|(_, _, id)| id)
+}
+
+/// Computes the edit distance between the two strings, which is the number
+/// of insertions, deletions, substitutions and swaps of adjacent characters
+/// needed to turn one string into the other.
+///
+/// # Arguments
+/// * `first` - The first string.
+/// * `second` - The second string.
+fn edit_distance(first: &str, second: &str) -> usize {
+    let first = first.chars().collect::<Vec<_>>();
+    let second = second.chars().collect::<Vec<_>>();
+    let mut distances = vec![vec![0; second.len() + 1]; first.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=first.len() {
+        for j in 1..=second.len() {
+            let substitution_cost = if first[i - 1] == second[j - 1] { 0 } else { 1 };
+            let mut distance = (distances[i - 1][j - 1] + substitution_cost)
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && first[i - 1] == second[j - 2] && first[i - 2] == second[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[first.len()][second.len()]
+}
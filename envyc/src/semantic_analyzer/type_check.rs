@@ -2,25 +2,29 @@ use crate::{
     environment::Environment,
     error::{Error, Span},
     function_table::FunctionTable,
+    interner::Interner,
     parser::{
         ast::{ExternDeclaration, Function, Parameter, Program},
         expression::{
-            Application, Binary, BinaryOperation, Expression, ExpressionKind, Identifier, If, Let,
-            Unary, UnaryOperation, While,
+            Application, AssertEq, Binary, BinaryOperation, Expression, ExpressionKind, Identifier,
+            If, Let, Unary, UnaryOperation, While,
         },
         typed_ast::{
             TypedExternDeclaration, TypedFunction, TypedParameter, TypedProgram, TypedPrototype,
         },
         typed_expression::{
-            TypedApplication, TypedBinary, TypedExpression, TypedExpressionKind, TypedIdentifier,
-            TypedIf, TypedLet, TypedUnary, TypedWhile,
+            TypedApplication, TypedAssertEq, TypedBinary, TypedExpression, TypedExpressionKind,
+            TypedIdentifier, TypedIf, TypedLet, TypedUnary, TypedWhile,
         },
     },
 };
 
-use super::types::Type;
+use super::{
+    suggestions::{suggest_function, suggest_variable},
+    types::Type,
+};
 
-pub trait TypeCheck<'a> {
+pub trait TypeCheck {
     type Output;
     type Error;
 
@@ -28,47 +32,60 @@ pub trait TypeCheck<'a> {
         self,
         env: &mut Environment<Type>,
         function_table: &mut FunctionTable,
+        interner: &Interner<String>,
     ) -> Result<Self::Output, Self::Error>;
 }
 
-pub trait TypeCheckSpan<'a> {
+/// Trait for the parts of a program that always produce an output.
+/// Any errors found are added to `errors` and the parts that failed to
+/// type check are given `Type::Error` so that checking can continue.
+pub trait TypeCheckSpan {
     type Output;
-    type Error;
 
     fn check_span(
         self,
-        span: Span<'a>,
+        span: Span,
         env: &mut Environment<Type>,
         function_table: &mut FunctionTable,
-    ) -> Result<Self::Output, Self::Error>;
+        interner: &Interner<String>,
+        errors: &mut Vec<Error>,
+    ) -> Self::Output;
 }
 
-pub trait TypeCheckFunction<'a> {
+/// Trait for the parts of a function that always produce an output.
+/// Any errors found are added to `errors` and the parts that failed to
+/// type check are given `Type::Error` so that checking can continue.
+pub trait TypeCheckFunction {
     type Output;
-    type Error;
 
     fn check(
         self,
         env: &mut Environment<Type>,
         function_table: &mut FunctionTable,
+        interner: &Interner<String>,
         current_function: usize,
-    ) -> Result<Self::Output, Self::Error>;
+        errors: &mut Vec<Error>,
+    ) -> Self::Output;
 }
 
-pub trait TypeCheckSpanFunction<'a> {
+/// Trait for the parts of a function that always produce an output.
+/// Any errors found are added to `errors` and the parts that failed to
+/// type check are given `Type::Error` so that checking can continue.
+pub trait TypeCheckSpanFunction {
     type Output;
-    type Error;
 
     fn check_span(
         self,
-        span: Span<'a>,
+        span: Span,
         env: &mut Environment<Type>,
         function_table: &mut FunctionTable,
+        interner: &Interner<String>,
         current_function: usize,
-    ) -> Result<Self::Output, Self::Error>;
+        errors: &mut Vec<Error>,
+    ) -> Self::Output;
 }
 
-impl<'a, T: TypeCheck<'a>> TypeCheck<'a> for Vec<T> {
+impl<T: TypeCheck> TypeCheck for Vec<T> {
     type Output = Vec<T::Output>;
     type Error = Vec<T::Error>;
 
@@ -76,11 +93,12 @@ impl<'a, T: TypeCheck<'a>> TypeCheck<'a> for Vec<T> {
         self,
         env: &mut Environment<Type>,
         function_table: &mut FunctionTable,
+        interner: &Interner<String>,
     ) -> Result<Self::Output, Self::Error> {
         let mut results = vec![];
         let mut errors = vec![];
         for value in self {
-            match value.check(env, function_table) {
+            match value.check(env, function_table, interner) {
                 Ok(result) => results.push(result),
                 Err(error) => errors.push(error),
             }
@@ -94,43 +112,46 @@ impl<'a, T: TypeCheck<'a>> TypeCheck<'a> for Vec<T> {
     }
 }
 
-impl<'a, T: TypeCheckFunction<'a>> TypeCheckFunction<'a> for Vec<T> {
+impl<T: TypeCheckFunction> TypeCheckFunction for Vec<T> {
     type Output = Vec<T::Output>;
-    type Error = Vec<T::Error>;
 
     fn check(
         self,
         env: &mut Environment<Type>,
         function_table: &mut FunctionTable,
+        interner: &Interner<String>,
         current_function: usize,
-    ) -> Result<Self::Output, Self::Error> {
-        let mut results = vec![];
-        let mut errors = vec![];
-        for value in self {
-            match value.check(env, function_table, current_function) {
-                Ok(result) => results.push(result),
-                Err(error) => errors.push(error),
-            }
-        }
-
-        if !errors.is_empty() {
-            Err(errors)
-        } else {
-            Ok(results)
-        }
+        errors: &mut Vec<Error>,
+    ) -> Self::Output {
+        self.into_iter()
+            .map(|value| value.check(env, function_table, interner, current_function, errors))
+            .collect()
     }
 }
 
-impl<'a> TypeCheck<'a> for Program<'a> {
-    type Output = TypedProgram<'a>;
-    type Error = Vec<Error<'a>>;
+impl TypeCheck for Program {
+    type Output = TypedProgram;
+    type Error = Vec<Error>;
 
     fn check(
         self,
         env: &mut Environment<Type>,
         function_table: &mut FunctionTable,
+        interner: &Interner<String>,
     ) -> Result<Self::Output, Self::Error> {
-        let extern_declarations = self.extern_declarations.check(env, function_table)?;
+        let mut errors = vec![];
+        let extern_declarations =
+            match self
+                .extern_declarations
+                .check(env, function_table, interner)
+            {
+                Ok(extern_declarations) => extern_declarations,
+                Err(extern_errors) => {
+                    errors.extend(extern_errors.into_iter().flatten());
+                    vec![]
+                }
+            };
+
         for function in &self.functions {
             let function_name = function.prototype.name;
             let function_return_type = function.prototype.return_type.0;
@@ -144,209 +165,302 @@ impl<'a> TypeCheck<'a> for Program<'a> {
             function_table.add_function_definition(function_name, parameter_types);
         }
 
-        Ok(TypedProgram {
-            extern_declarations,
-            functions: self.functions.check(env, function_table)?,
-        })
+        let functions = match self.functions.check(env, function_table, interner) {
+            Ok(functions) => functions,
+            Err(function_errors) => {
+                errors.extend(function_errors.into_iter().flatten());
+                vec![]
+            }
+        };
+
+        if !errors.is_empty() {
+            Err(errors)
+        } else {
+            Ok(TypedProgram {
+                extern_declarations,
+                functions,
+            })
+        }
     }
 }
 
-impl<'a> TypeCheck<'a> for ExternDeclaration<'a> {
-    type Output = TypedExternDeclaration<'a>;
-    type Error = Error<'a>;
+impl TypeCheck for ExternDeclaration {
+    type Output = TypedExternDeclaration;
+    type Error = Vec<Error>;
 
     fn check(
         self,
         env: &mut Environment<Type>,
         function_table: &mut FunctionTable,
+        _: &Interner<String>,
     ) -> Result<Self::Output, Self::Error> {
-        let mut parameters = vec![];
-        for parameter in self.parameters {
+        let mut errors = vec![];
+        for parameter in &self.parameters {
             if parameter.0 == Type::Void {
-                return Err(Error::IllegalType(parameter.1));
-            } else {
-                parameters.push((parameter.0, parameter.1));
+                errors.push(Error::IllegalType(parameter.1));
             }
         }
 
+        // The declaration is defined even if it has errors, so that
+        // calls to it do not report that the function is unknown.
         env.define(self.name, self.return_type.0);
         function_table.add_function_definition(
             self.name,
-            parameters.iter().map(|parameter| parameter.0).collect(),
+            self.parameters
+                .iter()
+                .map(|parameter| parameter.0)
+                .collect(),
         );
 
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
         Ok(TypedExternDeclaration {
             span: self.span,
             name: self.name,
-            parameters,
+            parameters: self.parameters,
             return_type: self.return_type,
         })
     }
 }
 
-impl<'a> TypeCheck<'a> for Function<'a> {
-    type Output = TypedFunction<'a>;
-    type Error = Error<'a>;
+impl TypeCheck for Function {
+    type Output = TypedFunction;
+    type Error = Vec<Error>;
 
     fn check(
         self,
         env: &mut Environment<Type>,
         function_table: &mut FunctionTable,
+        interner: &Interner<String>,
     ) -> Result<Self::Output, Self::Error> {
+        let mut errors = vec![];
         env.new_scope();
         let mut typed_params = vec![];
         for parameter in self.prototype.parameters {
             if parameter.ty == Type::Void {
-                return Err(Error::IllegalType(parameter.span));
+                errors.push(Error::IllegalType(parameter.span));
+                env.define(parameter.name, Type::Error);
             } else {
                 env.define(parameter.name, parameter.ty);
-                typed_params.push(TypedParameter::new(
-                    parameter.span,
-                    parameter.ty,
-                    parameter.name,
-                ));
             }
+
+            typed_params.push(TypedParameter::new(
+                parameter.span,
+                parameter.ty,
+                parameter.name,
+            ));
         }
 
-        let typed_body = self.body.check(env, function_table, self.prototype.name)?;
+        let typed_body = self.body.check(
+            env,
+            function_table,
+            interner,
+            self.prototype.name,
+            &mut errors,
+        );
         let return_type = get_type(&typed_body.1);
-        if self.prototype.return_type.0 != return_type {
-            return Err(Error::TypeMismatch {
+        if return_type != Type::Never && return_type.unify(self.prototype.return_type.0).is_none() {
+            errors.push(Error::TypeMismatch {
                 span: typed_body.0,
                 expected_type: self.prototype.return_type.0,
                 actual_type: return_type,
             });
         }
 
-        let typed_function = TypedFunction::new(
+        env.remove_top_scope();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(TypedFunction::new(
+            self.attributes,
             TypedPrototype::new(
                 self.prototype.span,
                 self.prototype.name,
                 typed_params,
-                return_type,
+                self.prototype.return_type.0,
             ),
             typed_body,
-        );
-        env.remove_top_scope();
-        Ok(typed_function)
+        ))
     }
 }
 
-impl<'a> TypeCheck<'a> for Parameter<'a> {
-    type Output = TypedParameter<'a>;
-    type Error = Error<'a>;
+impl TypeCheck for Parameter {
+    type Output = TypedParameter;
+    type Error = Error;
 
     fn check(
         self,
         _: &mut Environment<Type>,
         _: &mut FunctionTable,
+        _: &Interner<String>,
     ) -> Result<Self::Output, Self::Error> {
         Ok(TypedParameter::new(self.span, self.ty, self.name))
     }
 }
 
-impl<'a> TypeCheckFunction<'a> for Expression<'a> {
-    type Output = TypedExpression<'a>;
-    type Error = Error<'a>;
+impl TypeCheckFunction for Expression {
+    type Output = TypedExpression;
 
     fn check(
         self,
         env: &mut Environment<Type>,
         function_table: &mut FunctionTable,
+        interner: &Interner<String>,
         current_function: usize,
-    ) -> Result<Self::Output, Self::Error> {
+        errors: &mut Vec<Error>,
+    ) -> Self::Output {
         match self.1 {
-            ExpressionKind::Int(value) => Ok((self.0, TypedExpressionKind::Int(value))),
-            ExpressionKind::Float(value) => Ok((self.0, TypedExpressionKind::Float(value))),
-            ExpressionKind::Boolean(value) => Ok((self.0, TypedExpressionKind::Boolean(value))),
-            ExpressionKind::Char(value) => Ok((self.0, TypedExpressionKind::Char(value))),
-            ExpressionKind::Identifier(inner) => inner.check_span(self.0, env, function_table),
-            ExpressionKind::Unary(inner) => {
-                inner.check_span(self.0, env, function_table, current_function)
-            }
-            ExpressionKind::Binary(inner) => {
-                inner.check_span(self.0, env, function_table, current_function)
-            }
-            ExpressionKind::If(inner) => {
-                inner.check_span(self.0, env, function_table, current_function)
-            }
-            ExpressionKind::Let(inner) => {
-                inner.check_span(self.0, env, function_table, current_function)
+            ExpressionKind::Int(value) => (self.0, TypedExpressionKind::Int(value)),
+            ExpressionKind::Float(value) => (self.0, TypedExpressionKind::Float(value)),
+            ExpressionKind::Boolean(value) => (self.0, TypedExpressionKind::Boolean(value)),
+            ExpressionKind::Char(value) => (self.0, TypedExpressionKind::Char(value)),
+            ExpressionKind::Identifier(inner) => {
+                inner.check_span(self.0, env, function_table, interner, errors)
             }
+            ExpressionKind::Unary(inner) => inner.check_span(
+                self.0,
+                env,
+                function_table,
+                interner,
+                current_function,
+                errors,
+            ),
+            ExpressionKind::Binary(inner) => inner.check_span(
+                self.0,
+                env,
+                function_table,
+                interner,
+                current_function,
+                errors,
+            ),
+            ExpressionKind::If(inner) => inner.check_span(
+                self.0,
+                env,
+                function_table,
+                interner,
+                current_function,
+                errors,
+            ),
+            ExpressionKind::Let(inner) => inner.check_span(
+                self.0,
+                env,
+                function_table,
+                interner,
+                current_function,
+                errors,
+            ),
             ExpressionKind::Block(expressions) => {
                 env.new_scope();
-                match expressions.check(env, function_table, current_function) {
-                    Ok(typed_expressions) => {
-                        env.remove_top_scope();
-                        Ok((self.0, TypedExpressionKind::Block(typed_expressions)))
-                    }
-                    Err(errors) => Err(errors.into_iter().next().unwrap()),
-                }
+                let typed_expressions =
+                    expressions.check(env, function_table, interner, current_function, errors);
+                env.remove_top_scope();
+                (self.0, TypedExpressionKind::Block(typed_expressions))
             }
-            ExpressionKind::Application(inner) => {
-                inner.check_span(self.0, env, function_table, current_function)
-            }
-            ExpressionKind::While(inner) => {
-                inner.check_span(self.0, env, function_table, current_function)
-            }
-            ExpressionKind::Return(None) => Ok((self.0, TypedExpressionKind::Return(None))),
+            ExpressionKind::Application(inner) => inner.check_span(
+                self.0,
+                env,
+                function_table,
+                interner,
+                current_function,
+                errors,
+            ),
+            ExpressionKind::While(inner) => inner.check_span(
+                self.0,
+                env,
+                function_table,
+                interner,
+                current_function,
+                errors,
+            ),
+            ExpressionKind::Return(None) => (self.0, TypedExpressionKind::Return(None)),
             ExpressionKind::Return(Some(expression)) => {
-                let typed_expression = expression.check(env, function_table, current_function)?;
+                let typed_expression =
+                    expression.check(env, function_table, interner, current_function, errors);
                 let expression_type = get_type(&typed_expression.1);
                 let function_return_type = env.get(current_function).unwrap();
-                if function_return_type != expression_type {
-                    Err(Error::TypeMismatch {
+                if expression_type.unify(function_return_type).is_none() {
+                    errors.push(Error::TypeMismatch {
                         span: typed_expression.0,
                         expected_type: function_return_type,
                         actual_type: expression_type,
-                    })
-                } else {
-                    Ok((
-                        self.0,
-                        TypedExpressionKind::Return(Some(Box::new(typed_expression))),
-                    ))
+                    });
                 }
+
+                (
+                    self.0,
+                    TypedExpressionKind::Return(Some(Box::new(typed_expression))),
+                )
             }
+            ExpressionKind::Panic(message) => (self.0, TypedExpressionKind::Panic(message)),
+            ExpressionKind::Assert(condition) => {
+                let typed_condition =
+                    condition.check(env, function_table, interner, current_function, errors);
+                check_condition(&typed_condition, errors);
+                (
+                    self.0,
+                    TypedExpressionKind::Assert(Box::new(typed_condition)),
+                )
+            }
+            ExpressionKind::AssertEq(inner) => inner.check_span(
+                self.0,
+                env,
+                function_table,
+                interner,
+                current_function,
+                errors,
+            ),
+            ExpressionKind::Error => (self.0, TypedExpressionKind::Error),
         }
     }
 }
 
-impl<'a> TypeCheckSpan<'a> for Identifier {
-    type Output = TypedExpression<'a>;
-    type Error = Error<'a>;
+impl TypeCheckSpan for Identifier {
+    type Output = TypedExpression;
 
     fn check_span(
         self,
-        span: Span<'a>,
+        span: Span,
         env: &mut Environment<Type>,
-        _: &mut FunctionTable,
-    ) -> Result<Self::Output, Self::Error> {
-        match env.get(self.0) {
-            Some(ty) => Ok((
+        function_table: &mut FunctionTable,
+        interner: &Interner<String>,
+        errors: &mut Vec<Error>,
+    ) -> Self::Output {
+        let ty = env.get(self.0).unwrap_or_else(|| {
+            errors.push(Error::UndefinedVariable {
                 span,
-                TypedExpressionKind::Identifier(TypedIdentifier { id: self.0, ty }),
-            )),
-            None => Err(Error::UndefinedVariable(span)),
-        }
+                suggestion: suggest_variable(self.0, env, function_table, interner),
+            });
+            Type::Error
+        });
+
+        (
+            span,
+            TypedExpressionKind::Identifier(TypedIdentifier { id: self.0, ty }),
+        )
     }
 }
 
-impl<'a> TypeCheckSpanFunction<'a> for Unary<'a> {
-    type Output = TypedExpression<'a>;
-    type Error = Error<'a>;
+impl TypeCheckSpanFunction for Unary {
+    type Output = TypedExpression;
 
     fn check_span(
         self,
-        span: Span<'a>,
+        span: Span,
         env: &mut Environment<Type>,
         function_table: &mut FunctionTable,
+        interner: &Interner<String>,
         current_function: usize,
-    ) -> Result<Self::Output, Self::Error> {
-        let typed_expression = self
-            .expression
-            .check(env, function_table, current_function)?;
+        errors: &mut Vec<Error>,
+    ) -> Self::Output {
+        let typed_expression =
+            self.expression
+                .check(env, function_table, interner, current_function, errors);
         let expression_type = get_type(&typed_expression.1);
         let operation_ty = match (self.operation, expression_type) {
+            (_, Type::Error) => Some(Type::Error),
             (UnaryOperation::Plus, Type::Int) => Some(Type::Int),
             (UnaryOperation::Plus, Type::Float) => Some(Type::Float),
             (UnaryOperation::Minus, Type::Int) => Some(Type::Int),
@@ -355,42 +469,49 @@ impl<'a> TypeCheckSpanFunction<'a> for Unary<'a> {
             _ => None,
         };
 
-        if let Some(operation_ty) = operation_ty {
-            Ok((
-                span,
-                TypedExpressionKind::Unary(TypedUnary {
-                    operation: self.operation,
-                    expression: Box::new(typed_expression),
-                    ty: operation_ty,
-                }),
-            ))
-        } else {
-            let error = Error::UnsupportedOperation {
+        let operation_ty = operation_ty.unwrap_or_else(|| {
+            errors.push(Error::UnsupportedOperation {
                 operation_span: span,
                 operands: vec![(typed_expression.0, expression_type)],
-            };
+            });
 
-            Err(error)
-        }
+            Type::Error
+        });
+
+        (
+            span,
+            TypedExpressionKind::Unary(TypedUnary {
+                operation: self.operation,
+                expression: Box::new(typed_expression),
+                ty: operation_ty,
+            }),
+        )
     }
 }
 
-impl<'a> TypeCheckSpanFunction<'a> for Binary<'a> {
-    type Output = TypedExpression<'a>;
-    type Error = Error<'a>;
+impl TypeCheckSpanFunction for Binary {
+    type Output = TypedExpression;
 
     fn check_span(
         self,
-        span: Span<'a>,
+        span: Span,
         env: &mut Environment<Type>,
         function_table: &mut FunctionTable,
+        interner: &Interner<String>,
         current_function: usize,
-    ) -> Result<Self::Output, Self::Error> {
-        let typed_left = self.left.check(env, function_table, current_function)?;
-        let typed_right = self.right.check(env, function_table, current_function)?;
+        errors: &mut Vec<Error>,
+    ) -> Self::Output {
+        let typed_left = self
+            .left
+            .check(env, function_table, interner, current_function, errors);
+        let typed_right = self
+            .right
+            .check(env, function_table, interner, current_function, errors);
         let left_type = get_type(&typed_left.1);
         let right_type = get_type(&typed_right.1);
         let result_type = match (self.operation, left_type, right_type) {
+            (_, Type::Error, _) | (_, _, Type::Error) => Some(Type::Error),
+
             (BinaryOperation::Plus, Type::Int, Type::Int) => Some(Type::Int),
             (BinaryOperation::Plus, Type::Float, Type::Float) => Some(Type::Float),
             (BinaryOperation::Plus, Type::Char, Type::Char) => Some(Type::Char),
@@ -422,78 +543,84 @@ impl<'a> TypeCheckSpanFunction<'a> for Binary<'a> {
             _ => None,
         };
 
-        if let Some(result_type) = result_type {
-            Ok((
-                span,
-                TypedExpressionKind::Binary(TypedBinary {
-                    operation: self.operation,
-                    left: Box::new(typed_left),
-                    right: Box::new(typed_right),
-                    ty: result_type,
-                }),
-            ))
-        } else {
-            let error = Error::UnsupportedOperation {
+        let result_type = result_type.unwrap_or_else(|| {
+            errors.push(Error::UnsupportedOperation {
                 operation_span: span,
                 operands: vec![(typed_left.0, left_type), (typed_right.0, right_type)],
-            };
+            });
 
-            Err(error)
-        }
+            Type::Error
+        });
+
+        (
+            span,
+            TypedExpressionKind::Binary(TypedBinary {
+                operation: self.operation,
+                operation_span: self.operation_span,
+                left: Box::new(typed_left),
+                right: Box::new(typed_right),
+                ty: result_type,
+            }),
+        )
     }
 }
 
-impl<'a> TypeCheckSpanFunction<'a> for If<'a> {
-    type Output = TypedExpression<'a>;
-    type Error = Error<'a>;
+impl TypeCheckSpanFunction for If {
+    type Output = TypedExpression;
 
     fn check_span(
         self,
-        span: Span<'a>,
+        span: Span,
         env: &mut Environment<Type>,
         function_table: &mut FunctionTable,
+        interner: &Interner<String>,
         current_function: usize,
-    ) -> Result<Self::Output, Self::Error> {
-        let typed_condition = self
-            .condition
-            .check(env, function_table, current_function)?;
-        let condition_type = get_type(&typed_condition.1);
-        if condition_type != Type::Boolean {
-            return Err(Error::TypeMismatch {
-                span: typed_condition.0,
-                expected_type: Type::Boolean,
-                actual_type: condition_type,
-            });
-        }
-
-        let typed_then = self
-            .then_branch
-            .check(env, function_table, current_function)?;
+        errors: &mut Vec<Error>,
+    ) -> Self::Output {
+        let typed_condition =
+            self.condition
+                .check(env, function_table, interner, current_function, errors);
+        check_condition(&typed_condition, errors);
+
+        let typed_then =
+            self.then_branch
+                .check(env, function_table, interner, current_function, errors);
         let then_type = get_type(&typed_then.1);
         if let Some(else_branch) = self.else_branch {
-            let typed_else = else_branch.check(env, function_table, current_function)?;
+            let typed_else =
+                else_branch.check(env, function_table, interner, current_function, errors);
             let else_type = get_type(&typed_else.1);
 
-            if then_type == else_type {
-                Ok((
-                    span,
-                    TypedExpressionKind::If(TypedIf {
-                        condition: Box::new(typed_condition),
-                        then_branch: Box::new(typed_then),
-                        else_branch: Some(Box::new(typed_else)),
-                        ty: then_type,
-                    }),
-                ))
+            // A branch that never completes, such as a `panic`, takes
+            // on the type of the other branch.
+            let ty = if then_type == Type::Never {
+                else_type
+            } else if else_type == Type::Never {
+                then_type
             } else {
-                Err(Error::ConflictingType {
-                    first_span: typed_then.0,
-                    first_type: then_type,
-                    second_span: typed_else.0,
-                    second_type: else_type,
+                then_type.unify(else_type).unwrap_or_else(|| {
+                    errors.push(Error::ConflictingType {
+                        first_span: typed_then.0,
+                        first_type: then_type,
+                        second_span: typed_else.0,
+                        second_type: else_type,
+                    });
+
+                    Type::Error
                 })
-            }
+            };
+
+            (
+                span,
+                TypedExpressionKind::If(TypedIf {
+                    condition: Box::new(typed_condition),
+                    then_branch: Box::new(typed_then),
+                    else_branch: Some(Box::new(typed_else)),
+                    ty,
+                }),
+            )
         } else {
-            Ok((
+            (
                 span,
                 TypedExpressionKind::If(TypedIf {
                     condition: Box::new(typed_condition),
@@ -501,57 +628,69 @@ impl<'a> TypeCheckSpanFunction<'a> for If<'a> {
                     else_branch: None,
                     ty: Type::Void,
                 }),
-            ))
+            )
         }
     }
 }
 
-impl<'a> TypeCheckSpanFunction<'a> for Let<'a> {
-    type Output = TypedExpression<'a>;
-    type Error = Error<'a>;
+impl TypeCheckSpanFunction for Let {
+    type Output = TypedExpression;
 
     fn check_span(
         self,
-        span: Span<'a>,
+        span: Span,
         env: &mut Environment<Type>,
         function_table: &mut FunctionTable,
+        interner: &Interner<String>,
         current_function: usize,
-    ) -> Result<Self::Output, Self::Error> {
-        let typed_expression = self
-            .expression
-            .check(env, function_table, current_function)?;
+        errors: &mut Vec<Error>,
+    ) -> Self::Output {
+        let typed_expression =
+            self.expression
+                .check(env, function_table, interner, current_function, errors);
         let expression_type = get_type(&typed_expression.1);
-        if let Some(given_type) = self.given_type {
-            if expression_type != given_type {
-                return Err(Error::ConflictingType {
-                    first_span: self.name.0,
+        let name_span = self.name.0;
+        // When the types conflict, the variable keeps the type it was
+        // declared with so that its later uses are checked against it.
+        let variable_type = if let Some(given_type) = self.given_type {
+            if expression_type.unify(given_type).is_none() {
+                errors.push(Error::ConflictingType {
+                    first_span: name_span,
                     first_type: given_type,
                     second_span: typed_expression.0,
                     second_type: expression_type,
                 });
             }
+
+            given_type
         } else if let Some(previously_defined_type) = env.get(self.name.1 .0) {
-            if expression_type != previously_defined_type {
-                return Err(Error::ConflictingPreviousType {
-                    name_span: self.name.0,
-                    previous_type: previously_defined_type,
-                    second_span: typed_expression.0,
-                    second_type: expression_type,
-                });
-            }
-        }
+            expression_type
+                .unify(previously_defined_type)
+                .unwrap_or_else(|| {
+                    errors.push(Error::ConflictingPreviousType {
+                        name_span,
+                        previous_type: previously_defined_type,
+                        second_span: typed_expression.0,
+                        second_type: expression_type,
+                    });
+
+                    previously_defined_type
+                })
+        } else {
+            expression_type
+        };
 
         let (identifier_span, Identifier(identifier_id)) = self.name;
         let typed_name = (
             identifier_span,
             TypedIdentifier {
                 id: identifier_id,
-                ty: expression_type,
+                ty: variable_type,
             },
         );
 
-        env.define(identifier_id, expression_type);
-        Ok((
+        env.define(identifier_id, variable_type);
+        (
             span,
             TypedExpressionKind::Let(TypedLet {
                 name: typed_name,
@@ -559,93 +698,164 @@ impl<'a> TypeCheckSpanFunction<'a> for Let<'a> {
                 expression: Box::new(typed_expression),
                 ty: expression_type,
             }),
-        ))
+        )
     }
 }
 
-impl<'a> TypeCheckSpanFunction<'a> for Application<'a> {
-    type Output = TypedExpression<'a>;
-    type Error = Error<'a>;
+impl TypeCheckSpanFunction for Application {
+    type Output = TypedExpression;
 
     fn check_span(
         self,
-        span: Span<'a>,
+        span: Span,
         env: &mut Environment<Type>,
         function_table: &mut FunctionTable,
+        interner: &Interner<String>,
         current_function: usize,
-    ) -> Result<Self::Output, Self::Error> {
-        let mut parameters = Vec::new();
-        for parameter in self.parameters {
-            let typed_value = parameter.check(env, function_table, current_function)?;
-            parameters.push(typed_value);
-        }
+        errors: &mut Vec<Error>,
+    ) -> Self::Output {
+        let parameters =
+            self.parameters
+                .check(env, function_table, interner, current_function, errors);
 
         let (function_span, Identifier(function_name)) = self.function_name;
-        let defined_types = function_table.get_function_definition(function_name, function_span)?;
-        if parameters.len() != defined_types.len() {
-            return Err(Error::ParameterMismatch {
-                span,
-                expected_parameter_count: defined_types.len(),
-                actual_parameter_count: parameters.len(),
-            });
-        }
+        let return_type = match function_table.get_function_definition(function_name, function_span)
+        {
+            Ok(defined_types) => {
+                if parameters.len() != defined_types.len() {
+                    errors.push(Error::ParameterMismatch {
+                        span,
+                        expected_parameter_count: defined_types.len(),
+                        actual_parameter_count: parameters.len(),
+                    });
+                }
 
-        for (&defined_parameter_type, actual_parameter) in defined_types.iter().zip(&parameters) {
-            let actual_parameter_type = get_type(&actual_parameter.1);
-            if defined_parameter_type != actual_parameter_type {
-                return Err(Error::TypeMismatch {
-                    span: actual_parameter.0,
-                    expected_type: defined_parameter_type,
-                    actual_type: actual_parameter_type,
+                for (&defined_parameter_type, actual_parameter) in
+                    defined_types.iter().zip(&parameters)
+                {
+                    let actual_parameter_type = get_type(&actual_parameter.1);
+                    if actual_parameter_type
+                        .unify(defined_parameter_type)
+                        .is_none()
+                    {
+                        errors.push(Error::TypeMismatch {
+                            span: actual_parameter.0,
+                            expected_type: defined_parameter_type,
+                            actual_type: actual_parameter_type,
+                        });
+                    }
+                }
+
+                env.get(function_name).unwrap()
+            }
+            Err(_) => {
+                errors.push(Error::UnknownFunction {
+                    span: function_span,
+                    suggestion: suggest_function(function_name, env, function_table, interner),
                 });
+                Type::Error
             }
-        }
+        };
 
-        let return_type = env.get(function_name).unwrap();
-        Ok((
+        (
             span,
             TypedExpressionKind::Application(TypedApplication {
                 function_name: (function_span, function_name),
                 parameters,
                 ty: return_type,
             }),
-        ))
+        )
     }
 }
 
-impl<'a> TypeCheckSpanFunction<'a> for While<'a> {
-    type Output = TypedExpression<'a>;
-    type Error = Error<'a>;
+impl TypeCheckSpanFunction for While {
+    type Output = TypedExpression;
 
     fn check_span(
         self,
-        span: Span<'a>,
+        span: Span,
         env: &mut Environment<Type>,
         function_table: &mut FunctionTable,
+        interner: &Interner<String>,
         current_function: usize,
-    ) -> Result<Self::Output, Self::Error> {
-        let typed_condition = self
-            .condition
-            .check(env, function_table, current_function)?;
-        let condition_type = get_type(&typed_condition.1);
-        if condition_type != Type::Boolean {
-            return Err(Error::TypeMismatch {
-                span: typed_condition.0,
-                expected_type: Type::Boolean,
-                actual_type: condition_type,
-            });
-        }
-
-        let typed_expression = self
-            .expression
-            .check(env, function_table, current_function)?;
-        Ok((
+        errors: &mut Vec<Error>,
+    ) -> Self::Output {
+        let typed_condition =
+            self.condition
+                .check(env, function_table, interner, current_function, errors);
+        check_condition(&typed_condition, errors);
+
+        let typed_expression =
+            self.expression
+                .check(env, function_table, interner, current_function, errors);
+        (
             span,
             TypedExpressionKind::While(TypedWhile {
                 condition: Box::new(typed_condition),
                 expression: Box::new(typed_expression),
             }),
-        ))
+        )
+    }
+}
+
+impl TypeCheckSpanFunction for AssertEq {
+    type Output = TypedExpression;
+
+    fn check_span(
+        self,
+        span: Span,
+        env: &mut Environment<Type>,
+        function_table: &mut FunctionTable,
+        interner: &Interner<String>,
+        current_function: usize,
+        errors: &mut Vec<Error>,
+    ) -> Self::Output {
+        let typed_left = self
+            .left
+            .check(env, function_table, interner, current_function, errors);
+        let typed_right = self
+            .right
+            .check(env, function_table, interner, current_function, errors);
+        let left_type = get_type(&typed_left.1);
+        let right_type = get_type(&typed_right.1);
+        match left_type.unify(right_type) {
+            Some(Type::Int) | Some(Type::Float) | Some(Type::Char) | Some(Type::Boolean)
+            | Some(Type::Error) => {}
+            Some(_) => errors.push(Error::UnsupportedOperation {
+                operation_span: span,
+                operands: vec![(typed_left.0, left_type), (typed_right.0, right_type)],
+            }),
+            None => errors.push(Error::ConflictingType {
+                first_span: typed_left.0,
+                first_type: left_type,
+                second_span: typed_right.0,
+                second_type: right_type,
+            }),
+        }
+
+        (
+            span,
+            TypedExpressionKind::AssertEq(TypedAssertEq {
+                left: Box::new(typed_left),
+                right: Box::new(typed_right),
+            }),
+        )
+    }
+}
+
+/// Reports an error if the given condition is not a `Boolean`.
+///
+/// # Arguments
+/// * `typed_condition` - The condition that was type checked.
+/// * `errors` - The errors found so far.
+fn check_condition(typed_condition: &TypedExpression, errors: &mut Vec<Error>) {
+    let condition_type = get_type(&typed_condition.1);
+    if condition_type.unify(Type::Boolean).is_none() {
+        errors.push(Error::TypeMismatch {
+            span: typed_condition.0,
+            expected_type: Type::Boolean,
+            actual_type: condition_type,
+        });
     }
 }
 
@@ -669,5 +879,9 @@ fn get_type(typed_expression_kind: &TypedExpressionKind) -> Type {
         TypedExpressionKind::While(_) => Type::Void,
         TypedExpressionKind::Return(None) => Type::Void,
         TypedExpressionKind::Return(Some(ref inner)) => get_type(&inner.1),
+        TypedExpressionKind::Panic(_) => Type::Never,
+        TypedExpressionKind::Assert(_) | TypedExpressionKind::AssertEq(_) => Type::Void,
+        // The error was already reported while parsing.
+        TypedExpressionKind::Error => Type::Error,
     }
 }
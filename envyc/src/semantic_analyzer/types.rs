@@ -12,6 +12,10 @@ pub enum Type {
     Boolean,
     Char,
     Never,
+    /// The type of an expression that failed to type check. It unifies with
+    /// every other type so that errors caused by an already reported error
+    /// are not reported again.
+    Error,
 }
 
 impl PartialEq for Type {
@@ -24,10 +28,28 @@ impl PartialEq for Type {
                 | (Type::Float, Type::Float)
                 | (Type::Boolean, Type::Boolean)
                 | (Type::Char, Type::Char)
+                | (Type::Error, Type::Error)
         )
     }
 }
 
+impl Type {
+    /// Unifies this type with another type. Two types unify if they are equal,
+    /// or if either of them is `Type::Error`. The resulting type prefers the type
+    /// that is not `Type::Error`. Returns `None` if the types do not unify.
+    ///
+    /// # Arguments
+    /// * `other` - The type to unify with.
+    pub fn unify(self, other: Type) -> Option<Type> {
+        match (self, other) {
+            (Type::Error, other) => Some(other),
+            (ty, Type::Error) => Some(ty),
+            (ty, other) if ty == other => Some(ty),
+            _ => None,
+        }
+    }
+}
+
 impl Display for Type {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -37,6 +59,7 @@ impl Display for Type {
             Type::Boolean => write!(f, "Boolean"),
             Type::Char => write!(f, "Char"),
             Type::Never => write!(f, "Never"),
+            Type::Error => write!(f, "{{error}}"),
         }
     }
 }
@@ -0,0 +1,683 @@
+use std::{collections::HashMap, io::Write, panic, thread};
+
+use crate::{
+    check_lints,
+    codegen::{c_generator::generate_c, PANIC_EXIT_CODE},
+    environment::Environment,
+    error::{
+        reporter::{ErrorFormat, ErrorReporter},
+        Error, FileId, SourceMap,
+    },
+    filter_tokens,
+    function_table::FunctionTable,
+    interner::Interner,
+    interpreter::{Exit, Interpreter, STACK_SIZE},
+    lex,
+    lexer::token::Token,
+    linker::link_library,
+    lint::{LintConfig, LintDiagnostic, Severity},
+    optimization::{OptimizationLevel, Pass},
+    parse,
+    parser::{ast::Program, typed_ast::TypedProgram},
+    type_check, EmitKind,
+};
+#[cfg(feature = "llvm")]
+use crate::{
+    compile,
+    printer::{print_program, print_tokens, print_typed_program},
+    run_jit, Config,
+};
+
+/// The options that change how every file in a `Session` is compiled.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The kinds of output emitted by `Session::emit_all`.
+    pub emit: Vec<EmitKind>,
+    /// How much the generated code is optimized.
+    pub optimization_level: OptimizationLevel,
+    /// The passes to run instead of the pipeline of the optimization level.
+    pub passes: Option<Vec<Pass>>,
+    /// Whether the IR is collected after each optimization pass, so that
+    /// it can be printed with `take_ir_dumps`.
+    pub print_after_all: bool,
+    /// Whether the partially built module is collected when LLVM fails to
+    /// verify a function, so that it can be written with `take_ice_dumps`.
+    pub emit_ir_on_ice: bool,
+    /// The triple of the target to compile for, or `None` to compile for the host.
+    pub target_triple: Option<String>,
+    /// The CPU of the target, or `None` for a generic CPU.
+    /// The CPU `native` refers to the CPU of the host.
+    pub target_cpu: Option<String>,
+    /// The features of the target to enable or disable, such as `+avx2`.
+    pub target_features: Vec<String>,
+    /// Whether the generated code should be position independent.
+    pub position_independent: bool,
+    /// Whether the files are built into a library, so that none of their
+    /// functions are reported as unused.
+    pub library: bool,
+    /// Whether integer arithmetic traps on overflow and division by zero.
+    pub checked_arithmetic: bool,
+    /// The number of expressions that `Session::interpret` evaluates before
+    /// the program panics, or `None` if the program can run forever.
+    pub step_limit: Option<usize>,
+    /// Whether DWARF debug information is generated for debuggers.
+    pub debug_info: bool,
+    /// The level of each lint.
+    pub lints: LintConfig,
+    /// The format that the diagnostics are reported in.
+    pub error_format: ErrorFormat,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            emit: vec![EmitKind::Object],
+            optimization_level: OptimizationLevel::O2,
+            passes: None,
+            print_after_all: false,
+            emit_ir_on_ice: false,
+            target_triple: None,
+            target_cpu: None,
+            target_features: vec![],
+            position_independent: false,
+            library: false,
+            checked_arithmetic: true,
+            step_limit: None,
+            debug_info: false,
+            lints: LintConfig::default(),
+            error_format: ErrorFormat::Human,
+        }
+    }
+}
+
+impl Options {
+    /// Creates the configuration of the output of the given kinds.
+    ///
+    /// # Arguments
+    /// * `emit` - The kinds of output.
+    #[cfg(feature = "llvm")]
+    pub(crate) fn config<'a>(&'a self, emit: &'a [EmitKind]) -> Config<'a> {
+        Config {
+            emit,
+            optimization_level: self.optimization_level,
+            passes: self.passes.as_deref(),
+            target_triple: self.target_triple.as_deref(),
+            target_cpu: self.target_cpu.as_deref(),
+            target_features: &self.target_features,
+            position_independent: self.position_independent,
+            checked_arithmetic: self.checked_arithmetic,
+            debug_info: self.debug_info,
+        }
+    }
+}
+
+/// Enum that details the diagnostics collected by a `Session`.
+#[derive(Debug)]
+pub enum SessionDiagnostic {
+    Error(Error),
+    Lint(LintDiagnostic),
+}
+
+/// The results of the queries that have already been run on a file.
+#[derive(Default)]
+struct FileQueries {
+    tokens: Option<Vec<Token>>,
+    ast: Option<Program>,
+    typed_ast: Option<TypedProgram>,
+    /// The name of the library that the file is part of, if it is not part
+    /// of the program itself.
+    library: Option<String>,
+    /// Whether one of the stages failed. The failed stage already reported
+    /// its errors, so every later query returns `None` without running again.
+    failed: bool,
+}
+
+/// Struct that drives the compilation of files from their source to their
+/// output. It owns everything that the stages of the compiler share: the
+/// files, the interner, the options and the diagnostics that have not been
+/// reported yet. Every front end compiles through a `Session` by asking it for
+/// the result of a stage, such as the tokens or the type checked program of a
+/// file, and the `Session` runs the earlier stages as they are needed.
+///
+/// A query returns `None` when its stage or an earlier one failed. The errors
+/// are collected by the `Session` and can be printed with `report`.
+pub struct Session {
+    source_map: SourceMap,
+    interner: Interner<String>,
+    options: Options,
+    files: HashMap<FileId, FileQueries>,
+    diagnostics: Vec<SessionDiagnostic>,
+    ir_dumps: Vec<String>,
+    ice_dumps: Vec<String>,
+}
+
+impl Session {
+    /// Creates a `Session` without any files.
+    ///
+    /// # Arguments
+    /// * `options` - The options the files are compiled with.
+    pub fn new(options: Options) -> Self {
+        Self {
+            source_map: SourceMap::default(),
+            interner: Interner::default(),
+            options,
+            files: HashMap::new(),
+            diagnostics: vec![],
+            ir_dumps: vec![],
+            ice_dumps: vec![],
+        }
+    }
+
+    /// Adds a file to the `Session` and returns the id of the file.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the file.
+    /// * `source` - The source of the file.
+    pub fn add_file(&mut self, name: impl Into<String>, source: impl Into<String>) -> FileId {
+        let file_id = self.source_map.add(name, source);
+        self.files.insert(file_id, FileQueries::default());
+        file_id
+    }
+
+    /// Adds a file of a library, such as the standard library, to the `Session`
+    /// and returns the id of the file. When the file is linked with the files
+    /// of a program by `link`, the functions that the program defines take
+    /// priority over the functions of the library with the same names.
+    ///
+    /// # Arguments
+    /// * `library` - The name of the library.
+    /// * `name` - The name of the file.
+    /// * `source` - The source of the file.
+    pub fn add_library_file(
+        &mut self,
+        library: impl Into<String>,
+        name: impl Into<String>,
+        source: impl Into<String>,
+    ) -> FileId {
+        let file_id = self.add_file(name, source);
+        if let Some(queries) = self.files.get_mut(&file_id) {
+            queries.library = Some(library.into());
+        }
+
+        file_id
+    }
+
+    /// Gets the `SourceMap` of the files in the `Session`.
+    pub fn source_map(&self) -> &SourceMap {
+        &self.source_map
+    }
+
+    /// Gets the `Interner` shared by the files in the `Session`.
+    pub fn interner(&self) -> &Interner<String> {
+        &self.interner
+    }
+
+    /// Gets the options the files are compiled with.
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// Gets the tokens of the file, lexing it if needed.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the file.
+    pub fn tokens(&mut self, file_id: FileId) -> Option<&[Token]> {
+        let queries = self.files.get_mut(&file_id)?;
+        if queries.failed {
+            return None;
+        }
+
+        if queries.tokens.is_none() {
+            let bytes = self.source_map.source(file_id).as_bytes();
+            match lex(file_id, bytes, &mut self.interner) {
+                Ok(tokens) => queries.tokens = Some(tokens),
+                Err(errors) => {
+                    queries.failed = true;
+                    self.diagnostics
+                        .extend(errors.into_iter().map(SessionDiagnostic::Error));
+                    return None;
+                }
+            }
+        }
+
+        queries.tokens.as_deref()
+    }
+
+    /// Gets the program parsed from the file, parsing it if needed.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the file.
+    pub fn ast(&mut self, file_id: FileId) -> Option<&Program> {
+        let is_parsed = self.files.get(&file_id)?.ast.is_some();
+        if !is_parsed {
+            let tokens = self.tokens(file_id)?.to_vec();
+            let queries = self.files.get_mut(&file_id)?;
+            match parse(filter_tokens(tokens)) {
+                Ok(program) => queries.ast = Some(program),
+                Err(errors) => {
+                    queries.failed = true;
+                    self.diagnostics
+                        .extend(errors.into_iter().map(SessionDiagnostic::Error));
+                    return None;
+                }
+            }
+        }
+
+        self.files.get(&file_id)?.ast.as_ref()
+    }
+
+    /// Gets the type checked program of the file, type checking it if needed.
+    /// Type checking consumes the parsed program, so a later call to `ast`
+    /// parses the file again.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the file.
+    pub fn typed_ast(&mut self, file_id: FileId) -> Option<&TypedProgram> {
+        let is_checked = self.files.get(&file_id)?.typed_ast.is_some();
+        if !is_checked {
+            self.ast(file_id)?;
+            let queries = self.files.get_mut(&file_id)?;
+            let program = queries.ast.take()?;
+            let mut type_env = Environment::default();
+            let mut function_table = FunctionTable::default();
+            match type_check(
+                program,
+                &mut type_env,
+                &mut function_table,
+                &mut self.interner,
+            ) {
+                Ok(typed_program) => queries.typed_ast = Some(typed_program),
+                Err(errors) => {
+                    queries.failed = true;
+                    self.diagnostics
+                        .extend(errors.into_iter().map(SessionDiagnostic::Error));
+                    return None;
+                }
+            }
+        }
+
+        self.files.get(&file_id)?.typed_ast.as_ref()
+    }
+
+    /// Gets the signatures of the functions defined in the file, such as
+    /// `add(Int, Int) :: Int`, type checking the file if needed.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the file.
+    pub fn signatures(&mut self, file_id: FileId) -> Option<Vec<String>> {
+        self.typed_ast(file_id)?;
+        let typed_program = self.files.get(&file_id)?.typed_ast.as_ref()?;
+        let signatures = typed_program
+            .functions
+            .iter()
+            .map(|function| function.prototype.signature(&self.interner))
+            .collect();
+
+        Some(signatures)
+    }
+
+    /// Checks the type checked program of the file for lints and returns
+    /// whether any warnings were found. Returns `None` if a lint was denied.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the file.
+    pub fn lint(&mut self, file_id: FileId) -> Option<bool> {
+        self.typed_ast(file_id)?;
+        let queries = self.files.get_mut(&file_id)?;
+        let typed_program = queries.typed_ast.as_ref()?;
+        let lint_diagnostics = check_lints(
+            typed_program,
+            &self.interner,
+            &self.options.lints,
+            self.options.library,
+        );
+        let has_diagnostics = !lint_diagnostics.is_empty();
+        let is_denied = lint_diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error);
+        self.diagnostics
+            .extend(lint_diagnostics.into_iter().map(SessionDiagnostic::Lint));
+        if is_denied {
+            queries.failed = true;
+            None
+        } else {
+            Some(has_diagnostics)
+        }
+    }
+
+    /// Returns the bytes of the requested output of the file, running the
+    /// stages needed to produce it. The tokens and the programs are printed
+    /// in a form meant for people to read. The name of the LLVM module is
+    /// the name of the file without its extension.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the file.
+    /// * `kind` - The kind of output to emit.
+    #[cfg(feature = "llvm")]
+    pub fn emit(&mut self, file_id: FileId, kind: EmitKind) -> Option<Vec<u8>> {
+        match kind {
+            EmitKind::Tokens => {
+                self.tokens(file_id)?;
+                let tokens = self.files.get(&file_id)?.tokens.as_ref()?;
+                return Some(print_tokens(tokens, &self.source_map).into_bytes());
+            }
+            EmitKind::Ast => {
+                self.ast(file_id)?;
+                let program = self.files.get(&file_id)?.ast.as_ref()?;
+                return Some(print_program(program, &self.interner, &self.source_map).into_bytes());
+            }
+            EmitKind::TypedAst => {
+                self.typed_ast(file_id)?;
+                let typed_program = self.files.get(&file_id)?.typed_ast.as_ref()?;
+                let output = print_typed_program(typed_program, &self.interner, &self.source_map);
+                return Some(output.into_bytes());
+            }
+            _ => {}
+        }
+
+        self.compile(file_id, &[kind])?.pop()
+    }
+
+    /// Compiles the file and returns the bytes of every kind of output
+    /// requested by the options, in the order they were requested. The
+    /// LLVM module of the file is only generated and optimized once.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the file.
+    #[cfg(feature = "llvm")]
+    pub fn emit_all(&mut self, file_id: FileId) -> Option<Vec<(EmitKind, Vec<u8>)>> {
+        let kinds = self.options.emit.clone();
+        let backend_kinds = kinds
+            .iter()
+            .copied()
+            .filter(|kind| !kind.is_front_end())
+            .collect::<Vec<_>>();
+        let mut backend_outputs = if backend_kinds.is_empty() {
+            vec![]
+        } else {
+            self.compile(file_id, &backend_kinds)?
+        }
+        .into_iter();
+
+        kinds
+            .into_iter()
+            .map(|kind| {
+                let bytes = if kind.is_front_end() {
+                    self.emit(file_id, kind)?
+                } else {
+                    backend_outputs.next()?
+                };
+
+                Some((kind, bytes))
+            })
+            .collect()
+    }
+
+    /// Compiles the file into an LLVM module once and returns the bytes of
+    /// each of the kinds of output, in the given order.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the file.
+    /// * `kinds` - The kinds of output to emit.
+    #[cfg(feature = "llvm")]
+    fn compile(&mut self, file_id: FileId, kinds: &[EmitKind]) -> Option<Vec<Vec<u8>>> {
+        self.typed_ast(file_id)?;
+        let queries = self.files.get_mut(&file_id)?;
+        let typed_program = queries.typed_ast.as_ref()?;
+        let config = self.options.config(kinds);
+        match compile(
+            typed_program,
+            file_id,
+            &mut self.interner,
+            &self.source_map,
+            &config,
+            if self.options.print_after_all {
+                Some(&mut self.ir_dumps)
+            } else {
+                None
+            },
+            if self.options.emit_ir_on_ice {
+                Some(&mut self.ice_dumps)
+            } else {
+                None
+            },
+        ) {
+            Ok(outputs) => Some(outputs),
+            Err(errors) => {
+                queries.failed = true;
+                self.diagnostics
+                    .extend(errors.into_iter().map(SessionDiagnostic::Error));
+                None
+            }
+        }
+    }
+
+    /// Runs the `main` function of the files with LLVM's JIT, type checking
+    /// them if needed, and returns its exit code. The files are linked
+    /// together in memory with `link`, so they can call each other's functions
+    /// through `extern` declarations. Returns `None` if any of the files failed.
+    ///
+    /// # Arguments
+    /// * `file_ids` - The ids of the files to run.
+    /// * `arguments` - The command-line arguments of the program, starting with its name.
+    #[cfg(feature = "llvm")]
+    pub fn run_jit(&mut self, file_ids: &[FileId], arguments: &[&str]) -> Option<i32> {
+        self.link(file_ids)?;
+        let files = &self.files;
+        let programs = file_ids
+            .iter()
+            .map(|file_id| Some((*file_id, files.get(file_id)?.typed_ast.as_ref()?)))
+            .collect::<Option<Vec<_>>>()?;
+        let config = self.options.config(&[EmitKind::Object]);
+        match run_jit(
+            &programs,
+            &mut self.interner,
+            &self.source_map,
+            &config,
+            arguments,
+        ) {
+            Ok(exit_code) => Some(exit_code),
+            Err(errors) => {
+                self.diagnostics
+                    .extend(errors.into_iter().map(SessionDiagnostic::Error));
+                None
+            }
+        }
+    }
+
+    /// Runs the `main` function of the files with the `Interpreter`, type
+    /// checking them if needed, and returns its exit code. The files are
+    /// linked together like they are by `run_jit`. When the program panics,
+    /// the panic is written to `error_output` and the exit code is
+    /// `PANIC_EXIT_CODE`. Returns `None` if any of the files failed.
+    ///
+    /// The program runs on its own thread with a stack of `STACK_SIZE` bytes,
+    /// so that deeply recursive programs can run until they use most of it.
+    ///
+    /// # Arguments
+    /// * `file_ids` - The ids of the files to run.
+    /// * `arguments` - The command-line arguments of the program, starting with its name.
+    /// * `output` - The writer that the standard output of the program is written to.
+    /// * `error_output` - The writer that a panic is written to.
+    pub fn interpret(
+        &mut self,
+        file_ids: &[FileId],
+        arguments: &[&str],
+        output: &mut (dyn Write + Send),
+        error_output: &mut dyn Write,
+    ) -> Option<i32> {
+        self.link(file_ids)?;
+        let programs = self.typed_programs(file_ids)?;
+        let interner = &self.interner;
+        let checked_arithmetic = self.options.checked_arithmetic;
+        let step_limit = self.options.step_limit;
+        let run = move || {
+            Interpreter::new(&programs, interner, checked_arithmetic, step_limit, output)?
+                .run_main(arguments)
+        };
+        let exit = thread::scope(|scope| {
+            thread::Builder::new()
+                .stack_size(STACK_SIZE)
+                .spawn_scoped(scope, run)
+                .expect("could not start the thread of the interpreter")
+                .join()
+                .unwrap_or_else(|payload| panic::resume_unwind(payload))
+        });
+
+        match exit {
+            Ok(Exit::Panic(panic)) => {
+                let _ = writeln!(error_output, "{}", panic.render(&self.source_map));
+                Some(PANIC_EXIT_CODE as i32)
+            }
+            Ok(exit) => Some(exit.code()),
+            Err(errors) => {
+                self.diagnostics
+                    .extend(errors.into_iter().map(SessionDiagnostic::Error));
+                None
+            }
+        }
+    }
+
+    /// Lowers the files to a single C99 source file, type checking them if
+    /// needed. The files are linked together like they are by `run_jit`.
+    /// Returns `None` if any of the files failed or if the programs can
+    /// not be lowered, such as when none of them define `main`.
+    ///
+    /// # Arguments
+    /// * `file_ids` - The ids of the files to lower.
+    pub fn generate_c(&mut self, file_ids: &[FileId]) -> Option<String> {
+        self.link(file_ids)?;
+        let programs = self.typed_programs(file_ids)?;
+        match generate_c(
+            &programs,
+            &self.interner,
+            &self.source_map,
+            self.options.checked_arithmetic,
+        ) {
+            Ok(source) => Some(source),
+            Err(errors) => {
+                self.diagnostics
+                    .extend(errors.into_iter().map(SessionDiagnostic::Error));
+                None
+            }
+        }
+    }
+
+    /// Type checks the files if needed and links the files of the libraries
+    /// among them with the other files, so that the programs can be run or
+    /// given to another backend. The functions of each library are renamed so
+    /// that they never clash with the functions of the other files, which
+    /// take priority over them. The linked programs can then be retrieved with
+    /// `typed_programs`. Returns `None` if any of the files failed.
+    ///
+    /// # Arguments
+    /// * `file_ids` - The ids of the files.
+    pub fn link(&mut self, file_ids: &[FileId]) -> Option<()> {
+        for file_id in file_ids {
+            self.typed_ast(*file_id)?;
+        }
+
+        let mut programs = vec![];
+        let mut libraries = HashMap::<&str, Vec<_>>::new();
+        for (file_id, queries) in self.files.iter_mut() {
+            if !file_ids.contains(file_id) {
+                continue;
+            }
+
+            let program = queries.typed_ast.as_mut()?;
+            match queries.library {
+                Some(ref library) => libraries.entry(library).or_default().push(program),
+                None => programs.push(program),
+            }
+        }
+
+        for (library, mut library_programs) in libraries {
+            link_library(
+                &mut programs,
+                &mut library_programs,
+                library,
+                &mut self.interner,
+            );
+        }
+
+        Some(())
+    }
+
+    /// Gets the type checked programs of the files, so that they can be given
+    /// to another backend. Returns `None` if any of the files has not been
+    /// type checked with `typed_ast` or failed.
+    ///
+    /// # Arguments
+    /// * `file_ids` - The ids of the files.
+    pub fn typed_programs(&self, file_ids: &[FileId]) -> Option<Vec<&TypedProgram>> {
+        file_ids
+            .iter()
+            .map(|file_id| self.files.get(file_id)?.typed_ast.as_ref())
+            .collect()
+    }
+
+    /// Adds errors found outside of the `Session`, such as by another backend,
+    /// so that they are reported along with the other diagnostics.
+    ///
+    /// # Arguments
+    /// * `errors` - The errors to add.
+    pub fn add_errors(&mut self, errors: Vec<Error>) {
+        self.diagnostics
+            .extend(errors.into_iter().map(SessionDiagnostic::Error));
+    }
+
+    /// Removes and returns the IR collected after each optimization pass
+    /// when `print_after_all` is enabled.
+    pub fn take_ir_dumps(&mut self) -> Vec<String> {
+        self.ir_dumps.drain(..).collect()
+    }
+
+    /// Removes and returns the IR of the modules that LLVM failed to verify
+    /// when `emit_ir_on_ice` is enabled.
+    pub fn take_ice_dumps(&mut self) -> Vec<String> {
+        self.ice_dumps.drain(..).collect()
+    }
+
+    /// Determines whether any of the collected diagnostics is an error.
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(|diagnostic| match diagnostic {
+            SessionDiagnostic::Error(_) => true,
+            SessionDiagnostic::Lint(lint_diagnostic) => lint_diagnostic.severity == Severity::Error,
+        })
+    }
+
+    /// Renders the diagnostics collected so far and removes them from the `Session`.
+    ///
+    /// # Arguments
+    /// * `color` - Whether the diagnostics should be colored.
+    pub fn take_diagnostics(&mut self, color: bool) -> Vec<String> {
+        let mut error_reporter = ErrorReporter::new(&self.source_map);
+        error_reporter.set_format(self.options.error_format);
+        self.diagnostics
+            .drain(..)
+            .map(|diagnostic| {
+                let bytes = match diagnostic {
+                    SessionDiagnostic::Error(error) => error_reporter.report(&error, color),
+                    SessionDiagnostic::Lint(lint_diagnostic) => {
+                        error_reporter.report_lint(&lint_diagnostic, color)
+                    }
+                };
+
+                String::from_utf8_lossy(&bytes).into_owned()
+            })
+            .collect()
+    }
+
+    /// Writes the diagnostics collected so far and removes them from the `Session`.
+    ///
+    /// # Arguments
+    /// * `color` - Whether the diagnostics should be colored.
+    /// * `output` - The writer that the diagnostics are written to.
+    pub fn report(&mut self, color: bool, output: &mut dyn Write) -> std::io::Result<()> {
+        for diagnostic in self.take_diagnostics(color) {
+            output.write_all(diagnostic.as_bytes())?;
+        }
+
+        Ok(())
+    }
+}